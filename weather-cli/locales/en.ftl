@@ -0,0 +1,279 @@
+# English message catalog (reference locale).
+# Every other locale falls back to these messages for ids it does not define.
+
+## Weather report
+
+label-provider = Provider
+label-location = Location
+label-observed-at = Observed at
+label-condition = Condition
+label-temperature = Temperature
+label-feels-like = Feels like
+label-humidity = Humidity
+label-wind-speed = Wind speed
+label-pressure = Pressure
+label-precipitation = Precipitation
+
+## Times
+
+time-just-now = just now
+time-minutes-ago = { $count ->
+    [one] a minute ago
+   *[other] { $count } minutes ago
+}
+time-in-minutes = { $count ->
+    [one] in a minute
+   *[other] in { $count } minutes
+}
+time-hours-ago = { $count ->
+    [one] an hour ago
+   *[other] { $count } hours ago
+}
+time-in-hours = { $count ->
+    [one] in an hour
+   *[other] in { $count } hours
+}
+time-yesterday = yesterday at { $time }
+time-today = today at { $time }
+time-tomorrow = tomorrow at { $time }
+time-days-ago = { $count } days ago
+time-in-days = in { $count } days
+
+## weather show
+
+stale-notice = STALE: showing cached data from { $time } ({ $age } old).
+oneline = { $location }: { $icon }{ $temperature } (feels { $feels }), { $humidity }%, { $wind }
+
+dry-run-header = Dry run: nothing was sent.
+dry-run-date = Date
+dry-run-date-current = current weather
+dry-run-date-forecast = forecast for { $time }
+dry-run-date-history = history for { $time }
+dry-run-query = Query parameters
+
+batch-error = failed: { $error }
+batch-failed = { $failed ->
+    [one] 1 of { $total } locations failed.
+   *[other] { $failed } of { $total } locations failed.
+}
+
+geocode-select = Several places match '{ $address }'. Which one did you mean?
+geocode-remember = Always use this place for '{ $address }'?
+
+show-prompt-location = Location:
+show-prompt-location-help = An address, e.g. Kyiv, or lat,lon coordinates.
+show-prompt-location-saved = An address, lat,lon coordinates or a saved location: { $names }
+show-prompt-location-required = Please enter a location.
+show-prompt-date = Date (optional):
+show-prompt-date-help = RFC3339, e.g. 2025-12-04T12:00:00Z; leave empty for the current weather.
+
+diff-header = Weather in { $location }
+diff-now = Now
+diff-change = Change
+
+forecast-header = Forecast for { $location }
+forecast-warming = Warming by about { $change } a day.
+forecast-cooling = Cooling by about { $change } a day.
+forecast-temperature-steady = Temperatures stay about the same.
+forecast-pressure-rising = Pressure rising: settled weather ahead.
+forecast-pressure-steady = Pressure steady.
+forecast-pressure-falling = Pressure falling: the weather may turn.
+forecast-pressure-storm = Pressure falling fast: a storm is possible.
+forecast-precipitation = Precipitation expected { $start }–{ $end } ({ $amount }).
+forecast-dry = No precipitation expected.
+
+climate-header = { $location }: { $month ->
+    [1] January
+    [2] February
+    [3] March
+    [4] April
+    [5] May
+    [6] June
+    [7] July
+    [8] August
+    [9] September
+    [10] October
+    [11] November
+   *[12] December
+} normals, { $first }–{ $last }
+climate-normal = Normal
+climate-next-day = Next 24 h
+climate-high = Average high
+climate-low = Average low
+climate-record-high = Record high
+climate-record-low = Record low
+climate-wet-days = Days with precipitation
+
+## weather record / stats
+
+record-header = Recording the weather at '{ $location }' every { $interval }. Press Ctrl+C to stop.
+record-stored = { $time }  { $temperature }, { $condition }
+stats-header = { $location }: { $count ->
+    [one] 1 observation
+   *[other] { $count } observations
+} from { $first } to { $last }.
+stats-min = Min
+stats-max = Max
+stats-avg = Average
+stats-empty =
+    No observations of '{ $location }' recorded in the last { $period }.
+    Hint: run `weather record "{ $location }"` or `weather show "{ $location }" --store sqlite`.
+
+## weather monitor
+
+monitor-header = Watching '{ $location }' for severe weather every { $interval }. Press Ctrl+C to stop.
+monitor-quiet = { $time }  no new alerts
+monitor-alert = { $time }  { $event }: { $headline }
+monitor-alert-until = { $time }  { $event }: { $headline } (until { $until })
+monitor-limit = { $value } (limit { $limit })
+monitor-wind = Strong wind
+monitor-heat = Heat
+monitor-cold = Cold
+monitor-precipitation = Heavy precipitation
+
+## weather dashboard
+
+dashboard-header = Updated at { $time }, refreshing every { $interval }. Press Ctrl+C to quit.
+
+## weather location
+
+location-add-done = Location '{ $name }' saved.
+location-list-header = Saved locations:
+location-list-empty = No saved locations. Add one with `weather location add <name> <location>`.
+location-remove-done = Location '{ $name }' removed.
+location-remove-unknown =
+    No location named '{ $name }' is saved.
+    Hint: run `weather location list` to see saved locations.
+
+## weather init
+
+init-welcome = Let's set up weather. Nothing is saved until the last step; press Esc to cancel.
+init-provider = Weather provider:
+init-key-keep = Leave empty to keep the configured key.
+init-key-required = Please enter an API key.
+init-key-checking = Checking the key with a test request...
+init-key-ok = The key works.
+init-key-rejected = The provider rejected this key. Check it and try again.
+init-key-unchecked = Could not check the key: { $error }
+init-key-use-anyway = Use this key anyway?
+init-units = Units:
+init-language = Language for messages and weather conditions:
+init-location = Home location (optional):
+init-location-help = An address or lat,lon coordinates, saved as the location 'home' and shown by `weather show` without a location.
+init-done = All set. Configuration saved to '{ $path }'.
+init-try-home = Try `weather show`.
+
+## weather configure
+
+configure-prompt = Enter API key for provider '{ $provider }':
+configure-placeholder = API key
+configure-help = You can get this from your provider's dashboard.
+configure-updated = Configuration updated.
+configure-current-default = Current default provider: { $provider }
+configure-key-exists = This API key is already configured; nothing changed.
+configure-key-count = Provider '{ $provider }' now has { $count } API keys, used in turn.
+
+## weather provider
+
+provider-list-header = Providers:
+provider-status-default = configured, default
+provider-status-configured = configured
+provider-status-not-configured = not configured
+provider-key-count = { $count } API keys
+provider-list-hint-configure = Use `weather configure <provider>` to configure a provider.
+provider-list-hint-use = Use `weather provider use <provider>` to switch the default provider.
+provider-use-not-configured =
+    Provider '{ $provider }' is not configured.
+    Hint: run `weather configure { $provider }` first to add an API key.
+provider-use-done = Default provider set to '{ $provider }'.
+
+## weather config
+
+config-set-done = Setting '{ $key }' updated.
+config-imported = Settings imported from '{ $path }'.
+config-replaced = Configuration replaced with '{ $path }'.
+passphrase-prompt = Passphrase for the encrypted API keys:
+encrypt-prompt = New passphrase for the API keys:
+encrypt-confirm = Repeat the passphrase:
+encrypt-mismatch = The passphrases do not match.
+encrypt-already = The API keys are already encrypted.
+encrypt-done =
+    API keys encrypted. Commands that need them ask for the passphrase.
+    Hint: `weather config unlock` remembers it for a while; WEATHER_PASSPHRASE works too.
+decrypt-already = The API keys are not encrypted.
+decrypt-done = API keys stored in plaintext again.
+unlock-done = Passphrase remembered for { $duration }. Run `weather config lock` to forget it sooner.
+lock-done = Passphrase forgotten.
+
+## weather cache
+
+cache-cleared = { $count ->
+    [one] Removed 1 cached response.
+   *[other] Removed { $count } cached responses.
+}
+
+## weather usage
+
+usage-header = Provider calls (days and months in UTC):
+usage-today = Today
+usage-this-month = This month
+usage-warning-daily =
+    warning: '{ $name }' has used { $used } of { $limit } calls allowed today.
+    Hint: raise the quota with `weather config set daily_quota.{ $provider } <calls>` if your plan allows more.
+usage-warning-monthly =
+    warning: '{ $name }' has used { $used } of { $limit } calls allowed this month.
+    Hint: raise the quota with `weather config set monthly_quota.{ $provider } <calls>` if your plan allows more.
+
+## weather history
+
+history-time = Time
+history-empty = No lookups recorded yet.
+history-cleared = { $count ->
+    [one] Removed 1 recorded lookup.
+   *[other] Removed { $count } recorded lookups.
+}
+history-disabled = Lookups are not being recorded. Run `weather config set history true` to record them again.
+
+## Warnings
+
+warning-tls-insecure =
+    warning: TLS certificate verification is DISABLED (tls_insecure_skip_verify = true).
+    Provider traffic, including your API key, can be intercepted. Prefer `ca_bundle` instead.
+
+warning-log-file = warning: cannot open log file '{ $path }' ({ $error }); logging to stderr instead.
+
+warning-remember-place = warning: cannot remember the chosen place: { $error }
+
+warning-history = warning: cannot record the lookup in history: { $error }
+
+warning-export-plaintext = warning: the export contains your API keys in plaintext; keep it private.
+
+warning-record-failed = warning: { $time }  lookup failed, retrying at the next interval: { $error }
+
+warning-forecast-days = warning: { $provider } forecasts at most { $days } days ahead; showing those.
+
+warning-monitor-thresholds-only = warning: this provider publishes no weather alerts; only the configured alert_* limits are checked.
+
+warning-notify-failed = warning: cannot notify via { $channel }: { $error }
+
+## Errors
+
+error-empty-passphrase = The passphrase must not be empty.
+error-date-parse = Failed to parse --date as RFC3339: { $error }
+error-locations-file = Failed to read locations file '{ $path }': { $error }
+error-show-no-location = a location is required: pass one, --here or --locations-file, or save a 'home' location
+error-statusbar-many = The status bar formats show a single location.
+error-init-terminal = `weather init` is interactive; run it in a terminal, or use `weather configure` and `weather config set`.
+error-import-file = Failed to read config file '{ $path }': { $error }
+error-interval = Invalid interval '{ $value }'. Expected a positive number with an optional unit, e.g. 30s, 15m, 1h or 1d.
+error-forecast-empty = The provider returned no forecast for the coming days.
+error-monitor-interval = The monitor interval must be at least 1m.
+error-monitor-nothing =
+    This provider publishes no weather alerts and no alert limits are configured, so there is nothing to monitor.
+    Hint: set one, e.g. `weather config set alert_wind_speed 15`, or switch to weatherapi.
+error-here-unknown =
+    Your location has not been detected yet.
+    Hint: run `weather show --here` once while online.
+error-prefix = error
+error-chain-header = Error chain:
+error-verbose-hint = (run with -v or --verbose to see the full error chain)
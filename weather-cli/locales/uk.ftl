@@ -0,0 +1,298 @@
+# Ukrainian message catalog.
+
+## Weather report
+
+label-provider = Провайдер
+label-location = Місце
+label-observed-at = Час спостереження
+label-condition = Стан
+label-temperature = Температура
+label-feels-like = Відчувається як
+label-humidity = Вологість
+label-wind-speed = Швидкість вітру
+label-pressure = Тиск
+label-precipitation = Опади
+
+## Times
+
+time-just-now = щойно
+time-minutes-ago = { $count ->
+    [one] { $count } хвилину тому
+    [few] { $count } хвилини тому
+   *[other] { $count } хвилин тому
+}
+time-in-minutes = через { $count ->
+    [one] { $count } хвилину
+    [few] { $count } хвилини
+   *[other] { $count } хвилин
+}
+time-hours-ago = { $count ->
+    [one] { $count } годину тому
+    [few] { $count } години тому
+   *[other] { $count } годин тому
+}
+time-in-hours = через { $count ->
+    [one] { $count } годину
+    [few] { $count } години
+   *[other] { $count } годин
+}
+time-yesterday = вчора о { $time }
+time-today = сьогодні о { $time }
+time-tomorrow = завтра о { $time }
+time-days-ago = { $count ->
+    [one] { $count } день тому
+    [few] { $count } дні тому
+   *[other] { $count } днів тому
+}
+time-in-days = через { $count ->
+    [one] { $count } день
+    [few] { $count } дні
+   *[other] { $count } днів
+}
+
+## weather show
+
+stale-notice = ЗАСТАРІЛІ ДАНІ: показано збережену відповідь від { $time } ({ $age } тому).
+oneline = { $location }: { $icon }{ $temperature } (відчувається { $feels }), { $humidity }%, { $wind }
+
+dry-run-header = Пробний запуск: нічого не надіслано.
+dry-run-date = Дата
+dry-run-date-current = поточна погода
+dry-run-date-forecast = прогноз на { $time }
+dry-run-date-history = архів за { $time }
+dry-run-query = Параметри запиту
+
+batch-error = помилка: { $error }
+batch-failed = Не вдалося отримати погоду для { $failed } з { $total } локацій.
+
+geocode-select = Назві '{ $address }' відповідає кілька місць. Яке саме?
+geocode-remember = Завжди використовувати це місце для '{ $address }'?
+
+show-prompt-location = Місце:
+show-prompt-location-help = Адреса, наприклад Київ, або координати lat,lon.
+show-prompt-location-saved = Адреса, координати lat,lon або збережене місце: { $names }
+show-prompt-location-required = Введіть місце.
+show-prompt-date = Дата (необов'язково):
+show-prompt-date-help = RFC3339, наприклад 2025-12-04T12:00:00Z; залиште порожнім для поточної погоди.
+
+diff-header = Погода в { $location }
+diff-now = Зараз
+diff-change = Зміна
+
+forecast-header = Прогноз для { $location }
+forecast-warming = Теплішає приблизно на { $change } на день.
+forecast-cooling = Холоднішає приблизно на { $change } на день.
+forecast-temperature-steady = Температура майже не зміниться.
+forecast-pressure-rising = Тиск зростає: погода буде стійкою.
+forecast-pressure-steady = Тиск стабільний.
+forecast-pressure-falling = Тиск падає: погода може змінитися.
+forecast-pressure-storm = Тиск швидко падає: можлива буря.
+forecast-precipitation = Очікуються опади { $start }–{ $end } ({ $amount }).
+forecast-dry = Опадів не очікується.
+
+climate-header = { $location }: норми, { $month ->
+    [1] січень
+    [2] лютий
+    [3] березень
+    [4] квітень
+    [5] травень
+    [6] червень
+    [7] липень
+    [8] серпень
+    [9] вересень
+    [10] жовтень
+    [11] листопад
+   *[12] грудень
+} { $first }–{ $last }
+climate-normal = Норма
+climate-next-day = Наступні 24 год
+climate-high = Середній максимум
+climate-low = Середній мінімум
+climate-record-high = Рекордний максимум
+climate-record-low = Рекордний мінімум
+climate-wet-days = Днів з опадами
+
+## weather record / stats
+
+record-header = Запис погоди для '{ $location }' кожні { $interval }. Натисніть Ctrl+C, щоб зупинити.
+record-stored = { $time }  { $temperature }, { $condition }
+stats-header = { $location }: { $count ->
+    [one] { $count } спостереження
+    [few] { $count } спостереження
+   *[other] { $count } спостережень
+} з { $first } по { $last }.
+stats-min = Мін.
+stats-max = Макс.
+stats-avg = Середнє
+stats-empty =
+    За останні { $period } для '{ $location }' не записано жодного спостереження.
+    Підказка: виконайте `weather record "{ $location }"` або `weather show "{ $location }" --store sqlite`.
+
+## weather monitor
+
+monitor-header = Стежу за небезпечною погодою в '{ $location }' кожні { $interval }. Натисніть Ctrl+C, щоб зупинити.
+monitor-quiet = { $time }  нових попереджень немає
+monitor-alert = { $time }  { $event }: { $headline }
+monitor-alert-until = { $time }  { $event }: { $headline } (до { $until })
+monitor-limit = { $value } (поріг { $limit })
+monitor-wind = Сильний вітер
+monitor-heat = Спека
+monitor-cold = Холод
+monitor-precipitation = Сильні опади
+
+## weather dashboard
+
+dashboard-header = Оновлено о { $time }, оновлення кожні { $interval }. Натисніть Ctrl+C, щоб вийти.
+
+## weather location
+
+location-add-done = Локацію '{ $name }' збережено.
+location-list-header = Збережені локації:
+location-list-empty = Немає збережених локацій. Додайте локацію командою `weather location add <назва> <локація>`.
+location-remove-done = Локацію '{ $name }' видалено.
+location-remove-unknown =
+    Локацію з назвою '{ $name }' не збережено.
+    Підказка: виконайте `weather location list`, щоб побачити збережені локації.
+
+## weather init
+
+init-welcome = Налаштуймо weather. Нічого не зберігається до останнього кроку; натисніть Esc, щоб скасувати.
+init-provider = Провайдер погоди:
+init-key-keep = Залиште порожнім, щоб зберегти налаштований ключ.
+init-key-required = Введіть API-ключ.
+init-key-checking = Перевірка ключа тестовим запитом...
+init-key-ok = Ключ працює.
+init-key-rejected = Провайдер відхилив цей ключ. Перевірте його і спробуйте ще раз.
+init-key-unchecked = Не вдалося перевірити ключ: { $error }
+init-key-use-anyway = Усе одно використати цей ключ?
+init-units = Одиниці вимірювання:
+init-language = Мова повідомлень і опису погоди:
+init-location = Домашнє місце (необов'язково):
+init-location-help = Адреса або координати lat,lon, збережені як місце 'home', яке `weather show` показує без вказаного місця.
+init-done = Готово. Конфігурацію збережено в '{ $path }'.
+init-try-home = Спробуйте `weather show`.
+
+## weather configure
+
+configure-prompt = Введіть API-ключ для провайдера '{ $provider }':
+configure-placeholder = API-ключ
+configure-help = Його можна отримати в особистому кабінеті провайдера.
+configure-updated = Конфігурацію оновлено.
+configure-current-default = Поточний провайдер за замовчуванням: { $provider }
+configure-key-exists = Цей API-ключ уже налаштовано; нічого не змінено.
+configure-key-count = Провайдер '{ $provider }' тепер має { $count ->
+    [one] { $count } API-ключ
+    [few] { $count } API-ключі
+   *[other] { $count } API-ключів
+}, що використовуються по черзі.
+
+## weather provider
+
+provider-list-header = Провайдери:
+provider-status-default = налаштований, за замовчуванням
+provider-status-configured = налаштований
+provider-status-not-configured = не налаштований
+provider-key-count = { $count ->
+    [one] { $count } API-ключ
+    [few] { $count } API-ключі
+   *[other] { $count } API-ключів
+}
+provider-list-hint-configure = Виконайте `weather configure <provider>`, щоб налаштувати провайдера.
+provider-list-hint-use = Виконайте `weather provider use <provider>`, щоб змінити провайдера за замовчуванням.
+provider-use-not-configured =
+    Провайдер '{ $provider }' не налаштований.
+    Підказка: спершу виконайте `weather configure { $provider }`, щоб додати API-ключ.
+provider-use-done = Провайдером за замовчуванням тепер є '{ $provider }'.
+
+## weather config
+
+config-set-done = Параметр '{ $key }' оновлено.
+config-imported = Налаштування імпортовано з '{ $path }'.
+config-replaced = Конфігурацію замінено на '{ $path }'.
+passphrase-prompt = Пароль до зашифрованих API-ключів:
+encrypt-prompt = Новий пароль для API-ключів:
+encrypt-confirm = Повторіть пароль:
+encrypt-mismatch = Паролі не збігаються.
+encrypt-already = API-ключі вже зашифровано.
+encrypt-done =
+    API-ключі зашифровано. Команди, яким вони потрібні, запитуватимуть пароль.
+    Підказка: `weather config unlock` запам'ятовує його на деякий час; також можна задати WEATHER_PASSPHRASE.
+decrypt-already = API-ключі не зашифровано.
+decrypt-done = API-ключі знову зберігаються відкритим текстом.
+unlock-done = Пароль запам'ятовано на { $duration }. Виконайте `weather config lock`, щоб забути його раніше.
+lock-done = Пароль забуто.
+
+## weather cache
+
+cache-cleared = { $count ->
+    [one] Видалено { $count } збережену відповідь.
+    [few] Видалено { $count } збережені відповіді.
+   *[other] Видалено { $count } збережених відповідей.
+}
+
+## weather usage
+
+usage-header = Запити до провайдерів (дні та місяці за UTC):
+usage-today = Сьогодні
+usage-this-month = Цього місяця
+usage-warning-daily =
+    попередження: '{ $name }' використав { $used } з { $limit } запитів, дозволених на сьогодні.
+    Підказка: збільште квоту командою `weather config set daily_quota.{ $provider } <запити>`, якщо ваш тариф це дозволяє.
+usage-warning-monthly =
+    попередження: '{ $name }' використав { $used } з { $limit } запитів, дозволених цього місяця.
+    Підказка: збільште квоту командою `weather config set monthly_quota.{ $provider } <запити>`, якщо ваш тариф це дозволяє.
+
+## weather history
+
+history-time = Час
+history-empty = Запитів ще не записано.
+history-cleared = { $count ->
+    [one] Видалено { $count } запис.
+    [few] Видалено { $count } записи.
+   *[other] Видалено { $count } записів.
+}
+history-disabled = Запити не записуються. Виконайте `weather config set history true`, щоб знову їх записувати.
+
+## Warnings
+
+warning-tls-insecure =
+    попередження: перевірку TLS-сертифікатів ВИМКНЕНО (tls_insecure_skip_verify = true).
+    Трафік до провайдера, зокрема ваш API-ключ, можуть перехопити. Краще використайте `ca_bundle`.
+
+warning-log-file = попередження: не вдалося відкрити файл журналу '{ $path }' ({ $error }); журнал виводиться в stderr.
+
+warning-remember-place = попередження: не вдалося запам'ятати вибране місце: { $error }
+
+warning-history = попередження: не вдалося записати запит в історію: { $error }
+
+warning-export-plaintext = попередження: експорт містить ваші API-ключі відкритим текстом; зберігайте його в таємниці.
+
+warning-record-failed = попередження: { $time }  запит не вдався, повтор на наступному інтервалі: { $error }
+
+warning-forecast-days = попередження: { $provider } прогнозує щонайбільше на { $days } дн. вперед; показано їх.
+
+warning-monitor-thresholds-only = попередження: цей постачальник не публікує погодних попереджень; перевіряються лише налаштовані пороги alert_*.
+
+warning-notify-failed = попередження: не вдалося сповістити через { $channel }: { $error }
+
+## Errors
+
+error-empty-passphrase = Пароль не може бути порожнім.
+error-date-parse = Не вдалося розібрати --date як RFC3339: { $error }
+error-locations-file = Не вдалося прочитати файл локацій '{ $path }': { $error }
+error-show-no-location = потрібно вказати місце: передайте його, --here або --locations-file, або збережіть місце 'home'
+error-statusbar-many = Формати для панелі стану показують лише одну локацію.
+error-init-terminal = `weather init` інтерактивна; запустіть її в терміналі або скористайтеся `weather configure` і `weather config set`.
+error-import-file = Не вдалося прочитати файл конфігурації '{ $path }': { $error }
+error-interval = Недійсний інтервал '{ $value }'. Очікується додатне число з необов'язковою одиницею, наприклад 30s, 15m, 1h або 1d.
+error-forecast-empty = Постачальник не повернув прогнозу на найближчі дні.
+error-monitor-interval = Інтервал моніторингу має бути щонайменше 1m.
+error-monitor-nothing =
+    Цей постачальник не публікує погодних попереджень, а пороги не налаштовано, тож стежити нема за чим.
+    Підказка: задайте поріг, наприклад `weather config set alert_wind_speed 15`, або перейдіть на weatherapi.
+error-here-unknown =
+    Ваше місцезнаходження ще не визначено.
+    Підказка: виконайте `weather show --here` один раз, коли є мережа.
+error-prefix = помилка
+error-chain-header = Ланцюжок помилок:
+error-verbose-hint = (запустіть з -v або --verbose, щоб побачити повний ланцюжок помилок)
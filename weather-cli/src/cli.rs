@@ -1,8 +1,37 @@
-use chrono::{DateTime, Utc};
-use clap::{Parser, Subcommand, ValueHint};
-use inquire::Text;
+use crate::{i18n, timefmt};
+use chrono::{DateTime, Datelike, Local, Utc};
+use clap::{CommandFactory, Parser, Subcommand, ValueHint, error::ErrorKind};
+use clap_complete::{ArgValueCandidates, CompleteEnv, CompletionCandidate, env::Shells};
+use inquire::{Confirm, Password, Select, Text, validator::Validation};
+use std::{
+    env,
+    fmt::Display,
+    fs,
+    io::{self, IsTerminal},
+    path::{Path, PathBuf},
+    sync::OnceLock,
+    time::Duration,
+};
+
 use weather_core::{
-    Config, ProviderId, WeatherRequest, WeatherResponse, provider::default_provider_from_config,
+    ConditionKind, Config, Location, Precipitation, ProviderId, Temperature, Units, WeatherError,
+    WeatherPoint, WeatherProvider, WeatherRequest, WeatherResponse, WeatherSeries,
+    alerts::{AlertThresholds, SeenAlerts, Threshold, WeatherAlert},
+    cache::{CacheMode, CachedFetch, CachedProvider, ResponseCache, ResponseSource},
+    climate::{ClimateArchive, ClimateNormals},
+    config::{DEFAULT_LOCATION, SETTINGS},
+    geoip::IpLocator,
+    history::{History, HistoryEntry},
+    notify::{Channel, Notifier},
+    provider::{
+        DateRequest,
+        circuit::{CircuitBreaker, CircuitBreakerProvider},
+        default_provider_from_config, get_weather_all, provider_from_config,
+    },
+    secrets::{PassphraseSession, SecretString},
+    store::{ObservationStore, Summary},
+    trend::{PressureTendency, Trend},
+    usage::{ProviderUsage, UsageLog},
 };
 
 /// Top-level CLI struct.
@@ -14,15 +43,26 @@ use weather_core::{
     long_about = "
         A small weather command-line tool that can talk to multiple providers \
         (OpenWeather and WeatherAPI), store your API keys locally, and \
-        show current weather for a given address.",
+        show current weather for a given address or coordinates.",
     after_help = "\
         EXAMPLES:
+            # First run: choose a provider, enter its key and set a home location
+            weather init
+
             # Configure OpenWeather provider
             weather configure openweather
 
             # Configure WeatherAPI
             weather configure weatherapi
 
+            # Pool a second OpenWeather key; a rejected or rate-limited key is skipped
+            weather configure openweather --add
+            weather config set key_rotation failover
+
+            # Move the settings to another machine
+            weather config export > backup.toml
+            weather config import backup.toml
+
             # List providers and see which one is default
             weather provider list
 
@@ -32,35 +72,347 @@ use weather_core::{
             # Show current weather for a location
             weather show \"Kyiv\"
 
+            # Show current weather at coordinates (latitude,longitude)
+            weather show \"50.45,30.52\"
+
+            # Show current weather where you are (location detected from your IP address)
+            weather now
+            weather show --here --units imperial
+
+            # Save a location and use it by name
+            weather location add home \"Kyiv, UA\"
+            weather show home
+
+            # Compare several locations (or those listed in a file)
+            weather show \"Kyiv\" \"Lviv\" \"Odesa\"
+            weather show --locations-file cities.txt
+
+            # Log observations into a local SQLite database, then summarize them
+            weather record \"Kyiv\" --interval 30m
+            weather show \"Kyiv\" --store sqlite
+            weather stats \"Kyiv\" --last 7d
+
+            # Notify about severe weather at home, on the desktop and via a webhook
+            weather config set alert_wind_speed 15
+            weather config set webhook_url https://example.com/hooks/weather
+            weather monitor --location home --interval 15m --notify desktop,webhook
+
+            # All saved locations at a glance, refreshed every 15 minutes
+            weather dashboard --watch 15m
+
             # Show weather for a specific time
             weather show \"Kyiv\" --date 2025-12-04T12:00:00Z
+
+            # Feed a waybar custom module (\"return-type\": \"json\"), or polybar and tmux
+            weather show home --format statusbar
+            weather show home --format statusbar-plain
+
+            # A short line for a shell prompt or tmux, without icons if the font lacks them
+            weather show home --oneline
+            weather config set emoji false
+
+            # Show times in UTC rather than local time (\"12 minutes ago\")
+            weather show \"Kyiv\" --utc
+
+            # Will tomorrow be warmer than now?
+            weather diff \"Kyiv\" --date 2025-12-05T12:00:00Z
+
+            # Forecast for the next 5 days: warming, falling pressure, when it rains
+            weather forecast \"Kyiv\" --days 5
+
+            # What is December usually like, and how do the next 24 hours compare?
+            weather climate \"Kyiv\" --month 12
+            weather climate \"Kyiv\" --compare
+
+            # Show weather in imperial units
+            weather show \"Kyiv\" --units imperial
+
+            # Make imperial units the default
+            weather config set units imperial
+
+            # Show weather in Ukrainian
+            weather show \"Kyiv\" --lang uk
+
+            # Always use Ukrainian
+            weather config set language uk
+
+            # See which endpoint a query maps to, without sending it
+            weather show \"Kyiv\" --date 2025-12-04T12:00:00Z --dry-run
+
+            # Bypass the response cache / wipe it
+            weather show \"Kyiv\" --no-cache
+            weather cache clear
+
+            # Use only cached data (no network)
+            weather show \"Kyiv\" --offline
+
+            # Route requests through a proxy (HTTPS_PROXY is honored too)
+            weather config set proxy http://proxy.corp:3128
+
+            # Encrypt the stored API keys with a passphrase; enter it once per hour
+            weather config encrypt
+            weather config unlock --for 1h
+
+            # Trust a corporate root CA
+            weather config set ca_bundle /etc/ssl/corp-root.pem
+
+            # Stay under a provider quota (requests per minute, 0 = unlimited)
+            weather config set rate_limit.openweather 30
+
+            # Review past lookups / wipe them / stop recording them
+            weather history --last 5
+            weather history --clear
+            weather config set history false
+
+            # Check calls against quotas
+            weather usage
+            weather config set daily_quota.openweather 1000
+
+            # Tab completion for bash, including saved location names
+            echo 'source <(weather completions bash)' >> ~/.bashrc
+
+            # Use another config file (WEATHER_CONFIG=<path> does the same)
+            weather --config ./weather.toml show \"Kyiv\"
+
+            # Diagnose a misbehaving provider
+            weather show \"Kyiv\" --log-level debug
+            weather config set log_file /tmp/weather.log
+
+        EXIT STATUS:
+            0  success
+            1  other error
+            2  invalid usage or configuration (no provider configured, bad setting, empty address, ...)
+            3  API key rejected by the provider
+            4  location not found
+            5  date not supported by the provider
+            6  provider rate limit or quota reached, or provider temporarily skipped
+            7  provider unreachable (and nothing usable cached)
         "
 )]
 pub struct Cli {
     #[arg(short, long, global = true)]
     pub verbose: bool,
+    /// Unit system for output: metric or imperial (overrides the configured default).
+    #[arg(long, global = true, value_name = "UNITS")]
+    pub units: Option<String>,
+    /// Language for messages and condition descriptions, e.g. `en` or `uk` (defaults to
+    /// the configured language, then LANG).
+    #[arg(long, global = true, value_name = "LANG")]
+    pub lang: Option<String>,
+    /// Show times in UTC, as providers report them, instead of local time with a relative
+    /// form such as "12 minutes ago".
+    #[arg(long, global = true)]
+    pub utc: bool,
+    /// Config file to use instead of the default one; the WEATHER_CONFIG environment
+    /// variable does the same.
+    #[arg(long, global = true, value_name = "PATH", value_hint = ValueHint::FilePath)]
+    pub config: Option<PathBuf>,
+    /// Diagnostic log level: off, error, warn, info, debug or trace (default warn). Logs go
+    /// to stderr, or to the configured `log_file`.
+    #[arg(
+        long,
+        global = true,
+        value_name = "LEVEL",
+        value_parser = ["off", "error", "warn", "info", "debug", "trace"]
+    )]
+    pub log_level: Option<String>,
     #[command(subcommand)]
     pub command: Command,
 }
 
 #[derive(Debug, Subcommand)]
 pub enum Command {
+    /// Set up a provider, its API key, units, language and a home location step by step.
+    Init,
+
     /// Configure credentials for a specific provider.
     Configure {
         /// Run `weather provider list` to see all supported providers.
-        #[arg(value_name = "PROVIDER")]
+        #[arg(value_name = "PROVIDER", add = ArgValueCandidates::new(provider_candidates))]
         provider: String,
+
+        /// Keep the provider's API keys and add this one to them; requests then use the
+        /// keys in turn (see the `key_rotation` setting).
+        #[arg(long)]
+        add: bool,
     },
 
-    /// Show weather for an address.
+    /// Show weather for an address or coordinates; several are compared in a table.
     Show {
-        /// Address or location name, e.g. "Kyiv".
-        #[arg(value_name = "ADDRESS", value_hint = ValueHint::Other)]
-        address: String,
+        /// Address, e.g. "Kyiv", "lat,lon" coordinates, e.g. "50.45,30.52" or
+        /// "-33.92,18.42", or the name of a saved location (see `weather location`).
+        /// Defaults to the saved location `home`; asked for if there is none.
+        #[arg(
+            value_name = "LOCATION",
+            value_hint = ValueHint::Other,
+            allow_hyphen_values = true,
+            add = ArgValueCandidates::new(location_candidates)
+        )]
+        location: Option<String>,
+
+        /// More locations to compare with the first one. Put `--` before them if one of
+        /// them is coordinates with a negative latitude: `weather show Kyiv -- -33.92,18.42`.
+        #[arg(
+            value_name = "LOCATION",
+            value_hint = ValueHint::Other,
+            add = ArgValueCandidates::new(location_candidates)
+        )]
+        more_locations: Vec<String>,
+
+        /// Also show every location listed in a file, one per line (`#` starts a comment).
+        #[arg(long, value_name = "FILE", value_hint = ValueHint::FilePath)]
+        locations_file: Option<PathBuf>,
+
+        /// Use the approximate location of this machine, detected from its IP address.
+        #[arg(long, conflicts_with_all = ["location", "locations_file"])]
+        here: bool,
 
         /// Optional date/time in RFC3339 format, e.g. 2025-12-04T12:00:00Z;
         #[arg(long, value_name = "RFC3339_DATETIME")]
         date: Option<String>,
+
+        /// Always ask the provider, ignoring (and not updating) cached responses.
+        #[arg(long, conflicts_with = "offline")]
+        no_cache: bool,
+
+        /// Never use the network; show the cached response for this location and date,
+        /// however old.
+        #[arg(long)]
+        offline: bool,
+
+        /// Print the request that would be sent to the provider (API key masked) and exit.
+        #[arg(long, conflicts_with = "offline")]
+        dry_run: bool,
+
+        /// Also append the responses to a local database, for `weather stats`. Current
+        /// weather only: a forecast is not an observation.
+        #[arg(
+            long,
+            value_name = "STORE",
+            value_parser = ["sqlite"],
+            conflicts_with_all = ["dry_run", "date"]
+        )]
+        store: Option<String>,
+
+        /// Output format: `text`, `statusbar` (JSON for a waybar custom module, with text,
+        /// tooltip and class) or `statusbar-plain` (one short line for polybar, i3status or
+        /// tmux). The status bar formats take a single location.
+        #[arg(
+            long,
+            value_name = "FORMAT",
+            value_parser = ["text", "statusbar", "statusbar-plain"],
+            default_value = "text",
+            conflicts_with = "dry_run"
+        )]
+        format: String,
+
+        /// Print each location on one line, e.g. for a shell prompt or a tmux status line.
+        #[arg(long, conflicts_with_all = ["dry_run", "format"])]
+        oneline: bool,
+    },
+
+    /// Keep recording the weather at a location into the local database, for
+    /// `weather stats`.
+    Record {
+        /// Address, "lat,lon" coordinates or the name of a saved location.
+        #[arg(allow_hyphen_values = true, add = ArgValueCandidates::new(location_candidates))]
+        location: String,
+
+        /// Time between observations, e.g. 30s, 15m or 1h.
+        #[arg(long, value_name = "INTERVAL", value_parser = parse_interval, default_value = "30m")]
+        interval: Duration,
+    },
+
+    /// Watch a location for severe weather and send a notification when a new alert
+    /// appears: the provider's official warnings plus the configured `alert_*` limits.
+    Monitor {
+        /// Address, "lat,lon" coordinates or the name of a saved location.
+        #[arg(
+            long,
+            allow_hyphen_values = true,
+            add = ArgValueCandidates::new(location_candidates)
+        )]
+        location: String,
+
+        /// Time between checks, e.g. 5m, 15m or 1h; at least 1m.
+        #[arg(long, value_name = "INTERVAL", value_parser = parse_interval, default_value = "15m")]
+        interval: Duration,
+
+        /// Where to send new alerts: desktop, webhook or both, comma-separated.
+        #[arg(
+            long,
+            value_name = "CHANNELS",
+            value_delimiter = ',',
+            value_parser = ["desktop", "webhook"],
+            default_value = "desktop"
+        )]
+        notify: Vec<String>,
+
+        /// Check once and exit, e.g. when run from cron.
+        #[arg(long)]
+        once: bool,
+    },
+
+    /// Lowest, highest and average values recorded for a location.
+    Stats {
+        /// The location as given to `weather record` or `weather show --store sqlite`.
+        #[arg(allow_hyphen_values = true, add = ArgValueCandidates::new(location_candidates))]
+        location: String,
+
+        /// How far back to look, e.g. 24h, 7d or 30d.
+        #[arg(long, value_name = "PERIOD", value_parser = parse_interval, default_value = "7d")]
+        last: Duration,
+    },
+
+    /// Show current weather at your location (same as `weather show --here`).
+    Now,
+
+    /// Compare the weather at one date with another or with now, e.g. whether tomorrow
+    /// will be warmer than today.
+    Diff {
+        /// Address, "lat,lon" coordinates or the name of a saved location.
+        #[arg(allow_hyphen_values = true, add = ArgValueCandidates::new(location_candidates))]
+        location: String,
+
+        /// The date to look at, in RFC3339 format, e.g. 2025-12-05T12:00:00Z.
+        #[arg(long, value_name = "RFC3339_DATETIME")]
+        date: String,
+
+        /// The date to compare it with, in the same format (default: now).
+        #[arg(long, value_name = "RFC3339_DATETIME")]
+        baseline: Option<String>,
+    },
+
+    /// Forecast for the next days, with the trends in it: warming or cooling, falling
+    /// pressure and when to expect rain.
+    Forecast {
+        /// Address, "lat,lon" coordinates or the name of a saved location.
+        #[arg(allow_hyphen_values = true, add = ArgValueCandidates::new(location_candidates))]
+        location: String,
+
+        /// How many days ahead, 1 to 5. WeatherAPI forecasts at most 3 days ahead.
+        #[arg(long, value_parser = clap::value_parser!(u8).range(1..=5), default_value = "3")]
+        days: u8,
+    },
+
+    /// Typical highs, lows and precipitation of a month, averaged over past years
+    /// (from the Open-Meteo archive, no API key needed).
+    Climate {
+        /// Address, "lat,lon" coordinates or the name of a saved location.
+        #[arg(allow_hyphen_values = true, add = ArgValueCandidates::new(location_candidates))]
+        location: String,
+
+        /// The month, 1 (January) to 12 (default: this month).
+        #[arg(long, value_parser = clap::value_parser!(u32).range(1..=12))]
+        month: Option<u32>,
+
+        /// How many past years to average, 1 to 30.
+        #[arg(long, value_parser = clap::value_parser!(u32).range(1..=30), default_value = "10")]
+        years: u32,
+
+        /// Also show how the next 24 hours of the forecast compare with the normals.
+        #[arg(long)]
+        compare: bool,
     },
 
     /// Provider management commands.
@@ -68,6 +420,60 @@ pub enum Command {
         #[command(subcommand)]
         command: ProviderCommand,
     },
+
+    /// Current weather at every saved location, in one table.
+    Dashboard {
+        /// Keep the table on screen and refresh it every INTERVAL, e.g. 30s, 15m or 1h.
+        #[arg(long, value_name = "INTERVAL", value_parser = parse_interval)]
+        watch: Option<Duration>,
+    },
+
+    /// Saved locations, usable by name, e.g. `weather show home`.
+    Location {
+        #[command(subcommand)]
+        command: LocationCommand,
+    },
+
+    /// Settings management commands.
+    Config {
+        #[command(subcommand)]
+        command: ConfigCommand,
+    },
+
+    /// Response cache management commands.
+    Cache {
+        #[command(subcommand)]
+        command: CacheCommand,
+    },
+
+    /// Show provider calls made today and this month against their quotas.
+    Usage,
+
+    /// Review past lookups, newest last.
+    History {
+        /// How many of the most recent lookups to show.
+        #[arg(long, value_name = "N", default_value_t = 20)]
+        last: usize,
+
+        /// Delete all recorded lookups.
+        #[arg(long, conflicts_with = "last")]
+        clear: bool,
+    },
+
+    /// Print a shell completion script. Saved location names, providers and settings
+    /// are completed too, as they are when Tab is pressed.
+    #[command(after_help = "\
+        INSTALL:
+            bash        echo 'source <(weather completions bash)' >> ~/.bashrc
+            zsh         echo 'source <(weather completions zsh)' >> ~/.zshrc
+            fish        weather completions fish > ~/.config/fish/completions/weather.fish
+            powershell  weather completions powershell >> $PROFILE
+
+        Regenerate the script after upgrading weather.")]
+    Completions {
+        #[arg(value_parser = ["bash", "zsh", "fish", "powershell"])]
+        shell: String,
+    },
 }
 
 #[derive(Debug, Subcommand)]
@@ -76,17 +482,192 @@ pub enum ProviderCommand {
     List,
 
     /// Set default provider (must be already configured).
-    Use { provider: String },
+    Use {
+        #[arg(add = ArgValueCandidates::new(provider_candidates))]
+        provider: String,
+    },
+}
+
+#[derive(Debug, Subcommand)]
+pub enum LocationCommand {
+    /// Save a location under a name, e.g. `weather location add home "Kyiv, UA"`.
+    Add {
+        name: String,
+        /// Address or "lat,lon" coordinates.
+        #[arg(allow_hyphen_values = true)]
+        location: String,
+    },
+
+    /// List saved locations.
+    List,
+
+    /// Forget a saved location.
+    Remove {
+        #[arg(add = ArgValueCandidates::new(location_candidates))]
+        name: String,
+    },
+}
+
+#[derive(Debug, Subcommand)]
+pub enum CacheCommand {
+    /// Remove all cached responses.
+    Clear,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum ConfigCommand {
+    /// Change a setting, e.g. `weather config set units imperial`.
+    Set {
+        /// Setting name, e.g. units, language, cache_ttl, retry_attempts.
+        #[arg(add = ArgValueCandidates::new(setting_candidates))]
+        key: String,
+        value: String,
+    },
+
+    /// Print the configuration, with API keys masked.
+    Show,
+
+    /// Print the configuration file, API keys included, e.g.
+    /// `weather config export > backup.toml`. Encrypted keys stay encrypted.
+    Export,
+
+    /// Take over the settings of a file made by `weather config export`. Settings,
+    /// providers and locations in the file replace those of the same name; the rest is
+    /// kept.
+    Import {
+        #[arg(value_hint = ValueHint::FilePath)]
+        path: PathBuf,
+        /// Replace the whole configuration with the file instead.
+        #[arg(long)]
+        overwrite: bool,
+    },
+
+    /// Encrypt the stored API keys with a passphrase, asked for whenever they are needed.
+    Encrypt,
+
+    /// Store the API keys in plaintext again.
+    Decrypt,
+
+    /// Remember the passphrase for a while, so commands stop asking for it. Needs a
+    /// runtime directory (XDG_RUNTIME_DIR), which is cleared on logout.
+    Unlock {
+        /// How long to remember it, e.g. 15m or 8h.
+        #[arg(long = "for", value_name = "DURATION", value_parser = parse_interval, default_value = "15m")]
+        duration: Duration,
+    },
+
+    /// Forget a passphrase remembered by `weather config unlock`.
+    Lock,
 }
 
+/// Environment variable that makes the binary answer a completion request from the
+/// shell instead of running a command; see [`Cli::complete`].
+const COMPLETE_VAR: &str = "COMPLETE";
+
+/// The config file given with `--config`, if any; see [`Cli::use_config_file`].
+static CONFIG_FILE: OnceLock<PathBuf> = OnceLock::new();
+
 impl Cli {
+    /// Answer a completion request from a script printed by `weather completions`, then
+    /// exit. Does nothing otherwise; call before anything is printed.
+    pub fn complete() {
+        CompleteEnv::with_factory(Cli::command).var(COMPLETE_VAR).complete();
+    }
+
+    /// Make every command read and write the `--config` file, if one was given. Call
+    /// before loading the config.
+    pub fn use_config_file(&self) {
+        if let Some(path) = &self.config {
+            let _ = CONFIG_FILE.set(path.clone());
+        }
+    }
+
     pub async fn run(self) -> anyhow::Result<()> {
         match self.command {
-            Command::Configure { provider } => {
-                run_configure(provider)?;
+            Command::Init => {
+                run_init().await?;
+            }
+            Command::Configure { provider, add } => {
+                run_configure(provider, add)?;
+            }
+            Command::Show {
+                location,
+                more_locations,
+                locations_file,
+                here,
+                mut date,
+                no_cache,
+                offline,
+                dry_run,
+                store,
+                format,
+                oneline,
+            } => {
+                let mut locations: Vec<String> =
+                    location.into_iter().chain(more_locations).collect();
+                if locations.is_empty() && locations_file.is_none() && !here {
+                    let (location, prompted_date) = prompt_show_target(date.is_none())?;
+                    locations.push(location);
+                    date = date.or(prompted_date);
+                }
+                let when = parse_date_opt(date)?;
+                if let Some(path) = locations_file {
+                    locations.extend(read_locations_file(&path)?);
+                }
+                // `None` stands for `--here`.
+                let mut targets: Vec<Option<String>> = locations.into_iter().map(Some).collect();
+                if here {
+                    targets.push(None);
+                }
+                let mode = if offline { CacheMode::Offline } else { CacheMode::Online };
+                let cache = (!no_cache).then_some(mode);
+                let store = store.is_some();
+                if format != "text" && targets.len() > 1 {
+                    return Err(anyhow::anyhow!(t!("error-statusbar-many")));
+                }
+                let format = if oneline { "oneline".to_string() } else { format };
+
+                if dry_run {
+                    for (i, target) in targets.into_iter().enumerate() {
+                        if i > 0 {
+                            println!();
+                        }
+                        run_show_dry_run(target, when, self.lang.clone()).await?;
+                    }
+                } else if targets.len() == 1 {
+                    let location = targets.pop().flatten();
+                    let units = self.units;
+                    run_show(location, when, cache, store, &format, units, self.lang).await?;
+                } else {
+                    let locations = targets.into_iter().flatten().collect();
+                    let units = self.units;
+                    run_show_many(locations, when, cache, store, oneline, units, self.lang).await?;
+                }
+            }
+            Command::Now => {
+                let cache = Some(CacheMode::Online);
+                run_show(None, None, cache, false, "text", self.units, self.lang).await?;
+            }
+            Command::Diff { location, date, baseline } => {
+                let when = parse_date_opt(Some(date))?;
+                let baseline = parse_date_opt(baseline)?;
+                run_diff(location, when, baseline, self.units, self.lang).await?;
+            }
+            Command::Forecast { location, days } => {
+                run_forecast(location, days, self.units, self.lang).await?;
+            }
+            Command::Climate { location, month, years, compare } => {
+                let month = month.unwrap_or_else(|| Local::now().month());
+                run_climate(location, month, years, compare, self.units, self.lang).await?;
             }
-            Command::Show { address, date } => {
-                run_show(address, date).await?;
+            Command::Record { location, interval } => {
+                run_record(location, interval, self.units, self.lang).await?;
+            }
+            Command::Monitor { location, interval, notify, once } => {
+                run_monitor(location, interval, notify, once, self.units, self.lang).await?;
+            }
+            Command::Stats { location, last } => {
+                run_stats(location, last, self.units)?;
             }
             Command::Provider { command } => match command {
                 ProviderCommand::List => {
@@ -96,117 +677,2106 @@ impl Cli {
                     run_provider_use(provider)?;
                 }
             },
+            Command::Dashboard { watch } => {
+                run_dashboard(watch, self.units, self.lang).await?;
+            }
+            Command::Location { command } => match command {
+                LocationCommand::Add { name, location } => {
+                    run_location_add(name, location)?;
+                }
+                LocationCommand::List => {
+                    run_location_list()?;
+                }
+                LocationCommand::Remove { name } => {
+                    run_location_remove(name)?;
+                }
+            },
+            Command::Config { command } => match command {
+                ConfigCommand::Set { key, value } => {
+                    run_config_set(key, value)?;
+                }
+                ConfigCommand::Show => {
+                    run_config_show()?;
+                }
+                ConfigCommand::Export => {
+                    run_config_export()?;
+                }
+                ConfigCommand::Import { path, overwrite } => {
+                    run_config_import(&path, overwrite)?;
+                }
+                ConfigCommand::Encrypt => {
+                    run_config_encrypt()?;
+                }
+                ConfigCommand::Decrypt => {
+                    run_config_decrypt()?;
+                }
+                ConfigCommand::Unlock { duration } => {
+                    run_config_unlock(duration)?;
+                }
+                ConfigCommand::Lock => {
+                    run_config_lock()?;
+                }
+            },
+            Command::Cache { command } => match command {
+                CacheCommand::Clear => {
+                    run_cache_clear()?;
+                }
+            },
+            Command::Usage => {
+                run_usage()?;
+            }
+            Command::History { last, clear } => {
+                run_history(last, clear, self.units)?;
+            }
+            Command::Completions { shell } => {
+                run_completions(&shell)?;
+            }
         }
 
         Ok(())
     }
 }
 
+/// Process exit status for a failed command, see EXIT STATUS in `--help`.
+pub fn exit_code(err: &anyhow::Error) -> i32 {
+    let Some(err) = err.downcast_ref::<WeatherError>() else {
+        return 1;
+    };
+
+    match err {
+        WeatherError::ProviderNotConfigured(_)
+        | WeatherError::NoDefaultProvider
+        | WeatherError::UnknownProvider(_)
+        | WeatherError::InvalidSetting(_)
+        | WeatherError::InvalidRequest(_)
+        | WeatherError::AutoLocateDisabled
+        | WeatherError::ConfigLocked
+        | WeatherError::WrongPassphrase => 2,
+        WeatherError::Unauthorized { .. } => 3,
+        WeatherError::LocationNotFound { .. } | WeatherError::PlaceNotFound(_) => 4,
+        WeatherError::UnsupportedDate(_) => 5,
+        WeatherError::RateLimited { .. } | WeatherError::CircuitOpen(_) => 6,
+        WeatherError::Http { .. } | WeatherError::NotCached(_) => 7,
+        _ => 1,
+    }
+}
+
+/// The location for `weather show` run without one: the default location if one is
+/// saved, otherwise asked for together with, if `ask_date`, an optional date. Without a
+/// terminal to ask on, this is a usage error as before.
+fn prompt_show_target(ask_date: bool) -> anyhow::Result<(String, Option<String>)> {
+    if load_config().is_ok_and(|cfg| cfg.default_location().is_some()) {
+        return Ok((DEFAULT_LOCATION.to_string(), None));
+    }
+    if !io::stdin().is_terminal() {
+        let mut cli = Cli::command();
+        cli.build();
+        let show = cli.find_subcommand_mut("show").expect("show is a subcommand");
+        show.error(ErrorKind::MissingRequiredArgument, t!("error-show-no-location")).exit();
+    }
+
+    let saved = load_config().map(|cfg| cfg.locations.into_keys().collect::<Vec<_>>());
+    let help = match saved {
+        Ok(mut names) if !names.is_empty() => {
+            names.sort();
+            t!("show-prompt-location-saved", names = names.join(", "))
+        }
+        _ => t!("show-prompt-location-help"),
+    };
+    let location = Text::new(&t!("show-prompt-location"))
+        .with_help_message(&help)
+        .with_validator(|input: &str| {
+            Ok(if input.trim().is_empty() {
+                Validation::Invalid(t!("show-prompt-location-required").into())
+            } else {
+                Validation::Valid
+            })
+        })
+        .prompt()?;
+
+    let date = if ask_date {
+        let date = Text::new(&t!("show-prompt-date"))
+            .with_help_message(&t!("show-prompt-date-help"))
+            .with_validator(|input: &str| {
+                let input = input.trim();
+                if input.is_empty() {
+                    return Ok(Validation::Valid);
+                }
+                Ok(match DateTime::parse_from_rfc3339(input) {
+                    Ok(_) => Validation::Valid,
+                    Err(err) => Validation::Invalid(t!("error-date-parse", error = err).into()),
+                })
+            })
+            .prompt()?;
+        Some(date.trim().to_string()).filter(|date| !date.is_empty())
+    } else {
+        None
+    };
+
+    Ok((location.trim().to_string(), date))
+}
+
 fn parse_date_opt(s: Option<String>) -> anyhow::Result<Option<DateTime<Utc>>> {
     if let Some(raw) = s {
         let dt = DateTime::parse_from_rfc3339(&raw)
-            .map_err(|e| anyhow::anyhow!("Failed to parse --date as RFC3339: {e}"))?;
+            .map_err(|e| anyhow::anyhow!(t!("error-date-parse", error = e)))?;
         Ok(Some(dt.with_timezone(&Utc)))
     } else {
         Ok(None)
     }
 }
 
-fn print_weather(response: &WeatherResponse) {
-    println!("Provider:       {}", response.provider);
-    println!("Location:       {}", response.location_name);
-    println!("Observed at:    {}", response.observation_time);
-    println!("Condition:      {}", response.condition);
-    println!("Temperature:    {:.1} °C", response.temperature_c);
-    println!("Feels like:     {:.1} °C", response.feels_like_c);
-    println!("Humidity:       {} %", response.humidity_pct);
-    println!("Wind speed:     {:.1} m/s", response.wind_speed_mps);
+fn build_request(
+    location: Location,
+    when: Option<DateTime<Utc>>,
+    language: Option<String>,
+    units: Option<Units>,
+) -> anyhow::Result<WeatherRequest> {
+    let mut builder = WeatherRequest::builder().location(location);
+    if let Some(when) = when {
+        builder = builder.at(when);
+    }
+    if let Some(language) = language {
+        builder = builder.language(language);
+    }
+    if let Some(units) = units {
+        builder = builder.units(units);
+    }
+
+    Ok(builder.build()?)
+}
+
+/// Load the config from the `--config` file, `WEATHER_CONFIG` or the default location.
+pub fn load_config() -> anyhow::Result<Config> {
+    match CONFIG_FILE.get() {
+        Some(path) => Ok(Config::load_from(path)?),
+        None => Ok(Config::load()?),
+    }
 }
 
-/// Handle `weather configure <provider>`.
-fn run_configure(provider: String) -> anyhow::Result<()> {
-    let provider_id = ProviderId::try_from(provider.as_str())?;
+/// Save the config where [`load_config`] reads it from.
+fn save_config(cfg: &Config) -> anyhow::Result<()> {
+    match CONFIG_FILE.get() {
+        Some(path) => Ok(cfg.save_to(path)?),
+        None => Ok(cfg.save()?),
+    }
+}
 
-    let prompt = format!("Enter API key for provider '{provider_id}':");
-    let api_key = Text::new(&prompt)
-        .with_placeholder("API key")
-        .with_help_message("You can get this from your provider's dashboard.")
-        .prompt()?;
+/// Load the config with its API keys decrypted, if they are encrypted. The passphrase
+/// comes from a session started by `weather config unlock`, then `WEATHER_PASSPHRASE`,
+/// then a prompt.
+fn load_unlocked_config() -> anyhow::Result<Config> {
+    let mut cfg = load_config()?;
+    if !cfg.is_locked() {
+        return Ok(cfg);
+    }
 
-    let mut cfg = Config::load()?;
+    // A session outlives a passphrase change; then ask as if there were none.
+    if let Ok(path) = PassphraseSession::default_path()
+        && let Some(passphrase) = PassphraseSession::new(path).passphrase()
+        && cfg.unlock(passphrase).is_ok()
+    {
+        return Ok(cfg);
+    }
 
-    cfg.upsert_provider_api_key(provider_id, api_key);
-    cfg.save()?;
+    cfg.unlock(ask_passphrase()?)?;
+    Ok(cfg)
+}
 
-    println!("Configuration updated.");
-    if let Ok(default_id) = cfg.default_provider_id() {
-        println!("Current default provider: {default_id}");
+/// The passphrase of the encrypted API keys, from `WEATHER_PASSPHRASE` or a prompt.
+fn ask_passphrase() -> anyhow::Result<SecretString> {
+    if let Ok(passphrase) = env::var("WEATHER_PASSPHRASE") {
+        return Ok(passphrase.into());
+    }
+    if !io::stdin().is_terminal() {
+        return Err(WeatherError::ConfigLocked.into());
     }
 
-    Ok(())
+    let passphrase = Password::new(&t!("passphrase-prompt")).without_confirmation().prompt()?;
+    Ok(passphrase.into())
 }
 
-/// Handle `weather show <address> [--date ...]`.
-async fn run_show(address: String, date: Option<String>) -> anyhow::Result<()> {
-    let when = parse_date_opt(date)?;
+/// Resolve the unit system: `--units` flag first, then the configured default.
+fn resolve_units(flag: Option<String>, cfg: &Config) -> anyhow::Result<Units> {
+    match flag {
+        Some(raw) => Ok(Units::try_from(raw.as_str())?),
+        None => Ok(cfg.units()),
+    }
+}
 
-    let cfg = Config::load()?;
-    let provider = default_provider_from_config(&cfg)?;
+/// The unlocked config, units and language for a command that asks a provider; see
+/// [`with_preferences`].
+fn load_for_request(
+    units: Option<String>,
+    lang: Option<String>,
+) -> anyhow::Result<(Config, Units, Option<String>)> {
+    with_preferences(load_unlocked_config()?, units, lang)
+}
 
-    let request = WeatherRequest { address, when };
+/// `cfg` with the units and language to ask for, from the flags or the config, after
+/// reminding that TLS verification is off if it is.
+fn with_preferences(
+    cfg: Config,
+    units: Option<String>,
+    lang: Option<String>,
+) -> anyhow::Result<(Config, Units, Option<String>)> {
+    let units = resolve_units(units, &cfg)?;
+    let language = request_language(lang, &cfg);
+    warn_insecure_tls(&cfg);
+    Ok((cfg, units, language))
+}
 
-    let response = provider.get_weather(&request).await?;
+/// Language for condition descriptions: `--lang` first, then the configured language,
+/// then the system one.
+fn request_language(flag: Option<String>, cfg: &Config) -> Option<String> {
+    flag.or_else(|| cfg.language.clone()).or_else(i18n::system_language)
+}
 
-    print_weather(&response);
+/// Loudly remind that TLS verification is off; it must never go unnoticed.
+fn warn_insecure_tls(cfg: &Config) {
+    if cfg.tls_insecure_skip_verify() {
+        eprintln!("{}", t!("warning-tls-insecure"));
+    }
+}
 
-    Ok(())
+/// The first line of an error message, for one-line reports such as table cells.
+fn first_line(err: &impl Display) -> String {
+    err.to_string().lines().next().unwrap_or_default().to_string()
 }
 
-fn run_provider_list() -> anyhow::Result<()> {
-    let cfg = Config::load()?;
+/// Compact age such as `45s`, `12m`, `3h 5m`, `2d 4h` or `7d`.
+fn format_age(age: Duration) -> String {
+    let secs = age.as_secs();
+    let (days, hours, mins) = (secs / 86_400, secs / 3_600 % 24, secs / 60 % 60);
 
-    let default_id = cfg.default_provider_id().ok(); // ignore error, might be None
+    match (days, hours, mins) {
+        (0, 0, 0) => format!("{secs}s"),
+        (0, 0, m) => format!("{m}m"),
+        (0, h, 0) => format!("{h}h"),
+        (0, h, m) => format!("{h}h {m}m"),
+        (d, 0, _) => format!("{d}d"),
+        (d, h, _) => format!("{d}d {h}h"),
+    }
+}
 
-    println!("Providers:");
-    println!();
+/// The values of `response` with their labels, in report order.
+fn weather_rows(response: &WeatherResponse, units: Units) -> [(String, String); 10] {
+    [
+        (t!("label-provider"), response.provider.clone()),
+        (t!("label-location"), response.location_name.clone()),
+        (t!("label-observed-at"), timefmt::observed(response.observation_time)),
+        (t!("label-condition"), response.condition.clone()),
+        (
+            t!("label-temperature"),
+            format!(
+                "{:.1} {}",
+                units.temperature(response.temperature),
+                units.temperature_symbol()
+            ),
+        ),
+        (
+            t!("label-feels-like"),
+            format!("{:.1} {}", units.temperature(response.feels_like), units.temperature_symbol()),
+        ),
+        (t!("label-humidity"), format!("{} %", response.humidity_pct)),
+        (
+            t!("label-wind-speed"),
+            format!("{:.1} {}", units.wind_speed(response.wind_speed), units.wind_speed_symbol()),
+        ),
+        (
+            t!("label-pressure"),
+            format!("{:.2} {}", units.pressure(response.pressure), units.pressure_symbol()),
+        ),
+        (
+            t!("label-precipitation"),
+            format!(
+                "{:.2} {}",
+                units.precipitation(response.precipitation),
+                units.precipitation_symbol()
+            ),
+        ),
+    ]
+}
 
-    for id in ProviderId::all() {
-        let name = id.as_str();
-        let configured = cfg.is_provider_configured(*id);
-        let is_default = default_id == Some(*id);
+fn print_weather(response: &WeatherResponse, units: Units) {
+    let rows = weather_rows(response, units);
 
-        let status = if configured {
-            if is_default { "configured, default" } else { "configured" }
-        } else {
-            "not configured"
-        };
+    // Labels differ in length between locales, so align on the longest one.
+    let width = rows.iter().map(|(label, _)| label.chars().count()).max().unwrap_or(0) + 2;
 
-        println!("  - {:<12}  {}", name, status);
+    for (label, value) in rows {
+        println!("{:<width$}{value}", format!("{label}:"));
     }
+}
 
-    println!();
-    println!("Use `weather configure <provider>` to configure a provider.");
-    println!("Use `weather provider use <provider>` to switch the default provider.");
+/// Short text for a status bar: an icon for the condition, unless `emoji` is off, and
+/// the temperature rounded to whole degrees, e.g. `🌧 3°C`.
+fn statusbar_text(response: &WeatherResponse, units: Units, emoji: bool) -> String {
+    // Adding zero turns -0 into 0, which reads better.
+    let temperature = units.temperature(response.temperature).round() + 0.0;
 
-    Ok(())
+    format!("{}{temperature}{}", icon_prefix(response, emoji), units.temperature_symbol())
 }
 
-fn run_provider_use(provider: String) -> anyhow::Result<()> {
-    let id = ProviderId::try_from(provider.as_str())?;
+/// One line such as `Kyiv, UA: ☁ -2.1°C (feels -6.0), 81%, 4.2 m/s NW`, for shell
+/// prompts and tmux. The temperature is colored by how warm it is if `color` is on.
+fn oneline_text(response: &WeatherResponse, units: Units, emoji: bool, color: bool) -> String {
+    let symbol = units.temperature_symbol();
+    let temperature = format!("{:.1}{symbol}", units.temperature(response.temperature));
+    let temperature = match temperature_class(response.temperature) {
+        "freezing" | "cold" if color => format!("\x1b[34m{temperature}\x1b[0m"),
+        "hot" if color => format!("\x1b[31m{temperature}\x1b[0m"),
+        _ => temperature,
+    };
+    let mut wind =
+        format!("{:.1} {}", units.wind_speed(response.wind_speed), units.wind_speed_symbol());
+    if let Some(direction) = response.wind_direction {
+        wind = format!("{wind} {}", direction.compass());
+    }
 
-    let mut cfg = Config::load()?;
+    t!(
+        "oneline",
+        location = &response.location_name,
+        icon = icon_prefix(response, emoji),
+        temperature = temperature,
+        feels = format!("{:.1}", units.temperature(response.feels_like)),
+        humidity = response.humidity_pct,
+        wind = wind
+    )
+}
 
-    if !cfg.is_provider_configured(id) {
-        return Err(anyhow::anyhow!(
-            "Provider '{id}' is not configured.\n\
-             Hint: run `weather configure {id}` first to add an API key."
-        ));
+/// The condition icon followed by a space, or nothing if `emoji` is off.
+fn icon_prefix(response: &WeatherResponse, emoji: bool) -> String {
+    if !emoji {
+        return String::new();
+    }
+    format!("{} ", condition_icon(response.condition_kind()))
+}
+
+/// JSON for a waybar custom module with `"return-type": "json"`: the short text, the
+/// whole report as tooltip, and classes to style the module by, e.g. `["rain", "cold"]`,
+/// plus `stale` for cached data shown because the provider could not be reached.
+fn statusbar_json(response: &WeatherResponse, units: Units, emoji: bool, stale: bool) -> String {
+    let tooltip: Vec<String> = weather_rows(response, units)
+        .into_iter()
+        .map(|(label, value)| format!("{label}: {value}"))
+        .collect();
+
+    let kind = response.condition_kind();
+    let mut class = vec![kind.as_str(), temperature_class(response.temperature)];
+    if stale {
+        class.push("stale");
     }
 
-    cfg.set_default_provider(id);
-    cfg.save()?;
+    serde_json::json!({
+        "text": statusbar_text(response, units, emoji),
+        "alt": kind.as_str(),
+        "tooltip": tooltip.join("\n"),
+        "class": class,
+    })
+    .to_string()
+}
+
+fn condition_icon(kind: ConditionKind) -> &'static str {
+    match kind {
+        ConditionKind::Clear => "☀",
+        ConditionKind::Clouds => "☁",
+        ConditionKind::Fog => "🌫",
+        ConditionKind::Rain => "🌧",
+        ConditionKind::Snow => "❄",
+        ConditionKind::Thunderstorm => "⛈",
+        ConditionKind::Other => "🌡",
+    }
+}
+
+/// Status bar class for how warm it is: freezing, cold, mild, warm or hot.
+fn temperature_class(temperature: Temperature) -> &'static str {
+    match temperature.celsius() {
+        c if c < 0.0 => "freezing",
+        c if c < 10.0 => "cold",
+        c if c < 20.0 => "mild",
+        c if c < 30.0 => "warm",
+        _ => "hot",
+    }
+}
+
+/// Handle `weather init`: the first-run wizard. Nothing is saved until the last step.
+async fn run_init() -> anyhow::Result<()> {
+    if !io::stdin().is_terminal() {
+        return Err(anyhow::anyhow!(t!("error-init-terminal")));
+    }
+
+    let mut cfg = load_unlocked_config()?;
+    println!("{}", t!("init-welcome"));
+
+    let providers = ProviderId::all().to_vec();
+    let current = cfg.default_provider_id().ok();
+    let start = providers.iter().position(|id| Some(*id) == current).unwrap_or(0);
+    let provider_id =
+        Select::new(&t!("init-provider"), providers).with_starting_cursor(start).prompt()?;
+
+    loop {
+        let has_key = cfg.is_provider_configured(provider_id);
+        let api_key = Text::new(&t!("configure-prompt", provider = provider_id))
+            .with_placeholder(&t!("configure-placeholder"))
+            .with_help_message(&if has_key { t!("init-key-keep") } else { t!("configure-help") })
+            .with_validator(move |key: &str| {
+                Ok(if key.trim().is_empty() && !has_key {
+                    Validation::Invalid(t!("init-key-required").into())
+                } else {
+                    Validation::Valid
+                })
+            })
+            .prompt()?;
+        if api_key.trim().is_empty() {
+            break;
+        }
+
+        let mut candidate = cfg.clone();
+        candidate.upsert_provider_api_key(provider_id, api_key.trim().to_string());
+        if check_api_key(provider_id, &candidate).await? {
+            cfg = candidate;
+            break;
+        }
+    }
+    cfg.set_default_provider(provider_id);
 
-    println!("Default provider set to '{id}'.");
+    let units = Units::all().iter().map(Units::as_str).collect();
+    let start = Units::all().iter().position(|u| *u == cfg.units()).unwrap_or(0);
+    let units = Select::new(&t!("init-units"), units).with_starting_cursor(start).prompt()?;
+    cfg.set_value("units", units)?;
+
+    let language = Text::new(&t!("init-language"))
+        .with_default(cfg.language.as_deref().unwrap_or("en"))
+        .with_validator(|lang: &str| {
+            Ok(match Config::default().set_value("language", lang) {
+                Ok(()) => Validation::Valid,
+                Err(err) => Validation::Invalid(err.to_string().into()),
+            })
+        })
+        .prompt()?;
+    cfg.set_value("language", &language)?;
+
+    let home = cfg.default_location().map(ToString::to_string);
+    let (message, help) = (t!("init-location"), t!("init-location-help"));
+    let mut prompt = Text::new(&message).with_help_message(&help);
+    if let Some(home) = &home {
+        prompt = prompt.with_default(home);
+    }
+    let location = prompt
+        .with_validator(|input: &str| {
+            if input.trim().is_empty() {
+                return Ok(Validation::Valid);
+            }
+            Ok(match Location::from(input.trim()).validate() {
+                Ok(()) => Validation::Valid,
+                Err(err) => Validation::Invalid(err.to_string().into()),
+            })
+        })
+        .prompt()?;
+    if !location.trim().is_empty() {
+        cfg.add_location(DEFAULT_LOCATION, Location::from(location.trim()))?;
+    }
+
+    save_config(&cfg)?;
+
+    let path = match CONFIG_FILE.get() {
+        Some(path) => path.clone(),
+        None => Config::config_file_path()?,
+    };
+    println!("{}", t!("init-done", path = path.display()));
+    if cfg.default_location().is_some() {
+        println!("{}", t!("init-try-home"));
+    }
 
     Ok(())
 }
+
+/// Try `provider_id` with the key in `cfg` on a real request. Returns whether to keep
+/// the key: yes if it works, no if the provider rejects it, and the user's call if it
+/// could not be checked (e.g. offline).
+async fn check_api_key(provider_id: ProviderId, cfg: &Config) -> anyhow::Result<bool> {
+    println!("{}", t!("init-key-checking"));
+
+    let provider = provider_from_config(provider_id, cfg)?;
+    let request = WeatherRequest::builder().address("London").build()?;
+    match provider.get_weather(&request).await {
+        Ok(_) => {
+            println!("{}", t!("init-key-ok"));
+            Ok(true)
+        }
+        Err(WeatherError::Unauthorized { .. }) => {
+            println!("{}", t!("init-key-rejected"));
+            Ok(false)
+        }
+        Err(err) => {
+            println!("{}", t!("init-key-unchecked", error = err));
+            Ok(Confirm::new(&t!("init-key-use-anyway")).with_default(true).prompt()?)
+        }
+    }
+}
+
+/// Handle `weather configure <provider> [--add]`.
+fn run_configure(provider: String, add: bool) -> anyhow::Result<()> {
+    let provider_id = ProviderId::try_from(provider.as_str())?;
+
+    let prompt = t!("configure-prompt", provider = provider_id);
+    let placeholder = t!("configure-placeholder");
+    let help = t!("configure-help");
+    let api_key =
+        Text::new(&prompt).with_placeholder(&placeholder).with_help_message(&help).prompt()?;
+
+    let mut cfg = load_unlocked_config()?;
+
+    if !add {
+        cfg.upsert_provider_api_key(provider_id, api_key);
+    } else if !cfg.add_provider_api_key(provider_id, api_key) {
+        println!("{}", t!("configure-key-exists"));
+        return Ok(());
+    }
+    save_config(&cfg)?;
+
+    println!("{}", t!("configure-updated"));
+    let keys = cfg.provider_api_keys(provider_id).len();
+    if keys > 1 {
+        println!("{}", t!("configure-key-count", provider = provider_id, count = keys));
+    }
+    if let Ok(default_id) = cfg.default_provider_id() {
+        println!("{}", t!("configure-current-default", provider = default_id));
+    }
+
+    Ok(())
+}
+
+/// Turn the `show` argument into a location: a saved location by name, coordinates as
+/// given, the place remembered for an address, or, for an ambiguous address in an
+/// interactive terminal, the place the user picks. `provider` is `None` when nothing
+/// may be sent.
+async fn resolve_location(
+    input: String,
+    cfg: &mut Config,
+    provider: Option<&dyn WeatherProvider>,
+) -> anyhow::Result<Location> {
+    let location = match cfg.saved_location(&input) {
+        Some(saved) => saved.clone(),
+        None => Location::from(input),
+    };
+    let address = match location {
+        Location::Address(address) => address,
+        coordinates => return Ok(coordinates),
+    };
+    if let Some(place) = cfg.remembered_place(&address) {
+        return Ok(place.location());
+    }
+
+    let interactive = io::stdin().is_terminal() && io::stdout().is_terminal();
+    let Some(provider) = provider.filter(|_| interactive) else {
+        return Ok(Location::Address(address));
+    };
+
+    // A failed lookup is no reason to give up: the provider geocodes the address itself.
+    let places = provider.geocode(&address).await.unwrap_or_default();
+    if places.len() < 2 {
+        return Ok(Location::Address(address));
+    }
+
+    let place = Select::new(&t!("geocode-select", address = &address), places).prompt()?;
+    if Confirm::new(&t!("geocode-remember", address = &address)).with_default(false).prompt()? {
+        cfg.remember_place(&address, place.clone());
+        if let Err(err) = save_config(cfg) {
+            eprintln!("{}", t!("warning-remember-place", error = err));
+        }
+    }
+
+    Ok(place.location())
+}
+
+/// Approximate location of this machine; only the last detected one if `may_send` is
+/// false.
+async fn locate_here(cfg: &Config, may_send: bool) -> anyhow::Result<Location> {
+    let locator = IpLocator::from_config(cfg)?;
+    let place = if may_send {
+        locator.locate().await?
+    } else {
+        locator.last_known().ok_or_else(|| anyhow::anyhow!(t!("error-here-unknown")))?
+    };
+
+    Ok(place.location())
+}
+
+/// Handle `weather show <location>|--here [--date ...] [--no-cache | --offline]
+/// [--store sqlite]`.
+///
+/// `location` is `None` for `--here`; `cache` is `None` when caching is disabled for
+/// this call.
+async fn run_show(
+    location: Option<String>,
+    when: Option<DateTime<Utc>>,
+    cache: Option<CacheMode>,
+    store: bool,
+    format: &str,
+    units: Option<String>,
+    lang: Option<String>,
+) -> anyhow::Result<()> {
+    let (mut cfg, units, language) = load_for_request(units, lang)?;
+
+    let (id, provider) = guarded_provider(&cfg)?;
+    let provider = match cache {
+        Some(mode) => ShowProvider::Cached(
+            CachedProvider::new(provider, id, ResponseCache::from_config(&cfg)?)
+                .with_mode(mode)
+                .with_stale_fallback(cfg.offline_fallback()),
+        ),
+        None => ShowProvider::Direct(provider),
+    };
+    // Opened up front, so a broken database fails before anything is sent.
+    let store = store.then(ObservationStore::open_default).transpose()?;
+
+    let may_send = cache != Some(CacheMode::Offline);
+    let query = location.clone();
+    let location = match location {
+        Some(input) => {
+            resolve_location(input, &mut cfg, may_send.then_some(provider.as_dyn())).await?
+        }
+        None => locate_here(&cfg, may_send).await?,
+    };
+    let request = build_request(location, when, language, Some(units))?;
+    let query = query.unwrap_or_else(|| request.location.to_string());
+
+    let fetched = provider.fetch(&request).await?;
+
+    let stale = matches!(fetched.source, ResponseSource::Stale { .. });
+    if let ResponseSource::Stale { stored_at } = fetched.source
+        && format == "text"
+    {
+        let age = (Utc::now() - stored_at).to_std().unwrap_or_default();
+        println!("{}", t!("stale-notice", time = stored_at, age = format_age(age)));
+        println!();
+    }
+
+    // Status bars and prompts look up the weather every few minutes, which would bury
+    // the lookups made by hand.
+    if format == "text" {
+        record_history(&cfg, &query, &fetched.response);
+    }
+    // Under the location as typed, which is what `weather stats` is given.
+    if let Some(store) = &store {
+        store.insert(&Location::from(query.as_str()), &fetched.response)?;
+    }
+    let (response, emoji) = (&fetched.response, cfg.emoji());
+    match format {
+        "statusbar" => println!("{}", statusbar_json(response, units, emoji, stale)),
+        "statusbar-plain" => println!("{}", statusbar_text(response, units, emoji)),
+        "oneline" => println!("{}", oneline_text(response, units, emoji, colors_enabled())),
+        _ => print_weather(response, units),
+    }
+
+    Ok(())
+}
+
+/// Handle `weather diff <location> --date <date> [--baseline <date>]`: fetch both dates
+/// and print how the second differs from the first.
+async fn run_diff(
+    location: String,
+    when: Option<DateTime<Utc>>,
+    baseline: Option<DateTime<Utc>>,
+    units: Option<String>,
+    lang: Option<String>,
+) -> anyhow::Result<()> {
+    let (mut cfg, units, language) = load_for_request(units, lang)?;
+
+    let provider = cached_provider(&cfg, Some(CacheMode::Online))?;
+    let location = resolve_location(location, &mut cfg, Some(provider.as_ref())).await?;
+    let before = build_request(location.clone(), baseline, language.clone(), Some(units))?;
+    let after = build_request(location, when, language, Some(units))?;
+
+    let (before, after) = tokio::join!(provider.get_weather(&before), provider.get_weather(&after));
+    print_diff(&before?, &after?, [baseline, when], units);
+
+    Ok(())
+}
+
+/// Handle `weather forecast <location> [--days <days>]`: fetch the forecast series and
+/// print it with its trends.
+async fn run_forecast(
+    location: String,
+    days: u8,
+    units: Option<String>,
+    lang: Option<String>,
+) -> anyhow::Result<()> {
+    let (mut cfg, units, language) = load_for_request(units, lang)?;
+
+    let provider = cached_provider(&cfg, Some(CacheMode::Online))?;
+    let id = cfg.default_provider_id()?;
+    if days > id.forecast_days() {
+        eprintln!("{}", t!("warning-forecast-days", provider = id, days = id.forecast_days()));
+    }
+    let location = resolve_location(location, &mut cfg, Some(provider.as_ref())).await?;
+    let request = build_request(location, None, language, Some(units))?;
+
+    let now = Utc::now();
+    let range = now..now + chrono::Duration::days(days.into());
+    let series = provider.get_forecast_series(&request, range).await?;
+    if series.points.is_empty() {
+        return Err(anyhow::anyhow!(t!("error-forecast-empty")));
+    }
+
+    print_forecast(&series, &Trend::of(&series.points), units);
+
+    Ok(())
+}
+
+/// Handle `weather climate <location> [--month <month>] [--years <years>] [--compare]`:
+/// average the archive into normals and, with `compare`, set the forecast against them.
+async fn run_climate(
+    location: String,
+    month: u32,
+    years: u32,
+    compare: bool,
+    units: Option<String>,
+    lang: Option<String>,
+) -> anyhow::Result<()> {
+    // The archive needs no key; only the forecast to compare with does.
+    let cfg = if compare { load_unlocked_config()? } else { load_config()? };
+    let (mut cfg, units, language) = with_preferences(cfg, units, lang)?;
+
+    let archive = ClimateArchive::from_config(&cfg)?;
+    let location = resolve_location(location, &mut cfg, None).await?;
+    let (name, lat, lon) = match &location {
+        Location::Coordinates { lat, lon } => (location.to_string(), *lat, *lon),
+        Location::Address(address) => {
+            let place = archive.geocode(address).await?;
+            (place.to_string(), place.lat, place.lon)
+        }
+    };
+
+    let normals = archive.normals(lat, lon, month, years).await?;
+
+    let next_day = if compare {
+        let provider = cached_provider(&cfg, Some(CacheMode::Online))?;
+        let request = build_request(location, None, language, Some(units))?;
+        let now = Utc::now();
+        let series = provider.get_forecast_series(&request, now..now + chrono::Duration::days(1));
+        Some(series.await?.points)
+    } else {
+        None
+    };
+
+    print_climate(&name, &normals, next_day.as_deref(), units);
+
+    Ok(())
+}
+
+/// Several locations for `show`, at most this many fetched at once.
+const BATCH_CONCURRENCY: usize = 4;
+
+/// Handle `weather show <location> <location>... [--locations-file <file>]`: fetch all
+/// locations concurrently and compare them in one table.
+async fn run_show_many(
+    locations: Vec<String>,
+    when: Option<DateTime<Utc>>,
+    cache: Option<CacheMode>,
+    store: bool,
+    oneline: bool,
+    units: Option<String>,
+    lang: Option<String>,
+) -> anyhow::Result<()> {
+    let (mut cfg, units, language) = load_for_request(units, lang)?;
+
+    let provider = cached_provider(&cfg, cache)?;
+    let store = store.then(ObservationStore::open_default).transpose()?;
+
+    let may_send = cache != Some(CacheMode::Offline);
+    let mut requests = Vec::with_capacity(locations.len());
+    for input in &locations {
+        let location =
+            resolve_location(input.clone(), &mut cfg, may_send.then_some(provider.as_ref()))
+                .await?;
+        requests.push(build_request(location, when, language.clone(), Some(units))?);
+    }
+
+    let results = get_weather_all(provider.as_ref(), &requests, BATCH_CONCURRENCY).await;
+    for (query, result) in locations.iter().zip(&results) {
+        if let Ok(response) = result {
+            if !oneline {
+                record_history(&cfg, query, response);
+            }
+            if let Some(store) = &store {
+                store.insert(&Location::from(query.as_str()), response)?;
+            }
+        }
+    }
+    if oneline {
+        let color = colors_enabled();
+        for (location, result) in locations.iter().zip(&results) {
+            match result {
+                Ok(response) => println!("{}", oneline_text(response, units, cfg.emoji(), color)),
+                Err(err) => {
+                    let reason = first_line(&err);
+                    println!("{location}: {}", t!("batch-error", error = reason));
+                }
+            }
+        }
+    } else {
+        print_comparison(&locations, &results, units);
+    }
+
+    batch_outcome(&results)
+}
+
+/// Handle `weather dashboard [--watch <interval>]`.
+async fn run_dashboard(
+    watch: Option<Duration>,
+    units: Option<String>,
+    lang: Option<String>,
+) -> anyhow::Result<()> {
+    let (mut cfg, units, language) = load_for_request(units, lang)?;
+
+    let mut names: Vec<String> = cfg.locations.keys().cloned().collect();
+    if names.is_empty() {
+        println!("{}", t!("location-list-empty"));
+        return Ok(());
+    }
+    names.sort();
+
+    // Cached responses keep frequent refreshes from eating into the provider quota.
+    let provider = cached_provider(&cfg, Some(CacheMode::Online))?;
+    let mut requests = Vec::with_capacity(names.len());
+    for name in &names {
+        // No prompts: the dashboard may be left running unattended.
+        let location = resolve_location(name.clone(), &mut cfg, None).await?;
+        requests.push(build_request(location, None, language.clone(), Some(units))?);
+    }
+
+    loop {
+        let results = get_weather_all(provider.as_ref(), &requests, BATCH_CONCURRENCY).await;
+
+        let Some(interval) = watch else {
+            print_comparison(&names, &results, units);
+            return batch_outcome(&results);
+        };
+
+        // Clear the screen and move the cursor home, so each refresh replaces the last.
+        print!("\x1b[2J\x1b[H");
+        let time = timefmt::clock(Utc::now());
+        println!("{}", t!("dashboard-header", time = time, interval = format_age(interval)));
+        println!();
+        print_comparison(&names, &results, units);
+
+        tokio::time::sleep(interval).await;
+    }
+}
+
+/// Handle `weather record <location> [--interval <interval>]`: store an observation
+/// every interval until interrupted. Failed lookups are reported and retried at the
+/// next interval.
+async fn run_record(
+    input: String,
+    interval: Duration,
+    units: Option<String>,
+    lang: Option<String>,
+) -> anyhow::Result<()> {
+    let (mut cfg, units, language) = load_for_request(units, lang)?;
+
+    let store = ObservationStore::open_default()?;
+    // Every observation must be fresh, so the cache is bypassed.
+    let (_, provider) = guarded_provider(&cfg)?;
+    // No prompts: recording is meant to run unattended.
+    let location = resolve_location(input.clone(), &mut cfg, None).await?;
+    let request = build_request(location, None, language, Some(units))?;
+    // Under the location as typed, which is what `weather stats` is given.
+    let stored_as = Location::from(input.as_str());
+
+    println!("{}", t!("record-header", location = &input, interval = format_age(interval)));
+    loop {
+        let time = timefmt::clock(Utc::now());
+        let stored = match provider.get_weather(&request).await {
+            Ok(response) => store.insert(&stored_as, &response).map(|_| response),
+            Err(err) => Err(err),
+        };
+
+        match stored {
+            Ok(response) => println!(
+                "{}",
+                t!(
+                    "record-stored",
+                    time = time,
+                    temperature = format!(
+                        "{:.1} {}",
+                        units.temperature(response.temperature),
+                        units.temperature_symbol()
+                    ),
+                    condition = response.condition
+                )
+            ),
+            Err(err) => {
+                let reason = first_line(&err);
+                eprintln!("{}", t!("warning-record-failed", time = time, error = reason));
+            }
+        }
+
+        tokio::time::sleep(interval).await;
+    }
+}
+
+/// Shortest `weather monitor` interval, to stay well within free-tier rate limits.
+const MIN_MONITOR_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Handle `weather monitor --location <location> [--interval <interval>]
+/// [--notify <channels>] [--once]`: poll alerts and thresholds, and notify about the new
+/// ones.
+async fn run_monitor(
+    input: String,
+    interval: Duration,
+    notify: Vec<String>,
+    once: bool,
+    units: Option<String>,
+    lang: Option<String>,
+) -> anyhow::Result<()> {
+    if interval < MIN_MONITOR_INTERVAL {
+        return Err(anyhow::anyhow!(t!("error-monitor-interval")));
+    }
+
+    let (mut cfg, units, language) = load_for_request(units, lang)?;
+
+    let channels = notify.iter().map(|channel| Channel::try_from(channel.as_str()));
+    let notifier = Notifier::from_config(&cfg, channels.collect::<Result<_, _>>()?)?;
+    let thresholds = cfg.alert_thresholds();
+    let seen = SeenAlerts::new(SeenAlerts::default_path()?);
+    // Alerts must be current, so the cache is bypassed.
+    let (_, provider) = guarded_provider(&cfg)?;
+    // No prompts: monitoring is meant to run unattended.
+    let location = resolve_location(input.clone(), &mut cfg, None).await?;
+    let request = build_request(location, None, language, Some(units))?;
+
+    if !once {
+        println!("{}", t!("monitor-header", location = &input, interval = format_age(interval)));
+    }
+    let mut official_alerts = true;
+    loop {
+        let time = timefmt::clock(Utc::now());
+        let mut alerts = Vec::new();
+        let mut wait = interval;
+        let mut failed = |err: WeatherError| {
+            // Polling again before a rate limit is over only makes it worse.
+            if let WeatherError::RateLimited { retry_after: Some(retry_after), .. } = err {
+                wait = wait.max(retry_after);
+            }
+            let reason = first_line(&err);
+            eprintln!("{}", t!("warning-record-failed", time = &time, error = reason));
+        };
+
+        // Official alerts come with the current weather, so one call covers both.
+        let mut current = None;
+        if official_alerts {
+            match provider.alerts(&request).await {
+                Ok(report) => {
+                    alerts.extend(report.alerts);
+                    current = Some(Ok(report.current));
+                }
+                Err(WeatherError::AlertsUnsupported) if thresholds.is_empty() => {
+                    return Err(anyhow::anyhow!(t!("error-monitor-nothing")));
+                }
+                Err(WeatherError::AlertsUnsupported) => {
+                    official_alerts = false;
+                    eprintln!("{}", t!("warning-monitor-thresholds-only"));
+                }
+                Err(err) => current = Some(Err(err)),
+            }
+        }
+        let current = match current {
+            Some(current) => current,
+            None => provider.get_weather(&request).await,
+        };
+        match current {
+            Ok(response) => alerts.extend(
+                thresholds
+                    .crossed(&response)
+                    .into_iter()
+                    .map(|threshold| threshold_alert(threshold, &response, &thresholds, units)),
+            ),
+            Err(err) => failed(err),
+        }
+
+        let new = seen.take_new(&request.location, alerts);
+        if new.is_empty() && !once {
+            println!("{}", t!("monitor-quiet", time = &time));
+        }
+        for alert in &new {
+            let (event, headline) = (&alert.event, &alert.headline);
+            let line = match alert.ends {
+                Some(ends) => t!(
+                    "monitor-alert-until",
+                    time = &time,
+                    event = event,
+                    headline = headline,
+                    until = timefmt::relative(ends, Utc::now())
+                ),
+                None => t!("monitor-alert", time = &time, event = event, headline = headline),
+            };
+            println!("{line}");
+            for &channel in notifier.channels() {
+                if let Err(err) = notifier.send(channel, &input, alert).await {
+                    let reason = first_line(&err);
+                    eprintln!("{}", t!("warning-notify-failed", channel = channel, error = reason));
+                }
+            }
+        }
+
+        if once {
+            return Ok(());
+        }
+        tokio::time::sleep(wait).await;
+    }
+}
+
+/// An alert for `threshold`, crossed by `response`, in the user's units and language.
+fn threshold_alert(
+    threshold: Threshold,
+    response: &WeatherResponse,
+    thresholds: &AlertThresholds,
+    units: Units,
+) -> WeatherAlert {
+    let temperature = |t| format!("{:.1} {}", units.temperature(t), units.temperature_symbol());
+    let wind_speed = |w| format!("{:.1} {}", units.wind_speed(w), units.wind_speed_symbol());
+    let precipitation =
+        |p| format!("{:.1} {}", units.precipitation(p), units.precipitation_symbol());
+
+    let (event, value, limit) = match threshold {
+        Threshold::WindSpeed => (
+            t!("monitor-wind"),
+            wind_speed(response.wind_speed),
+            thresholds.wind_speed.map(wind_speed),
+        ),
+        Threshold::TemperatureAbove => (
+            t!("monitor-heat"),
+            temperature(response.temperature),
+            thresholds.temperature_above.map(temperature),
+        ),
+        Threshold::TemperatureBelow => (
+            t!("monitor-cold"),
+            temperature(response.temperature),
+            thresholds.temperature_below.map(temperature),
+        ),
+        Threshold::Precipitation => (
+            t!("monitor-precipitation"),
+            precipitation(response.precipitation),
+            thresholds.precipitation.map(precipitation),
+        ),
+    };
+
+    WeatherAlert {
+        id: threshold.alert_id(),
+        event,
+        severity: None,
+        headline: t!("monitor-limit", value = value, limit = limit.unwrap_or_default()),
+        starts: Some(response.observation_time),
+        ends: None,
+    }
+}
+
+/// Handle `weather stats <location> [--last <period>]`.
+fn run_stats(input: String, last: Duration, units: Option<String>) -> anyhow::Result<()> {
+    let cfg = load_config()?;
+    let units = resolve_units(units, &cfg)?;
+
+    // As given to `weather record` or `show --store`, which store it unresolved.
+    let location = Location::from(input.as_str());
+    // A period reaching before the representable past means every observation.
+    let since = chrono::Duration::from_std(last)
+        .ok()
+        .and_then(|last| Utc::now().checked_sub_signed(last))
+        .unwrap_or(DateTime::<Utc>::MIN_UTC);
+    let Some(stats) = ObservationStore::open_default()?.stats(&location, since)? else {
+        println!("{}", t!("stats-empty", location = &input, period = format_age(last)));
+        return Ok(());
+    };
+
+    println!(
+        "{}",
+        t!(
+            "stats-header",
+            location = &input,
+            count = stats.count,
+            first = timefmt::absolute(stats.first),
+            last = timefmt::absolute(stats.last)
+        )
+    );
+    println!();
+
+    let row = |label: String, summary: Summary<f64>, symbol: &str, precision: usize| {
+        let value = |v: f64| format!("{v:.precision$} {symbol}");
+        vec![label, value(summary.min), value(summary.max), value(summary.avg)]
+    };
+    let temperature = |t| units.temperature(t);
+    let symbol = units.temperature_symbol();
+
+    print_table(vec![
+        vec![String::new(), t!("stats-min"), t!("stats-max"), t!("stats-avg")],
+        row(t!("label-temperature"), stats.temperature.map(temperature), symbol, 1),
+        row(t!("label-feels-like"), stats.feels_like.map(temperature), symbol, 1),
+        row(t!("label-humidity"), stats.humidity_pct, "%", 0),
+        row(
+            t!("label-wind-speed"),
+            stats.wind_speed.map(|w| units.wind_speed(w)),
+            units.wind_speed_symbol(),
+            1,
+        ),
+        row(
+            t!("label-pressure"),
+            stats.pressure.map(|p| units.pressure(p)),
+            units.pressure_symbol(),
+            2,
+        ),
+    ]);
+
+    Ok(())
+}
+
+/// Fail if any location of a batch failed; the table has already shown which.
+fn batch_outcome(results: &[Result<WeatherResponse, WeatherError>]) -> anyhow::Result<()> {
+    let failed = results.iter().filter(|r| r.is_err()).count();
+    if failed > 0 {
+        return Err(anyhow::anyhow!(t!("batch-failed", failed = failed, total = results.len())));
+    }
+
+    Ok(())
+}
+
+/// Names of the supported providers, for shell completion.
+fn provider_candidates() -> Vec<CompletionCandidate> {
+    ProviderId::all().iter().map(|id| CompletionCandidate::new(id.as_str())).collect()
+}
+
+/// Names of the saved locations, for shell completion; none if the config is unreadable.
+fn location_candidates() -> Vec<CompletionCandidate> {
+    let Ok(cfg) = load_config() else {
+        return Vec::new();
+    };
+
+    cfg.locations
+        .iter()
+        .map(|(name, location)| {
+            CompletionCandidate::new(name).help(Some(location.to_string().into()))
+        })
+        .collect()
+}
+
+/// Settings accepted by `weather config set`, one per provider where they take one.
+fn setting_candidates() -> Vec<CompletionCandidate> {
+    SETTINGS
+        .iter()
+        .flat_map(|setting| match setting.strip_suffix("<provider>") {
+            Some(prefix) => ProviderId::all().iter().map(|id| format!("{prefix}{id}")).collect(),
+            None => vec![setting.to_string()],
+        })
+        .map(CompletionCandidate::new)
+        .collect()
+}
+
+/// A refresh interval such as `90`, `30s`, `15m`, `1h` or `1d` (bare numbers are seconds).
+fn parse_interval(value: &str) -> Result<Duration, String> {
+    let (number, unit_secs) = match value.char_indices().last() {
+        Some((i, 's')) => (&value[..i], 1),
+        Some((i, 'm')) => (&value[..i], 60),
+        Some((i, 'h')) => (&value[..i], 3_600),
+        Some((i, 'd')) => (&value[..i], 86_400),
+        _ => (value, 1),
+    };
+
+    number
+        .parse::<u64>()
+        .ok()
+        .filter(|&n| n > 0)
+        .and_then(|n| n.checked_mul(unit_secs))
+        .map(Duration::from_secs)
+        .ok_or_else(|| t!("error-interval", value = value))
+}
+
+/// One row per location: the essentials of each response, or why it failed.
+fn print_comparison(
+    locations: &[String],
+    results: &[Result<WeatherResponse, WeatherError>],
+    units: Units,
+) {
+    let header = vec![
+        t!("label-location"),
+        t!("label-temperature"),
+        t!("label-feels-like"),
+        t!("label-humidity"),
+        t!("label-wind-speed"),
+        t!("label-condition"),
+    ];
+    let temperature = |t| format!("{:.1} {}", units.temperature(t), units.temperature_symbol());
+
+    let mut rows = vec![header];
+    for (location, result) in locations.iter().zip(results) {
+        let row = match result {
+            Ok(res) => vec![
+                location.clone(),
+                temperature(res.temperature),
+                temperature(res.feels_like),
+                format!("{} %", res.humidity_pct),
+                format!("{:.1} {}", units.wind_speed(res.wind_speed), units.wind_speed_symbol()),
+                res.condition.clone(),
+            ],
+            Err(err) => {
+                let reason = first_line(&err);
+                vec![location.clone(), t!("batch-error", error = reason)]
+            }
+        };
+        rows.push(row);
+    }
+
+    print_table(rows);
+}
+
+/// Print the values of `before` and `after` side by side with the change between them.
+/// `dates` are the requested dates of both, `None` meaning now.
+fn print_diff(
+    before: &WeatherResponse,
+    after: &WeatherResponse,
+    dates: [Option<DateTime<Utc>>; 2],
+    units: Units,
+) {
+    let date = |date: Option<DateTime<Utc>>| date.map_or(t!("diff-now"), timefmt::absolute);
+    let temperature = |t| format!("{:.1} {}", units.temperature(t), units.temperature_symbol());
+    let wind_speed = |w| format!("{:.1} {}", units.wind_speed(w), units.wind_speed_symbol());
+    let pressure = |p| format!("{:.2} {}", units.pressure(p), units.pressure_symbol());
+    let color = colors_enabled();
+
+    let change = |from: f64, to: f64, precision: usize, symbol: &str| {
+        paint_delta(format_delta(to - from, precision, symbol), color)
+    };
+    let rows = vec![
+        vec![String::new(), date(dates[0]), date(dates[1]), t!("diff-change")],
+        vec![
+            t!("label-temperature"),
+            temperature(before.temperature),
+            temperature(after.temperature),
+            change(
+                units.temperature(before.temperature),
+                units.temperature(after.temperature),
+                1,
+                units.temperature_symbol(),
+            ),
+        ],
+        vec![
+            t!("label-feels-like"),
+            temperature(before.feels_like),
+            temperature(after.feels_like),
+            change(
+                units.temperature(before.feels_like),
+                units.temperature(after.feels_like),
+                1,
+                units.temperature_symbol(),
+            ),
+        ],
+        vec![
+            t!("label-humidity"),
+            format!("{} %", before.humidity_pct),
+            format!("{} %", after.humidity_pct),
+            change(before.humidity_pct.into(), after.humidity_pct.into(), 0, "%"),
+        ],
+        vec![
+            t!("label-wind-speed"),
+            wind_speed(before.wind_speed),
+            wind_speed(after.wind_speed),
+            change(
+                units.wind_speed(before.wind_speed),
+                units.wind_speed(after.wind_speed),
+                1,
+                units.wind_speed_symbol(),
+            ),
+        ],
+        vec![
+            t!("label-pressure"),
+            pressure(before.pressure),
+            pressure(after.pressure),
+            change(
+                units.pressure(before.pressure),
+                units.pressure(after.pressure),
+                2,
+                units.pressure_symbol(),
+            ),
+        ],
+        vec![t!("label-condition"), before.condition.clone(), after.condition.clone()],
+    ];
+
+    println!("{}", t!("diff-header", location = &after.location_name));
+    println!();
+    print_table(rows);
+}
+
+/// Print `normals` and, if there is a `forecast` to compare with, its highest and lowest
+/// temperature next to them.
+fn print_climate(
+    location: &str,
+    normals: &ClimateNormals,
+    forecast: Option<&[WeatherPoint]>,
+    units: Units,
+) {
+    let temperature = |t| format!("{:.1} {}", units.temperature(t), units.temperature_symbol());
+    let color = colors_enabled();
+    let change = |from: Temperature, to: Temperature| {
+        let delta = units.temperature(to) - units.temperature(from);
+        paint_delta(format_delta(delta, 1, units.temperature_symbol()), color)
+    };
+
+    let forecast = forecast.filter(|series| !series.is_empty()).map(|series| {
+        let temperatures = series.iter().map(|step| step.temperature.celsius());
+        let high = temperatures.clone().fold(f64::MIN, f64::max);
+        let low = temperatures.fold(f64::MAX, f64::min);
+        (Temperature::from_celsius(high), Temperature::from_celsius(low))
+    });
+
+    let mut rows = vec![vec![String::new(), t!("climate-normal")]];
+    let mut high = vec![t!("climate-high"), temperature(normals.high)];
+    let mut low = vec![t!("climate-low"), temperature(normals.low)];
+    if let Some((next_high, next_low)) = forecast {
+        rows[0].extend([t!("climate-next-day"), t!("diff-change")]);
+        high.extend([temperature(next_high), change(normals.high, next_high)]);
+        low.extend([temperature(next_low), change(normals.low, next_low)]);
+    }
+    rows.extend([
+        high,
+        low,
+        vec![t!("climate-record-high"), temperature(normals.record_high)],
+        vec![t!("climate-record-low"), temperature(normals.record_low)],
+        vec![
+            t!("label-precipitation"),
+            format!(
+                "{:.1} {}",
+                units.precipitation(normals.precipitation),
+                units.precipitation_symbol()
+            ),
+        ],
+        vec![t!("climate-wet-days"), format!("{:.1}", normals.wet_days)],
+    ]);
+
+    println!(
+        "{}",
+        t!(
+            "climate-header",
+            location = location,
+            month = normals.month,
+            first = normals.first_year,
+            last = normals.last_year
+        )
+    );
+    println!();
+    print_table(rows);
+}
+
+/// Print the steps of a forecast series in a table, followed by the trends in it.
+fn print_forecast(series: &WeatherSeries, trend: &Trend, units: Units) {
+    let precipitation = |p: Precipitation| match units.precipitation(p) {
+        0.0 => String::new(),
+        amount => format!("{amount:.1} {}", units.precipitation_symbol()),
+    };
+
+    let mut rows = vec![vec![
+        String::new(),
+        t!("label-temperature"),
+        t!("label-condition"),
+        t!("label-precipitation"),
+    ]];
+    rows.extend(series.points.iter().map(|step| {
+        vec![
+            timefmt::weekday_time(step.time),
+            format!("{:.1} {}", units.temperature(step.temperature), units.temperature_symbol()),
+            step.condition.clone(),
+            precipitation(step.precipitation),
+        ]
+    }));
+
+    println!("{}", t!("forecast-header", location = &series.location_name));
+    println!();
+    print_table(rows);
+    println!();
+
+    if let Some(per_day) = trend.warming_per_day {
+        // A difference of temperatures, so without the offset of the scale.
+        let change = units.temperature(Temperature::from_celsius(per_day))
+            - units.temperature(Temperature::from_celsius(0.0));
+        let change = format!("{:.1} {}", change.abs(), units.temperature_symbol());
+        let line = match format_delta(per_day, 1, "").chars().next() {
+            Some('↑') => t!("forecast-warming", change = change),
+            Some('↓') => t!("forecast-cooling", change = change),
+            _ => t!("forecast-temperature-steady"),
+        };
+        println!("{line}");
+    }
+
+    if let Some(tendency) = trend.pressure {
+        let line = match tendency {
+            PressureTendency::Rising => t!("forecast-pressure-rising"),
+            PressureTendency::Steady => t!("forecast-pressure-steady"),
+            PressureTendency::Falling => t!("forecast-pressure-falling"),
+            PressureTendency::FallingFast => t!("forecast-pressure-storm"),
+        };
+        println!("{line}");
+    }
+
+    if trend.precipitation.is_empty() {
+        println!("{}", t!("forecast-dry"));
+    }
+    for spell in &trend.precipitation {
+        let amount =
+            format!("{:.1} {}", units.precipitation(spell.total), units.precipitation_symbol());
+        println!(
+            "{}",
+            t!(
+                "forecast-precipitation",
+                start = timefmt::weekday_time(spell.start),
+                end = timefmt::weekday_time(spell.end),
+                amount = amount
+            )
+        );
+    }
+}
+
+/// A change such as `↑ +2.7 °C` or `↓ -1 %`, or `= 0.0 °C` if it rounds away.
+fn format_delta(delta: f64, precision: usize, symbol: &str) -> String {
+    let rounded = format!("{delta:.precision$}").parse().unwrap_or(0.0);
+    if rounded == 0.0 {
+        return format!("= {:.precision$} {symbol}", 0.0);
+    }
+
+    let arrow = if rounded > 0.0 { '↑' } else { '↓' };
+    format!("{arrow} {delta:+.precision$} {symbol}")
+}
+
+/// A [`format_delta`] change in red if it is up and blue if it is down, if `color`.
+fn paint_delta(delta: String, color: bool) -> String {
+    match delta.chars().next() {
+        Some('↑') if color => format!("\x1b[31m{delta}\x1b[0m"),
+        Some('↓') if color => format!("\x1b[34m{delta}\x1b[0m"),
+        _ => delta,
+    }
+}
+
+/// Whether to color output: only on a terminal, and never with `NO_COLOR` set.
+fn colors_enabled() -> bool {
+    io::stdout().is_terminal() && env::var_os("NO_COLOR").is_none_or(|value| value.is_empty())
+}
+
+/// Print `rows` (the first one being the header) in aligned columns. Rows shorter than
+/// the header, such as error messages, only set the width of the first column.
+fn print_table(rows: Vec<Vec<String>>) {
+    let columns = rows[0].len();
+    let mut widths = vec![0; columns];
+    for row in rows.iter().filter(|row| row.len() == columns) {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(cell.chars().count());
+        }
+    }
+    widths[0] = rows.iter().map(|row| row[0].chars().count()).max().unwrap_or(0);
+
+    for row in rows {
+        let line: Vec<_> =
+            row.iter().zip(&widths).map(|(cell, &w)| format!("{cell:<w$}")).collect();
+        println!("{}", line.join("  ").trim_end());
+    }
+}
+
+/// Append a lookup to the history journal, unless turned off. A journal that cannot be
+/// written is reported, but does not fail the lookup.
+fn record_history(cfg: &Config, query: &str, response: &WeatherResponse) {
+    if !cfg.history() {
+        return;
+    }
+
+    let recorded = History::default_path()
+        .and_then(|path| History::new(path).record(&HistoryEntry::new(query, response)));
+    if let Err(err) = recorded {
+        eprintln!("{}", t!("warning-history", error = err));
+    }
+}
+
+/// Locations listed in `path`, one per line; blank lines and `#` comments are skipped.
+fn read_locations_file(path: &Path) -> anyhow::Result<Vec<String>> {
+    let contents = fs::read_to_string(path).map_err(|e| {
+        anyhow::anyhow!(t!("error-locations-file", path = path.display(), error = e))
+    })?;
+
+    Ok(contents
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(str::to_string)
+        .collect())
+}
+
+/// The default provider from config, skipped while its circuit is open.
+fn guarded_provider(cfg: &Config) -> anyhow::Result<(ProviderId, Box<dyn WeatherProvider>)> {
+    let id = cfg.default_provider_id()?;
+    let provider = CircuitBreakerProvider::new(
+        default_provider_from_config(cfg)?,
+        id,
+        CircuitBreaker::from_config(cfg)?,
+    );
+
+    Ok((id, Box::new(provider)))
+}
+
+/// [`guarded_provider`] behind the response cache, unless `cache` is `None`.
+fn cached_provider(
+    cfg: &Config,
+    cache: Option<CacheMode>,
+) -> anyhow::Result<Box<dyn WeatherProvider>> {
+    let (id, provider) = guarded_provider(cfg)?;
+    let Some(mode) = cache else {
+        return Ok(provider);
+    };
+
+    Ok(Box::new(
+        CachedProvider::new(provider, id, ResponseCache::from_config(cfg)?)
+            .with_mode(mode)
+            .with_stale_fallback(cfg.offline_fallback()),
+    ))
+}
+
+/// The provider `show` asks, behind the response cache unless caching is disabled.
+enum ShowProvider {
+    Cached(CachedProvider),
+    Direct(Box<dyn WeatherProvider>),
+}
+
+impl ShowProvider {
+    fn as_dyn(&self) -> &dyn WeatherProvider {
+        match self {
+            Self::Cached(provider) => provider,
+            Self::Direct(provider) => provider.as_ref(),
+        }
+    }
+
+    async fn fetch(&self, request: &WeatherRequest) -> Result<CachedFetch, WeatherError> {
+        match self {
+            Self::Cached(provider) => provider.fetch(request).await,
+            Self::Direct(provider) => Ok(CachedFetch {
+                response: provider.get_weather(request).await?,
+                source: ResponseSource::Provider,
+            }),
+        }
+    }
+}
+
+/// Handle `weather show <location>|--here --dry-run`: resolve the request, send nothing.
+async fn run_show_dry_run(
+    location: Option<String>,
+    when: Option<DateTime<Utc>>,
+    lang: Option<String>,
+) -> anyhow::Result<()> {
+    let mut cfg = load_unlocked_config()?;
+    let language = request_language(lang, &cfg);
+
+    let provider = default_provider_from_config(&cfg)?;
+    let location = match location {
+        Some(input) => resolve_location(input, &mut cfg, None).await?,
+        None => locate_here(&cfg, false).await?,
+    };
+    let plan = provider.plan(&build_request(location, when, language, None)?)?;
+
+    let date = match plan.date {
+        DateRequest::Current => t!("dry-run-date-current"),
+        DateRequest::Future(dt) => t!("dry-run-date-forecast", time = dt),
+        DateRequest::Past(dt) => t!("dry-run-date-history", time = dt),
+    };
+
+    let rows = [
+        (t!("label-provider"), plan.provider.to_string()),
+        (t!("dry-run-date"), date),
+        ("URL".to_string(), format!("GET {}", plan.url)),
+    ];
+    let width = rows.iter().map(|(label, _)| label.chars().count()).max().unwrap_or(0) + 2;
+
+    println!("{}", t!("dry-run-header"));
+    println!();
+    for (label, value) in rows {
+        println!("{:<width$}{value}", format!("{label}:"));
+    }
+    println!("{}:", t!("dry-run-query"));
+    for (name, value) in &plan.query {
+        println!("  {name} = {value}");
+    }
+
+    Ok(())
+}
+
+fn run_provider_list() -> anyhow::Result<()> {
+    let cfg = load_unlocked_config()?;
+
+    let default_id = cfg.default_provider_id().ok(); // ignore error, might be None
+
+    println!("{}", t!("provider-list-header"));
+    println!();
+
+    for id in ProviderId::all() {
+        let name = id.as_str();
+        let configured = cfg.is_provider_configured(*id);
+        let is_default = default_id == Some(*id);
+
+        let mut status = if configured {
+            if is_default {
+                t!("provider-status-default")
+            } else {
+                t!("provider-status-configured")
+            }
+        } else {
+            t!("provider-status-not-configured")
+        };
+        let keys = cfg.provider_api_keys(*id).len();
+        if keys > 1 {
+            status = format!("{status} ({})", t!("provider-key-count", count = keys));
+        }
+
+        println!("  - {:<12}  {}", name, status);
+    }
+
+    println!();
+    println!("{}", t!("provider-list-hint-configure"));
+    println!("{}", t!("provider-list-hint-use"));
+
+    Ok(())
+}
+
+fn run_provider_use(provider: String) -> anyhow::Result<()> {
+    let id = ProviderId::try_from(provider.as_str())?;
+
+    let mut cfg = load_unlocked_config()?;
+
+    if !cfg.is_provider_configured(id) {
+        return Err(anyhow::anyhow!(t!("provider-use-not-configured", provider = id)));
+    }
+
+    cfg.set_default_provider(id);
+    save_config(&cfg)?;
+
+    println!("{}", t!("provider-use-done", provider = id));
+
+    Ok(())
+}
+
+/// Handle `weather location add <name> <location>`.
+fn run_location_add(name: String, location: String) -> anyhow::Result<()> {
+    let mut cfg = load_config()?;
+
+    cfg.add_location(&name, Location::from(location))?;
+    save_config(&cfg)?;
+
+    println!("{}", t!("location-add-done", name = name));
+
+    Ok(())
+}
+
+/// Handle `weather location list`.
+fn run_location_list() -> anyhow::Result<()> {
+    let cfg = load_config()?;
+
+    if cfg.locations.is_empty() {
+        println!("{}", t!("location-list-empty"));
+        return Ok(());
+    }
+
+    let mut locations: Vec<_> = cfg.locations.iter().collect();
+    locations.sort_by(|a, b| a.0.cmp(b.0));
+    let width = locations.iter().map(|(name, _)| name.chars().count()).max().unwrap_or(0);
+
+    println!("{}", t!("location-list-header"));
+    println!();
+    for (name, location) in locations {
+        println!("  - {name:<width$}  {location}");
+    }
+
+    Ok(())
+}
+
+/// Handle `weather location remove <name>`.
+fn run_location_remove(name: String) -> anyhow::Result<()> {
+    let mut cfg = load_config()?;
+
+    if cfg.remove_location(&name).is_none() {
+        return Err(anyhow::anyhow!(t!("location-remove-unknown", name = name)));
+    }
+    save_config(&cfg)?;
+
+    println!("{}", t!("location-remove-done", name = name));
+
+    Ok(())
+}
+
+/// Handle `weather config set <key> <value>`.
+fn run_config_set(key: String, value: String) -> anyhow::Result<()> {
+    let mut cfg = load_config()?;
+
+    cfg.set_value(&key, &value)?;
+    save_config(&cfg)?;
+
+    println!("{}", t!("config-set-done", key = key));
+    warn_insecure_tls(&cfg);
+
+    Ok(())
+}
+
+/// Handle `weather config show`.
+fn run_config_show() -> anyhow::Result<()> {
+    let cfg = load_config()?;
+
+    print!("{}", cfg.redacted().to_toml()?);
+
+    Ok(())
+}
+
+/// Handle `weather config export`.
+fn run_config_export() -> anyhow::Result<()> {
+    let cfg = load_config()?;
+
+    print!("{}", cfg.to_toml()?);
+    if !cfg.is_encrypted() && !cfg.providers.is_empty() {
+        eprintln!("{}", t!("warning-export-plaintext"));
+    }
+
+    Ok(())
+}
+
+/// Handle `weather config import <path> [--overwrite]`.
+fn run_config_import(path: &Path, overwrite: bool) -> anyhow::Result<()> {
+    let contents = fs::read_to_string(path)
+        .map_err(|e| anyhow::anyhow!(t!("error-import-file", path = path.display(), error = e)))?;
+    let mut imported = Config::from_toml(&contents)?;
+
+    // Replacing needs no keys: encrypted ones are written back as they are.
+    if overwrite {
+        save_config(&imported)?;
+        println!("{}", t!("config-replaced", path = path.display()));
+        return Ok(());
+    }
+
+    let mut cfg = load_unlocked_config()?;
+    if imported.is_locked() {
+        // Most likely a backup of this very config, encrypted the same way.
+        let same = cfg.passphrase.clone().map(|passphrase| imported.unlock(passphrase));
+        if !matches!(same, Some(Ok(()))) {
+            imported.unlock(ask_passphrase()?)?;
+        }
+    }
+    cfg.merge(imported)?;
+    save_config(&cfg)?;
+
+    println!("{}", t!("config-imported", path = path.display()));
+
+    Ok(())
+}
+
+/// Handle `weather config encrypt`.
+fn run_config_encrypt() -> anyhow::Result<()> {
+    let mut cfg = load_unlocked_config()?;
+    if cfg.is_encrypted() {
+        println!("{}", t!("encrypt-already"));
+        return Ok(());
+    }
+
+    let passphrase = match env::var("WEATHER_PASSPHRASE") {
+        Ok(passphrase) => passphrase,
+        Err(_) => Password::new(&t!("encrypt-prompt"))
+            .with_custom_confirmation_message(&t!("encrypt-confirm"))
+            .with_custom_confirmation_error_message(&t!("encrypt-mismatch"))
+            .prompt()?,
+    };
+    if passphrase.is_empty() {
+        return Err(anyhow::anyhow!(t!("error-empty-passphrase")));
+    }
+
+    cfg.encrypt_providers(passphrase.into())?;
+    save_config(&cfg)?;
+
+    println!("{}", t!("encrypt-done"));
+
+    Ok(())
+}
+
+/// Handle `weather config decrypt`.
+fn run_config_decrypt() -> anyhow::Result<()> {
+    let mut cfg = load_unlocked_config()?;
+    if !cfg.is_encrypted() {
+        println!("{}", t!("decrypt-already"));
+        return Ok(());
+    }
+
+    cfg.decrypt_providers()?;
+    save_config(&cfg)?;
+    end_passphrase_session()?;
+
+    println!("{}", t!("decrypt-done"));
+
+    Ok(())
+}
+
+/// Handle `weather config unlock [--for <duration>]`.
+fn run_config_unlock(duration: Duration) -> anyhow::Result<()> {
+    let mut cfg = load_config()?;
+    if !cfg.is_encrypted() {
+        println!("{}", t!("decrypt-already"));
+        return Ok(());
+    }
+
+    // Checked first, so nobody types a passphrase that cannot be kept.
+    let session = PassphraseSession::new(PassphraseSession::default_path()?);
+    PassphraseSession::expires_at(duration)?;
+    let passphrase = ask_passphrase()?;
+    // Only a passphrase that works is worth remembering.
+    cfg.unlock(passphrase.clone())?;
+    session.start(&passphrase, duration)?;
+
+    println!("{}", t!("unlock-done", duration = format_age(duration)));
+
+    Ok(())
+}
+
+/// Handle `weather config lock`.
+fn run_config_lock() -> anyhow::Result<()> {
+    end_passphrase_session()?;
+
+    println!("{}", t!("lock-done"));
+
+    Ok(())
+}
+
+/// Forget the passphrase remembered by `weather config unlock`, if this system can
+/// remember one at all.
+fn end_passphrase_session() -> anyhow::Result<()> {
+    match PassphraseSession::default_path() {
+        Ok(path) => PassphraseSession::new(path).end().map(drop).map_err(Into::into),
+        Err(WeatherError::NoSessionDir) => Ok(()),
+        Err(err) => Err(err.into()),
+    }
+}
+
+/// Handle `weather completions <shell>`: the script asks this binary for completions,
+/// see [`CompleteEnv`].
+fn run_completions(shell: &str) -> anyhow::Result<()> {
+    let shells = Shells::builtins();
+    let completer = shells.completer(shell).expect("clap checked the shell name");
+    let exe = env::current_exe()?;
+    let bin = exe.file_name().map_or("weather".into(), |name| name.to_string_lossy());
+
+    completer.write_registration(
+        COMPLETE_VAR,
+        "weather",
+        &bin,
+        &exe.to_string_lossy(),
+        &mut io::stdout(),
+    )?;
+
+    Ok(())
+}
+
+/// Handle `weather cache clear`.
+fn run_cache_clear() -> anyhow::Result<()> {
+    let cfg = load_config()?;
+    let removed = ResponseCache::from_config(&cfg)?.clear()?;
+
+    println!("{}", t!("cache-cleared", count = removed));
+
+    Ok(())
+}
+
+/// Handle `weather usage`.
+fn run_usage() -> anyhow::Result<()> {
+    let cfg = load_config()?;
+    let log = UsageLog::new(UsageLog::default_path()?);
+    let now = Utc::now();
+
+    // Only the current month is ever reported; keep the log from growing forever.
+    log.prune(now)?;
+    let report = log.report(&cfg, now)?;
+
+    let count = |used: u64, limit: Option<u64>| match limit {
+        Some(limit) => format!("{used} / {limit}"),
+        None => used.to_string(),
+    };
+
+    // A provider used with several API keys has a row for each.
+    let name = |usage: &ProviderUsage| match &usage.key {
+        Some(key) => format!("{} {key}", usage.provider),
+        None => usage.provider.to_string(),
+    };
+    let width =
+        report.iter().map(|usage| name(usage).chars().count()).max().unwrap_or_default().max(12);
+
+    println!("{}", t!("usage-header"));
+    println!();
+    println!("  {:<width$}  {:>16}  {:>20}", "", t!("usage-today"), t!("usage-this-month"));
+
+    for usage in &report {
+        println!(
+            "  {:<width$}  {:>16}  {:>20}",
+            name(usage),
+            count(usage.today, usage.quota.daily),
+            count(usage.this_month, usage.quota.monthly)
+        );
+    }
+
+    for usage in &report {
+        if let (true, Some(limit)) = (usage.near_daily_cap(), usage.quota.daily) {
+            eprintln!(
+                "{}",
+                t!(
+                    "usage-warning-daily",
+                    name = name(usage),
+                    provider = usage.provider,
+                    used = usage.today,
+                    limit = limit
+                )
+            );
+        }
+        if let (true, Some(limit)) = (usage.near_monthly_cap(), usage.quota.monthly) {
+            eprintln!(
+                "{}",
+                t!(
+                    "usage-warning-monthly",
+                    name = name(usage),
+                    provider = usage.provider,
+                    used = usage.this_month,
+                    limit = limit
+                )
+            );
+        }
+    }
+
+    Ok(())
+}
+
+/// Handle `weather history [--last <n>] [--clear]`.
+fn run_history(last: usize, clear: bool, units: Option<String>) -> anyhow::Result<()> {
+    let cfg = load_config()?;
+    let units = resolve_units(units, &cfg)?;
+    let history = History::new(History::default_path()?);
+
+    if clear {
+        let removed = history.clear()?;
+        println!("{}", t!("history-cleared", count = removed));
+        return Ok(());
+    }
+
+    let entries = history.last(last)?;
+    if entries.is_empty() {
+        println!("{}", t!("history-empty"));
+    } else {
+        let mut rows = vec![vec![
+            t!("history-time"),
+            t!("label-location"),
+            t!("label-temperature"),
+            t!("label-condition"),
+            t!("label-provider"),
+        ]];
+        for entry in entries {
+            rows.push(vec![
+                timefmt::absolute(entry.at),
+                entry.query,
+                format!(
+                    "{:.1} {}",
+                    units.temperature(entry.temperature),
+                    units.temperature_symbol()
+                ),
+                entry.condition,
+                entry.provider,
+            ]);
+        }
+        print_table(rows);
+    }
+
+    if !cfg.history() {
+        eprintln!("{}", t!("history-disabled"));
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn show_takes_negative_coordinates() {
+        let parse = |args: &[&str]| match Cli::try_parse_from(args).map(|cli| cli.command) {
+            Ok(Command::Show { location, more_locations, date, offline, .. }) => {
+                (location.into_iter().chain(more_locations).collect::<Vec<_>>(), date, offline)
+            }
+            other => panic!("{args:?} parsed as {other:?}"),
+        };
+
+        let (locations, date, offline) =
+            parse(&["weather", "show", "-33.92,18.42", "--date", "2025-12-04T12:00:00Z"]);
+        assert_eq!(locations, ["-33.92,18.42"]);
+        assert_eq!(date.as_deref(), Some("2025-12-04T12:00:00Z"));
+        assert!(!offline);
+
+        let (locations, _, offline) = parse(&["weather", "show", "-1,-2", "Kyiv", "--offline"]);
+        assert_eq!(locations, ["-1,-2", "Kyiv"]);
+        assert!(offline);
+
+        let (locations, _, _) = parse(&["weather", "show", "Kyiv", "--", "-33.92,18.42"]);
+        assert_eq!(locations, ["Kyiv", "-33.92,18.42"]);
+    }
+
+    #[test]
+    fn intervals_take_an_optional_unit() {
+        assert_eq!(parse_interval("90"), Ok(Duration::from_secs(90)));
+        assert_eq!(parse_interval("30s"), Ok(Duration::from_secs(30)));
+        assert_eq!(parse_interval("15m"), Ok(Duration::from_secs(900)));
+        assert_eq!(parse_interval("1h"), Ok(Duration::from_secs(3_600)));
+        assert_eq!(parse_interval("2d"), Ok(Duration::from_secs(172_800)));
+
+        for invalid in ["", "0", "m", "-5m", "1.5h", "5w"] {
+            assert!(parse_interval(invalid).is_err(), "{invalid}");
+        }
+    }
+
+    #[test]
+    fn deltas_point_up_or_down() {
+        assert_eq!(format_delta(2.66, 1, "°C"), "↑ +2.7 °C");
+        assert_eq!(format_delta(-17.0, 0, "%"), "↓ -17 %");
+        assert_eq!(format_delta(-0.04, 1, "m/s"), "= 0.0 m/s");
+    }
+
+    #[test]
+    fn ages_skip_empty_units() {
+        assert_eq!(format_age(Duration::from_secs(45)), "45s");
+        assert_eq!(format_age(Duration::from_secs(3 * 3_600 + 5 * 60)), "3h 5m");
+        assert_eq!(format_age(Duration::from_secs(3_600)), "1h");
+        assert_eq!(format_age(Duration::from_secs(7 * 86_400)), "7d");
+    }
+
+    #[test]
+    fn compact_output_composes_units_icons_and_classes() {
+        let response = WeatherResponse {
+            provider: "openweather".to_string(),
+            location_name: "Kyiv, UA".to_string(),
+            temperature: Temperature::from_celsius(-0.3),
+            feels_like: Temperature::from_celsius(-4.0),
+            condition: "light snow".to_string(),
+            humidity_pct: 81,
+            wind_speed: weather_core::WindSpeed::from_mps(4.2),
+            wind_direction: Some(weather_core::WindDirection::from_degrees(310.0)),
+            pressure: weather_core::Pressure::from_hpa(1012.0),
+            precipitation: Precipitation::from_mm(0.4),
+            observation_time: Utc::now(),
+        };
+
+        assert_eq!(statusbar_text(&response, Units::Metric, true), "❄ 0°C");
+        assert_eq!(statusbar_text(&response, Units::Imperial, false), "31°F");
+
+        let json: serde_json::Value =
+            serde_json::from_str(&statusbar_json(&response, Units::Metric, true, true)).unwrap();
+        assert_eq!(json["class"], serde_json::json!(["snow", "freezing", "stale"]));
+        assert!(json["tooltip"].as_str().unwrap().contains("Kyiv, UA"));
+
+        let line = oneline_text(&response, Units::Metric, true, false);
+        assert!(line.starts_with("Kyiv, UA: ❄ -0.3°C (") && line.ends_with("81%, 4.2 m/s NW"));
+        assert!(line.contains("-4.0"), "{line}");
+        let line = oneline_text(&response, Units::Imperial, false, false);
+        assert!(line.starts_with("Kyiv, UA: 31.5°F (") && line.ends_with("9.4 mph NW"));
+    }
+}
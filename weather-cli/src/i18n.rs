@@ -0,0 +1,191 @@
+//! Localized user-facing text.
+//!
+//! Messages live in Fluent catalogs under `weather-cli/locales/<lang>.ftl` and are
+//! compiled into the binary. To add a locale, drop a new `.ftl` file next to the
+//! existing ones and register it in [`CATALOGS`]; ids missing from it fall back to
+//! English.
+
+use std::fmt::Display;
+use std::sync::OnceLock;
+
+use fluent_bundle::concurrent::FluentBundle;
+use fluent_bundle::types::FluentNumber;
+use fluent_bundle::{FluentArgs, FluentResource, FluentValue};
+use unic_langid::LanguageIdentifier;
+
+/// Built-in catalogs, English first (it is the fallback for every other locale).
+const CATALOGS: &[(&str, &str)] =
+    &[("en", include_str!("../locales/en.ftl")), ("uk", include_str!("../locales/uk.ftl"))];
+
+static LOCALIZER: OnceLock<Localizer> = OnceLock::new();
+
+/// Format a localized message, optionally with named arguments.
+///
+/// ```ignore
+/// t!("configure-updated");
+/// t!("provider-use-done", provider = id);
+/// ```
+macro_rules! t {
+    ($id:literal) => {
+        $crate::i18n::message($id, None)
+    };
+    ($id:literal, $($name:ident = $value:expr),+ $(,)?) => {{
+        let mut args = fluent_bundle::FluentArgs::new();
+        $( args.set(stringify!($name), $crate::i18n::arg($value)); )+
+        $crate::i18n::message($id, Some(&args))
+    }};
+}
+
+struct Localizer {
+    bundle: FluentBundle<FluentResource>,
+    fallback: FluentBundle<FluentResource>,
+}
+
+impl Localizer {
+    fn new(requested: Option<&str>) -> Self {
+        let lang = detect_language(requested);
+
+        Self { bundle: bundle_for(lang), fallback: bundle_for(CATALOGS[0].0) }
+    }
+
+    fn format(&self, id: &str, args: Option<&FluentArgs>) -> String {
+        [&self.bundle, &self.fallback]
+            .into_iter()
+            .find_map(|bundle| {
+                let pattern = bundle.get_message(id)?.value()?;
+                let mut errors = Vec::new();
+                Some(bundle.format_pattern(pattern, args, &mut errors).into_owned())
+            })
+            .unwrap_or_else(|| id.to_string())
+    }
+}
+
+/// Select the UI language: `--lang` first, then the usual locale env vars.
+///
+/// Must be called before the first message is formatted; later calls are ignored.
+pub fn init(requested: Option<&str>) {
+    let _ = LOCALIZER.set(Localizer::new(requested));
+}
+
+/// Look up a message by id. Prefer the [`t!`] macro.
+pub fn message(id: &str, args: Option<&FluentArgs>) -> String {
+    LOCALIZER.get_or_init(|| Localizer::new(None)).format(id, args)
+}
+
+/// Convert a message argument, keeping numbers numeric so plural selectors work.
+pub fn arg(value: impl Display) -> FluentValue<'static> {
+    let text = value.to_string();
+
+    match text.parse::<FluentNumber>() {
+        Ok(number) => number.into(),
+        Err(_) => text.into(),
+    }
+}
+
+fn bundle_for(lang: &str) -> FluentBundle<FluentResource> {
+    let source = CATALOGS
+        .iter()
+        .find(|(code, _)| *code == lang)
+        .map(|(_, source)| *source)
+        .unwrap_or(CATALOGS[0].1);
+
+    let langid: LanguageIdentifier = lang.parse().unwrap_or_default();
+    let resource =
+        FluentResource::try_new(source.to_string()).unwrap_or_else(|(resource, _errors)| resource);
+
+    let mut bundle = FluentBundle::new_concurrent(vec![langid]);
+    // Unicode isolation marks show up as garbage in most terminals.
+    bundle.set_use_isolating(false);
+    bundle.add_resource(resource).expect("built-in catalogs have unique message ids");
+
+    bundle
+}
+
+/// Language code from `LC_ALL`, `LC_MESSAGES` or `LANG` (in POSIX precedence order),
+/// e.g. `uk` for `uk_UA.UTF-8`. `None` for unset or the `C`/`POSIX` locales.
+pub fn system_language() -> Option<String> {
+    let raw = ["LC_ALL", "LC_MESSAGES", "LANG"]
+        .into_iter()
+        .filter_map(|var| std::env::var(var).ok())
+        .find(|value| !value.is_empty())?;
+
+    let tag = raw.split(['.', '@', '_', '-']).next().unwrap_or_default();
+    if tag.is_empty() || tag == "C" || tag == "POSIX" {
+        return None;
+    }
+
+    Some(tag.to_lowercase())
+}
+
+/// Resolve a supported catalog code from an explicit request or the system locale.
+fn detect_language(requested: Option<&str>) -> &'static str {
+    let raw = requested.map(str::to_string).or_else(system_language).unwrap_or_default();
+
+    supported_language(&raw).unwrap_or(CATALOGS[0].0)
+}
+
+/// Map a locale string such as `uk_UA.UTF-8` or `uk-UA` onto a catalog code.
+fn supported_language(raw: &str) -> Option<&'static str> {
+    let tag = raw.split(['.', '@']).next().unwrap_or_default().replace('_', "-");
+    let langid: LanguageIdentifier = tag.parse().ok()?;
+
+    CATALOGS.iter().map(|(code, _)| *code).find(|code| *code == langid.language.as_str())
+}
+
+#[cfg(test)]
+mod tests {
+    use fluent_syntax::ast::Entry;
+    use fluent_syntax::parser;
+
+    use super::*;
+
+    fn message_ids(source: &str) -> Vec<String> {
+        let resource = parser::parse(source).expect("catalog must parse");
+        resource
+            .body
+            .into_iter()
+            .filter_map(|entry| match entry {
+                Entry::Message(msg) => Some(msg.id.name.to_string()),
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn locale_strings_are_normalized() {
+        assert_eq!(supported_language("uk_UA.UTF-8"), Some("uk"));
+        assert_eq!(supported_language("uk-UA"), Some("uk"));
+        assert_eq!(supported_language("en_US.UTF-8"), Some("en"));
+        assert_eq!(supported_language("C"), None);
+        assert_eq!(supported_language("fr_FR"), None);
+    }
+
+    #[test]
+    fn every_catalog_defines_all_english_messages() {
+        let english = message_ids(CATALOGS[0].1);
+
+        for (code, source) in &CATALOGS[1..] {
+            let ids = message_ids(source);
+            for id in &english {
+                assert!(ids.contains(id), "locale '{code}' is missing message '{id}'");
+            }
+        }
+    }
+
+    #[test]
+    fn messages_are_formatted_with_arguments() {
+        let localizer = Localizer::new(Some("uk"));
+        let mut args = FluentArgs::new();
+        args.set("key", "units");
+
+        assert_eq!(localizer.format("config-set-done", Some(&args)), "Параметр 'units' оновлено.");
+        assert_eq!(localizer.format("no-such-message", None), "no-such-message");
+
+        let mut args = FluentArgs::new();
+        args.set("count", arg(3));
+        assert_eq!(
+            localizer.format("cache-cleared", Some(&args)),
+            "Видалено 3 збережені відповіді."
+        );
+    }
+}
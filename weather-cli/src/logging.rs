@@ -0,0 +1,45 @@
+//! Diagnostic logging (`--log-level`).
+//!
+//! Events come from `weather-core` (provider requests, retries, cache hits and misses,
+//! circuit breaker and rate limiter decisions) and are written to stderr, or appended
+//! to the configured `log_file`. Events from dependencies are filtered out.
+
+use std::{
+    fs::{self, OpenOptions},
+    io,
+    path::Path,
+    sync::Mutex,
+};
+
+use tracing_subscriber::{
+    filter::{LevelFilter, Targets},
+    fmt,
+    prelude::*,
+};
+
+/// Install the global subscriber. `level` defaults to `warn`.
+pub fn init(level: Option<&str>, log_file: Option<&Path>) {
+    let level = level.and_then(|raw| raw.parse().ok()).unwrap_or(LevelFilter::WARN);
+    let targets =
+        Targets::new().with_target("weather_core", level).with_target("weather_cli", level);
+    let registry = tracing_subscriber::registry().with(targets);
+
+    match log_file.map(|path| (path, open(path))) {
+        Some((_, Ok(file))) => {
+            registry.with(fmt::layer().with_writer(Mutex::new(file)).with_ansi(false)).init();
+        }
+        Some((path, Err(err))) => {
+            eprintln!("{}", t!("warning-log-file", path = path.display(), error = err));
+            registry.with(fmt::layer().with_writer(io::stderr)).init();
+        }
+        None => registry.with(fmt::layer().with_writer(io::stderr)).init(),
+    }
+}
+
+fn open(path: &Path) -> io::Result<fs::File> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+
+    OpenOptions::new().create(true).append(true).open(path)
+}
@@ -8,25 +8,40 @@
 use clap::Parser;
 use cli::Cli;
 
+#[macro_use]
+mod i18n;
+
 mod cli;
+mod logging;
+mod timefmt;
 
 #[tokio::main]
 async fn main() {
+    Cli::complete();
+
     let cli = Cli::parse();
     let verbose = cli.verbose;
+    cli.use_config_file();
+
+    // A broken config file is reported by the command itself, not here.
+    let config = cli::load_config().ok();
+    let lang = cli.lang.clone().or_else(|| config.as_ref()?.language.clone());
+    i18n::init(lang.as_deref());
+    timefmt::init(cli.utc);
+    logging::init(cli.log_level.as_deref(), config.as_ref().and_then(|c| c.log_file.as_deref()));
 
     if let Err(err) = cli.run().await {
-        eprintln!("\nerror: {err}");
+        eprintln!("\n{}: {err}", t!("error-prefix"));
 
         if verbose {
-            eprintln!("\nError chain:");
+            eprintln!("\n{}", t!("error-chain-header"));
             for (i, cause) in err.chain().enumerate().skip(1) {
                 eprintln!("  {i}: {cause}");
             }
         } else {
-            eprintln!("(run with -v or --verbose to see the full error chain)");
+            eprintln!("{}", t!("error-verbose-hint"));
         }
 
-        std::process::exit(1);
+        std::process::exit(cli::exit_code(&err));
     }
 }
@@ -0,0 +1,149 @@
+//! Dates and times in text output.
+//!
+//! Times are shown in the local time zone, or in UTC with `--utc`. Where it helps to
+//! read them at a glance, a relative form such as "12 minutes ago" or "tomorrow at
+//! 15:00" goes next to them. The zone is chosen once per run, see [`init`].
+
+use std::sync::OnceLock;
+
+use chrono::{DateTime, Local, NaiveDate, Utc};
+
+static UTC: OnceLock<bool> = OnceLock::new();
+
+/// Show times in UTC instead of the local time zone.
+///
+/// Must be called before the first time is formatted; later calls are ignored.
+pub fn init(utc: bool) {
+    let _ = UTC.set(utc);
+}
+
+fn utc() -> bool {
+    UTC.get().copied().unwrap_or(false)
+}
+
+/// `time` in the chosen zone, in chrono's `strftime` syntax.
+fn format(time: DateTime<Utc>, format: &str) -> String {
+    if utc() {
+        time.format(format).to_string()
+    } else {
+        time.with_timezone(&Local).format(format).to_string()
+    }
+}
+
+/// Calendar day of `time` in the chosen zone.
+fn day(time: DateTime<Utc>) -> NaiveDate {
+    if utc() { time.date_naive() } else { time.with_timezone(&Local).date_naive() }
+}
+
+/// Date and time, e.g. `2026-10-16 14:05`, marked `UTC` with `--utc`.
+pub fn absolute(time: DateTime<Utc>) -> String {
+    if utc() {
+        time.format("%Y-%m-%d %H:%M UTC").to_string()
+    } else {
+        format(time, "%Y-%m-%d %H:%M")
+    }
+}
+
+/// Time of day, e.g. `14:05:09`, for the progress lines of long-running commands.
+pub fn clock(time: DateTime<Utc>) -> String {
+    format(time, "%H:%M:%S")
+}
+
+/// Weekday and time, e.g. `Sat 15:00`, for the steps of a forecast.
+pub fn weekday_time(time: DateTime<Utc>) -> String {
+    format(time, "%a %H:%M")
+}
+
+/// `Observed at:` of a report: the local time with its UTC offset followed by how long
+/// ago (or how far ahead) that is, e.g. `2026-10-16 14:05:00 +03:00 (12 minutes ago)`.
+/// With `--utc`, the raw UTC time only.
+pub fn observed(time: DateTime<Utc>) -> String {
+    if utc() {
+        return time.to_string();
+    }
+
+    let local = time.with_timezone(&Local).format("%Y-%m-%d %H:%M:%S %:z");
+    format!("{local} ({})", relative(time, Utc::now()))
+}
+
+/// How `time` relates to `now`, e.g. "12 minutes ago", "in 2 hours" or "tomorrow at
+/// 15:00".
+pub fn relative(time: DateTime<Utc>, now: DateTime<Utc>) -> String {
+    match Relative::between(time, now) {
+        Relative::JustNow => t!("time-just-now"),
+        Relative::MinutesAgo(count) => t!("time-minutes-ago", count = count),
+        Relative::InMinutes(count) => t!("time-in-minutes", count = count),
+        Relative::HoursAgo(count) => t!("time-hours-ago", count = count),
+        Relative::InHours(count) => t!("time-in-hours", count = count),
+        Relative::Yesterday(time) => t!("time-yesterday", time = time),
+        Relative::Today(time) => t!("time-today", time = time),
+        Relative::Tomorrow(time) => t!("time-tomorrow", time = time),
+        Relative::DaysAgo(count) => t!("time-days-ago", count = count),
+        Relative::InDays(count) => t!("time-in-days", count = count),
+    }
+}
+
+/// The relative forms, before they are put into words.
+#[derive(Debug, PartialEq)]
+enum Relative {
+    JustNow,
+    MinutesAgo(i64),
+    InMinutes(i64),
+    HoursAgo(i64),
+    InHours(i64),
+    /// With the time of day.
+    Yesterday(String),
+    Today(String),
+    Tomorrow(String),
+    DaysAgo(i64),
+    InDays(i64),
+}
+
+impl Relative {
+    /// Minutes within the hour, hours within 3 hours, and calendar days beyond that,
+    /// which read better than "in 14 hours".
+    fn between(time: DateTime<Utc>, now: DateTime<Utc>) -> Self {
+        let minutes = (time - now).num_minutes();
+        let days = (day(time) - day(now)).num_days();
+
+        match minutes {
+            0 => Self::JustNow,
+            -59..=-1 => Self::MinutesAgo(-minutes),
+            1..=59 => Self::InMinutes(minutes),
+            -179..=-60 => Self::HoursAgo(-minutes / 60),
+            60..=179 => Self::InHours(minutes / 60),
+            _ => match days {
+                -1 => Self::Yesterday(format(time, "%H:%M")),
+                0 => Self::Today(format(time, "%H:%M")),
+                1 => Self::Tomorrow(format(time, "%H:%M")),
+                ..=-2 => Self::DaysAgo(-days),
+                _ => Self::InDays(days),
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{Duration, TimeZone};
+
+    use super::*;
+
+    #[test]
+    fn near_times_count_minutes_and_hours_and_far_ones_days() {
+        // Noon, so that a few hours either way stay on the same day.
+        let noon = Local::now().date_naive().and_hms_opt(12, 0, 0).unwrap();
+        let now = Local.from_local_datetime(&noon).earliest().unwrap().to_utc();
+        let between = |delta: Duration| Relative::between(now + delta, now);
+
+        assert_eq!(between(Duration::seconds(40)), Relative::JustNow);
+        assert_eq!(between(-Duration::minutes(12)), Relative::MinutesAgo(12));
+        assert_eq!(between(Duration::minutes(59)), Relative::InMinutes(59));
+        assert_eq!(between(Duration::minutes(150)), Relative::InHours(2));
+        assert_eq!(between(-Duration::hours(5)), Relative::Today("07:00".to_string()));
+        assert!(matches!(between(Duration::hours(27)), Relative::Tomorrow(_)));
+        assert!(matches!(between(-Duration::hours(20)), Relative::Yesterday(_)));
+        assert_eq!(between(-Duration::hours(24 * 3 + 1)), Relative::DaysAgo(3));
+        assert_eq!(between(Duration::hours(24 * 4)), Relative::InDays(4));
+    }
+}
@@ -0,0 +1,214 @@
+//! Severe-weather alerts, for `weather monitor`.
+//!
+//! Alerts come from two places: official warnings of providers that publish them (see
+//! [`WeatherProvider::alerts`](crate::WeatherProvider::alerts)) and the user's own
+//! [`AlertThresholds`], e.g. "wind above 15 m/s". A monitor polls both and only reports
+//! what [`SeenAlerts`] has not seen yet, so a warning that stays active for a day is
+//! announced once.
+
+use std::{collections::HashMap, path::PathBuf, time::Duration};
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use tracing::warn;
+
+use crate::{
+    Location, Precipitation, Temperature, WeatherResponse, WindSpeed, geocode::normalize_address,
+    state,
+};
+#[cfg(not(target_arch = "wasm32"))]
+use crate::{config::project_dirs, error::Result};
+
+/// An alert that is no longer reported for this long is forgotten, so that it is
+/// announced again if it comes back. Shorter gaps, e.g. a wind hovering around its
+/// threshold, do not announce it again.
+const REARM_AFTER: Duration = Duration::from_secs(6 * 3600);
+
+/// A weather warning for one location.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WeatherAlert {
+    /// Stable while the alert is active; used to recognize alerts already announced.
+    pub id: String,
+    /// What is happening, e.g. "Wind warning".
+    pub event: String,
+    /// As published, e.g. "Moderate" or "Severe".
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub severity: Option<String>,
+    pub headline: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub starts: Option<DateTime<Utc>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ends: Option<DateTime<Utc>>,
+}
+
+/// What [`WeatherProvider::alerts`](crate::WeatherProvider::alerts) returns: the
+/// official warnings and the current weather, which providers send in the same response,
+/// so that a monitor can check its thresholds without a second call.
+#[derive(Debug, Clone)]
+pub struct AlertReport {
+    pub current: WeatherResponse,
+    pub alerts: Vec<WeatherAlert>,
+}
+
+/// A condition checked by [`AlertThresholds`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Threshold {
+    WindSpeed,
+    TemperatureAbove,
+    TemperatureBelow,
+    Precipitation,
+}
+
+impl Threshold {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Threshold::WindSpeed => "wind_speed",
+            Threshold::TemperatureAbove => "temperature_above",
+            Threshold::TemperatureBelow => "temperature_below",
+            Threshold::Precipitation => "precipitation",
+        }
+    }
+
+    /// [`WeatherAlert::id`] of alerts raised for this threshold.
+    pub fn alert_id(&self) -> String {
+        format!("threshold:{}", self.as_str())
+    }
+}
+
+/// Limits beyond which the weather counts as severe; unset ones are not checked.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct AlertThresholds {
+    pub wind_speed: Option<WindSpeed>,
+    pub temperature_above: Option<Temperature>,
+    pub temperature_below: Option<Temperature>,
+    pub precipitation: Option<Precipitation>,
+}
+
+impl AlertThresholds {
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// The thresholds `response` crosses.
+    pub fn crossed(&self, response: &WeatherResponse) -> Vec<Threshold> {
+        let checks = [
+            (Threshold::WindSpeed, self.wind_speed.is_some_and(|w| response.wind_speed > w)),
+            (
+                Threshold::TemperatureAbove,
+                self.temperature_above.is_some_and(|t| response.temperature > t),
+            ),
+            (
+                Threshold::TemperatureBelow,
+                self.temperature_below.is_some_and(|t| response.temperature < t),
+            ),
+            (
+                Threshold::Precipitation,
+                self.precipitation.is_some_and(|p| response.precipitation > p),
+            ),
+        ];
+
+        checks.into_iter().filter_map(|(threshold, crossed)| crossed.then_some(threshold)).collect()
+    }
+}
+
+/// The alerts announced per location, kept in a JSON file that is locked while it is
+/// updated, so monitors of different locations can share it.
+#[derive(Debug, Clone)]
+pub struct SeenAlerts {
+    path: PathBuf,
+}
+
+/// Alert ids by location key, with when each was last reported.
+type Seen = HashMap<String, HashMap<String, DateTime<Utc>>>;
+
+impl SeenAlerts {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into() }
+    }
+
+    /// `alerts.json` in the platform cache directory.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn default_path() -> Result<PathBuf> {
+        Ok(project_dirs()?.cache_dir().join("alerts.json"))
+    }
+
+    /// Note that `alerts` are active at `location` and return those not seen before.
+    pub fn take_new(&self, location: &Location, alerts: Vec<WeatherAlert>) -> Vec<WeatherAlert> {
+        self.take_new_at(location, alerts, Utc::now())
+    }
+
+    fn take_new_at(
+        &self,
+        location: &Location,
+        alerts: Vec<WeatherAlert>,
+        now: DateTime<Utc>,
+    ) -> Vec<WeatherAlert> {
+        let rearm_after = chrono::Duration::from_std(REARM_AFTER).unwrap_or_default();
+        let key = normalize_address(&location.to_string());
+
+        // Whether each alert is new; with no state to go by, all of them are.
+        let fresh = state::update(&self.path, |seen: &mut Seen| {
+            let known = seen.entry(key).or_default();
+            known.retain(|_, last_seen| now - *last_seen < rearm_after);
+            alerts.iter().map(|alert| known.insert(alert.id.clone(), now).is_none()).collect()
+        });
+        let fresh: Vec<bool> = fresh.unwrap_or_else(|err| {
+            warn!(error = %err, "failed to update seen alerts");
+            vec![true; alerts.len()]
+        });
+
+        alerts.into_iter().zip(fresh).filter_map(|(alert, new)| new.then_some(alert)).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::provider::mock;
+
+    fn alert(id: &str) -> WeatherAlert {
+        WeatherAlert {
+            id: id.to_string(),
+            event: "Wind warning".to_string(),
+            severity: Some("Moderate".to_string()),
+            headline: "Strong winds expected".to_string(),
+            starts: None,
+            ends: None,
+        }
+    }
+
+    #[test]
+    fn thresholds_report_what_is_crossed() {
+        let thresholds = AlertThresholds {
+            wind_speed: Some(WindSpeed::from_mps(2.5)),
+            temperature_below: Some(Temperature::from_celsius(0.0)),
+            ..AlertThresholds::default()
+        };
+
+        // The mock response has 12.5 °C and a wind of 3 m/s.
+        assert_eq!(thresholds.crossed(&mock::response("Kyiv")), [Threshold::WindSpeed]);
+        assert!(AlertThresholds::default().crossed(&mock::response("Kyiv")).is_empty());
+    }
+
+    #[test]
+    fn alerts_are_announced_once_until_they_lapse() {
+        let dir = tempfile::tempdir().unwrap();
+        let seen = SeenAlerts::new(dir.path().join("alerts.json"));
+        let kyiv = Location::from("Kyiv");
+        let now = DateTime::from_timestamp(1_760_000_000, 0).unwrap();
+        let ids = |alerts: Vec<WeatherAlert>| -> Vec<String> {
+            alerts.into_iter().map(|alert| alert.id).collect()
+        };
+
+        assert_eq!(ids(seen.take_new_at(&kyiv, vec![alert("wind")], now)), ["wind"]);
+        let later = now + chrono::Duration::hours(1);
+        let alerts = vec![alert("wind"), alert("flood")];
+        assert_eq!(ids(seen.take_new_at(&kyiv, alerts, later)), ["flood"]);
+        // Other locations keep their own alerts.
+        assert_eq!(ids(seen.take_new_at(&"Lviv".into(), vec![alert("wind")], later)), ["wind"]);
+
+        // Gone for longer than REARM_AFTER: announced again.
+        let much_later = later + chrono::Duration::hours(7);
+        assert_eq!(ids(seen.take_new_at(&kyiv, vec![alert("wind")], much_later)), ["wind"]);
+    }
+}
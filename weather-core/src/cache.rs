@@ -0,0 +1,596 @@
+//! On-disk cache of provider responses.
+//!
+//! Entries are keyed by provider, normalized address, language and a time bucket
+//! (`current` for "now", otherwise the requested hour) and stored as JSON files under
+//! the platform cache directory. An entry is served until it is older than the
+//! configured TTL; older entries are still kept around for offline use. Geocoding
+//! candidates are cached next to them, per provider and address, for [`PLACES_TTL`].
+
+use std::{
+    fs,
+    ops::Range,
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use tracing::{Instrument, debug, info, info_span, warn};
+
+#[cfg(not(target_arch = "wasm32"))]
+use crate::{Config, config::project_dirs};
+use crate::{
+    WeatherRequest, WeatherResponse, WeatherSeries,
+    alerts::AlertReport,
+    error::{Context, Result, WeatherError},
+    geocode::{Place, normalize_address},
+    provider::{ProviderId, RequestPlan, WeatherProvider, http},
+};
+
+/// How long geocoding candidates are served: places move far less than the weather.
+pub const PLACES_TTL: Duration = Duration::from_secs(30 * 24 * 60 * 60);
+
+/// Identifies a cacheable request.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CacheKey {
+    provider: ProviderId,
+    address: String,
+    language: Option<String>,
+    bucket: String,
+}
+
+impl CacheKey {
+    pub fn new(provider: ProviderId, request: &WeatherRequest) -> Self {
+        let bucket = match request.when {
+            None => "current".to_string(),
+            Some(dt) => dt.format("%Y%m%dT%H").to_string(),
+        };
+
+        Self {
+            provider,
+            address: normalize_address(&request.location.to_string()),
+            language: request.language.as_ref().map(|l| l.to_lowercase()),
+            bucket,
+        }
+    }
+
+    /// The geocoding candidates for `address`, whatever the language or time.
+    pub fn places(provider: ProviderId, address: &str) -> Self {
+        Self {
+            provider,
+            address: normalize_address(address),
+            language: None,
+            bucket: "places".to_string(),
+        }
+    }
+
+    /// Stable textual form, stored inside the entry to detect file name collisions.
+    fn id(&self) -> String {
+        format!(
+            "{}|{}|{}|{}",
+            self.provider,
+            self.address,
+            self.language.as_deref().unwrap_or_default(),
+            self.bucket
+        )
+    }
+
+    fn relative_path(&self) -> PathBuf {
+        Path::new(self.provider.as_str()).join(slug(&self.address)).join(self.file_name())
+    }
+
+    fn file_name(&self) -> String {
+        match &self.language {
+            Some(lang) => format!("{}.{}.json", self.bucket, slug(lang)),
+            None => format!("{}.json", self.bucket),
+        }
+    }
+}
+
+/// A response read back from the cache together with when it was stored.
+#[derive(Debug, Clone)]
+pub struct CachedResponse {
+    pub response: WeatherResponse,
+    pub stored_at: DateTime<Utc>,
+}
+
+impl CachedResponse {
+    pub fn age(&self) -> Duration {
+        (Utc::now() - self.stored_at).to_std().unwrap_or_default()
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct CacheEntry<T = WeatherResponse> {
+    key: String,
+    stored_at: DateTime<Utc>,
+    response: T,
+}
+
+/// File-backed response cache.
+#[derive(Debug, Clone)]
+pub struct ResponseCache {
+    dir: PathBuf,
+    ttl: Duration,
+}
+
+impl ResponseCache {
+    pub fn new(dir: impl Into<PathBuf>, ttl: Duration) -> Self {
+        Self { dir: dir.into(), ttl }
+    }
+
+    /// Cache in the platform cache directory, with the TTL from config.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn from_config(config: &Config) -> Result<Self> {
+        Ok(Self::new(Self::default_dir()?, config.cache_ttl()))
+    }
+
+    /// Default cache location, e.g. `~/.cache/weather-cli/responses` on Linux.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn default_dir() -> Result<PathBuf> {
+        Ok(project_dirs()?.cache_dir().join("responses"))
+    }
+
+    /// Return the cached response for `key` if it is still within the TTL.
+    pub fn get(&self, key: &CacheKey) -> Option<WeatherResponse> {
+        let entry = self.read(key)?;
+        let age = (Utc::now() - entry.stored_at).to_std().unwrap_or_default();
+
+        (age < self.ttl).then_some(entry.response)
+    }
+
+    pub fn put(&self, key: &CacheKey, response: &WeatherResponse) -> Result<()> {
+        self.write(key, response)
+    }
+
+    /// Return the cached geocoding candidates for `key` if they are within
+    /// [`PLACES_TTL`].
+    pub fn get_places(&self, key: &CacheKey) -> Option<Vec<Place>> {
+        let entry = self.read::<Vec<Place>>(key)?;
+        let age = (Utc::now() - entry.stored_at).to_std().unwrap_or_default();
+
+        (age < PLACES_TTL).then_some(entry.response)
+    }
+
+    pub fn put_places(&self, key: &CacheKey, places: &[Place]) -> Result<()> {
+        self.write(key, places)
+    }
+
+    fn write<T: Serialize + ?Sized>(&self, key: &CacheKey, response: &T) -> Result<()> {
+        let path = self.dir.join(key.relative_path());
+
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).with_context(|| {
+                format!("Failed to create cache directory: {}", parent.display())
+            })?;
+        }
+
+        let entry = CacheEntry { key: key.id(), stored_at: Utc::now(), response };
+        let json = serde_json::to_string(&entry).context("Failed to serialize cache entry")?;
+
+        fs::write(&path, json)
+            .with_context(|| format!("Failed to write cache file: {}", path.display()))
+    }
+
+    /// Remove every cached response. Returns the number of entries removed.
+    pub fn clear(&self) -> Result<usize> {
+        if !self.dir.exists() {
+            return Ok(0);
+        }
+
+        let count = count_files(&self.dir)?;
+        fs::remove_dir_all(&self.dir)
+            .with_context(|| format!("Failed to remove cache directory: {}", self.dir.display()))?;
+
+        Ok(count)
+    }
+
+    /// The entry for `key` however old, as a stale answer. Only the same time bucket
+    /// will do: current weather is no answer for another date, nor one hour's forecast
+    /// for the next.
+    pub fn latest(&self, key: &CacheKey) -> Option<CachedResponse> {
+        let entry = self.read::<WeatherResponse>(key)?;
+
+        Some(CachedResponse { response: entry.response, stored_at: entry.stored_at })
+    }
+
+    fn read<T: DeserializeOwned>(&self, key: &CacheKey) -> Option<CacheEntry<T>> {
+        let entry = read_entry(&self.dir.join(key.relative_path()))?;
+
+        (entry.key == key.id()).then_some(entry)
+    }
+}
+
+fn read_entry<T: DeserializeOwned>(path: &Path) -> Option<CacheEntry<T>> {
+    let contents = fs::read_to_string(path).ok()?;
+    serde_json::from_str(&contents).ok()
+}
+
+/// How [`CachedProvider`] uses the network.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum CacheMode {
+    /// Serve fresh entries, otherwise ask the provider.
+    #[default]
+    Online,
+    /// Never touch the network; serve whatever is cached, however old.
+    Offline,
+}
+
+/// Where a [`CachedFetch`] response came from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ResponseSource {
+    /// Fetched from the provider just now.
+    Provider,
+    /// Served from the cache within its TTL.
+    Cache,
+    /// Served from the cache past its TTL, because the provider could not be reached
+    /// or offline mode was requested.
+    Stale { stored_at: DateTime<Utc> },
+}
+
+#[derive(Debug, Clone)]
+pub struct CachedFetch {
+    pub response: WeatherResponse,
+    pub source: ResponseSource,
+}
+
+/// Provider wrapper that serves fresh cached responses and caches new ones.
+///
+/// With stale fallback enabled, a request that fails because the provider is
+/// unreachable is answered from the cached entry for the same request, however old.
+#[derive(Debug)]
+pub struct CachedProvider {
+    inner: Box<dyn WeatherProvider>,
+    id: ProviderId,
+    cache: ResponseCache,
+    mode: CacheMode,
+    stale_fallback: bool,
+}
+
+impl CachedProvider {
+    pub fn new(inner: Box<dyn WeatherProvider>, id: ProviderId, cache: ResponseCache) -> Self {
+        Self { inner, id, cache, mode: CacheMode::Online, stale_fallback: false }
+    }
+
+    pub fn with_mode(mut self, mode: CacheMode) -> Self {
+        self.mode = mode;
+        self
+    }
+
+    pub fn with_stale_fallback(mut self, enabled: bool) -> Self {
+        self.stale_fallback = enabled;
+        self
+    }
+
+    /// Like [`WeatherProvider::get_weather`], but also reports where the answer came from.
+    pub async fn fetch(&self, request: &WeatherRequest) -> Result<CachedFetch> {
+        let span = info_span!("fetch", provider = %self.id, location = %request.location);
+        self.fetch_inner(request).instrument(span).await
+    }
+
+    async fn fetch_inner(&self, request: &WeatherRequest) -> Result<CachedFetch> {
+        let key = CacheKey::new(self.id, request);
+
+        if self.mode == CacheMode::Offline {
+            let cached = self
+                .cache
+                .latest(&key)
+                .ok_or_else(|| WeatherError::NotCached(request.location.to_string()))?;
+            debug!(stored_at = %cached.stored_at, "offline, serving cached response");
+            return Ok(stale(cached));
+        }
+
+        if let Some(response) = self.cache.get(&key) {
+            info!("cache hit");
+            return Ok(CachedFetch { response, source: ResponseSource::Cache });
+        }
+        info!("cache miss");
+
+        match self.inner.get_weather(request).await {
+            Ok(response) => {
+                if let Err(err) = self.cache.put(&key, &response) {
+                    warn!(error = %err, "failed to cache response");
+                }
+
+                Ok(CachedFetch { response, source: ResponseSource::Provider })
+            }
+            Err(err) if self.stale_fallback && is_unreachable(&err) => {
+                let cached = self.cache.latest(&key).ok_or(err)?;
+                info!(stored_at = %cached.stored_at, "provider unreachable, serving stale copy");
+                Ok(stale(cached))
+            }
+            Err(err) => Err(err),
+        }
+    }
+}
+
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+impl WeatherProvider for CachedProvider {
+    async fn get_weather(&self, request: &WeatherRequest) -> Result<WeatherResponse> {
+        self.fetch(request).await.map(|fetched| fetched.response)
+    }
+
+    fn plan(&self, request: &WeatherRequest) -> Result<RequestPlan> {
+        self.inner.plan(request)
+    }
+
+    /// Candidates are cached per address, so that picking a place again costs nothing;
+    /// offline, only cached ones are served.
+    async fn geocode(&self, address: &str) -> Result<Vec<Place>> {
+        let key = CacheKey::places(self.id, address);
+        if let Some(places) = self.cache.get_places(&key) {
+            debug!("geocoding cache hit");
+            return Ok(places);
+        }
+        if self.mode == CacheMode::Offline {
+            return Err(WeatherError::NotCached(address.to_string()));
+        }
+
+        let places = self.inner.geocode(address).await?;
+        if let Err(err) = self.cache.put_places(&key, &places) {
+            warn!(error = %err, "failed to cache geocoding candidates");
+        }
+
+        Ok(places)
+    }
+
+    /// Not cached: a forecast series is only asked for now and then.
+    async fn get_forecast_series(
+        &self,
+        request: &WeatherRequest,
+        range: Range<DateTime<Utc>>,
+    ) -> Result<WeatherSeries> {
+        if self.mode == CacheMode::Offline {
+            return Err(WeatherError::NotCached(request.location.to_string()));
+        }
+        self.inner.get_forecast_series(request, range).await
+    }
+
+    /// Not cached either: alerts are polled to find out what is new.
+    async fn alerts(&self, request: &WeatherRequest) -> Result<AlertReport> {
+        if self.mode == CacheMode::Offline {
+            return Err(WeatherError::NotCached(request.location.to_string()));
+        }
+        self.inner.alerts(request).await
+    }
+}
+
+fn stale(cached: CachedResponse) -> CachedFetch {
+    CachedFetch {
+        response: cached.response,
+        source: ResponseSource::Stale { stored_at: cached.stored_at },
+    }
+}
+
+/// Connection and timeout failures or an open circuit, as opposed to the provider
+/// rejecting the request.
+fn is_unreachable(err: &WeatherError) -> bool {
+    match err {
+        WeatherError::CircuitOpen(_) => true,
+        WeatherError::Http { source, .. } => http::is_transient(source),
+        _ => false,
+    }
+}
+
+/// Longest slug kept whole, in bytes; file names are limited to 255 on most systems.
+const MAX_SLUG_BYTES: usize = 100;
+
+/// File-system friendly form of a key component. A long one is cut short and followed
+/// by a hash of the whole value, which keeps it a valid file name.
+fn slug(value: &str) -> String {
+    let slug: String = value.chars().map(|c| if c.is_alphanumeric() { c } else { '_' }).collect();
+    if slug.len() <= MAX_SLUG_BYTES {
+        return slug;
+    }
+
+    let mut end = MAX_SLUG_BYTES;
+    while !slug.is_char_boundary(end) {
+        end -= 1;
+    }
+    format!("{}-{:016x}", &slug[..end], fnv1a(value))
+}
+
+/// 64-bit FNV-1a, which unlike `DefaultHasher` stays the same across Rust releases.
+fn fnv1a(value: &str) -> u64 {
+    value.bytes().fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(0x0100_0000_01b3)
+    })
+}
+
+fn count_files(dir: &Path) -> Result<usize> {
+    let mut count = 0;
+
+    for entry in fs::read_dir(dir)
+        .with_context(|| format!("Failed to read cache directory: {}", dir.display()))?
+    {
+        let path = entry
+            .with_context(|| format!("Failed to read cache directory: {}", dir.display()))?
+            .path();
+        if path.is_dir() {
+            count += count_files(&path)?;
+        } else {
+            count += 1;
+        }
+    }
+
+    Ok(count)
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+
+    use super::*;
+    use crate::{
+        model::{Precipitation, Pressure, Temperature, WindSpeed},
+        provider::mock::MockProvider,
+    };
+
+    fn request(address: &str) -> WeatherRequest {
+        WeatherRequest::builder().address(address).build().unwrap()
+    }
+
+    fn response() -> WeatherResponse {
+        WeatherResponse {
+            provider: "openweather".to_string(),
+            location_name: "Kyiv".to_string(),
+            temperature: Temperature::from_celsius(-2.0),
+            feels_like: Temperature::from_celsius(-6.0),
+            condition: "overcast clouds".to_string(),
+            humidity_pct: 81,
+            wind_speed: WindSpeed::from_mps(4.2),
+            wind_direction: None,
+            pressure: Pressure::from_hpa(1012.0),
+            precipitation: Precipitation::from_mm(0.0),
+            observation_time: Utc.with_ymd_and_hms(2025, 12, 4, 12, 0, 0).unwrap(),
+        }
+    }
+
+    #[test]
+    fn key_normalizes_address() {
+        let a = CacheKey::new(ProviderId::OpenWeather, &request("  Kyiv,   UA "));
+        let b = CacheKey::new(ProviderId::OpenWeather, &request("kyiv, ua"));
+        assert_eq!(a, b);
+
+        let other_provider = CacheKey::new(ProviderId::WeatherApi, &request("kyiv, ua"));
+        assert_ne!(a, other_provider);
+    }
+
+    #[test]
+    fn long_addresses_still_make_valid_file_names() {
+        let street = "вулиця Хрещатик, ".repeat(10);
+        let a = CacheKey::new(ProviderId::OpenWeather, &request(&format!("{street}1, Київ")));
+        let b = CacheKey::new(ProviderId::OpenWeather, &request(&format!("{street}2, Київ")));
+
+        let (a, b) = (a.relative_path(), b.relative_path());
+        assert_ne!(a, b);
+        assert!(a.iter().all(|part| part.len() <= 255), "{a:?}");
+
+        let dir = tempfile::tempdir().unwrap();
+        let cache = ResponseCache::new(dir.path(), Duration::from_secs(600));
+        let key = CacheKey::new(ProviderId::OpenWeather, &request(&street));
+        cache.put(&key, &response()).unwrap();
+        assert!(cache.get(&key).is_some());
+    }
+
+    #[test]
+    fn key_buckets_requested_time_by_hour() {
+        let mut req = request("Kyiv");
+        req.when = Some(Utc.with_ymd_and_hms(2025, 12, 4, 12, 5, 0).unwrap());
+        let a = CacheKey::new(ProviderId::OpenWeather, &req);
+
+        req.when = Some(Utc.with_ymd_and_hms(2025, 12, 4, 12, 55, 0).unwrap());
+        let b = CacheKey::new(ProviderId::OpenWeather, &req);
+
+        req.when = Some(Utc.with_ymd_and_hms(2025, 12, 4, 13, 0, 0).unwrap());
+        let c = CacheKey::new(ProviderId::OpenWeather, &req);
+
+        assert_eq!(a, b);
+        assert_ne!(a, c);
+    }
+
+    #[test]
+    fn put_then_get_within_ttl() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = ResponseCache::new(dir.path(), Duration::from_secs(600));
+        let key = CacheKey::new(ProviderId::OpenWeather, &request("Kyiv"));
+
+        assert!(cache.get(&key).is_none());
+
+        cache.put(&key, &response()).unwrap();
+        let cached = cache.get(&key).expect("fresh entry must be served");
+        assert_eq!(cached.location_name, "Kyiv");
+    }
+
+    #[test]
+    fn expired_entries_are_not_served() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = ResponseCache::new(dir.path(), Duration::ZERO);
+        let key = CacheKey::new(ProviderId::OpenWeather, &request("Kyiv"));
+
+        cache.put(&key, &response()).unwrap();
+        assert!(cache.get(&key).is_none());
+    }
+
+    #[test]
+    fn latest_ignores_ttl_but_not_the_time_bucket() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = ResponseCache::new(dir.path(), Duration::ZERO);
+
+        let mut req = request("Kyiv");
+        req.when = Some(Utc.with_ymd_and_hms(2025, 12, 4, 12, 0, 0).unwrap());
+        let noon = CacheKey::new(ProviderId::OpenWeather, &req);
+        cache.put(&noon, &response()).unwrap();
+
+        assert!(cache.get(&noon).is_none());
+        let stale = cache.latest(&noon).expect("expired entry must still be found");
+        assert_eq!(stale.response.location_name, "Kyiv");
+
+        // A forecast for noon is no answer for now, nor for another hour.
+        let current = CacheKey::new(ProviderId::OpenWeather, &request("kyiv"));
+        assert!(cache.latest(&current).is_none());
+        req.when = Some(Utc.with_ymd_and_hms(2025, 12, 4, 13, 0, 0).unwrap());
+        assert!(cache.latest(&CacheKey::new(ProviderId::OpenWeather, &req)).is_none());
+    }
+
+    #[tokio::test]
+    async fn offline_mode_serves_stale_entries_only() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = ResponseCache::new(dir.path(), Duration::ZERO);
+
+        let inner = MockProvider::new();
+        let provider =
+            CachedProvider::new(Box::new(inner.clone()), ProviderId::OpenWeather, cache.clone())
+                .with_mode(CacheMode::Offline);
+
+        let err = provider.fetch(&request("Kyiv")).await.unwrap_err();
+        assert!(matches!(err, WeatherError::NotCached(_)));
+        assert!(err.to_string().contains("available offline"));
+
+        cache.put(&CacheKey::new(ProviderId::OpenWeather, &request("Kyiv")), &response()).unwrap();
+        let fetched = provider.fetch(&request("Kyiv")).await.unwrap();
+        assert!(matches!(fetched.source, ResponseSource::Stale { .. }));
+
+        // Offline mode never reaches the inner provider.
+        assert!(inner.calls().is_empty());
+    }
+
+    #[tokio::test]
+    async fn geocoding_candidates_are_cached_per_address() {
+        use crate::provider::http::testing::{json_ok, serve};
+
+        let body = r#"[{"name":"Paris","lat":48.85,"lon":2.35,"country":"FR"},
+                      {"name":"Paris","lat":33.66,"lon":-95.55,"country":"US"}]"#;
+        // One response only: a second lookup reaching the provider would fail.
+        let (url, requests) = serve(vec![json_ok(body)]).await;
+
+        let dir = tempfile::tempdir().unwrap();
+        let cache = ResponseCache::new(dir.path(), Duration::ZERO);
+        let inner = crate::provider::openweather::OpenWeatherProvider::builder()
+            .api_key("secret")
+            .base_url(url)
+            .build()
+            .unwrap();
+        let provider = CachedProvider::new(Box::new(inner), ProviderId::OpenWeather, cache);
+
+        assert_eq!(provider.geocode("Paris").await.unwrap().len(), 2);
+        assert_eq!(provider.geocode("  paris ").await.unwrap().len(), 2);
+        assert!(requests.recv().unwrap().starts_with("GET /geo/1.0/direct?"));
+        assert!(requests.try_recv().is_err());
+    }
+
+    #[test]
+    fn clear_removes_all_entries() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = ResponseCache::new(dir.path().join("responses"), Duration::from_secs(600));
+
+        cache.put(&CacheKey::new(ProviderId::OpenWeather, &request("Kyiv")), &response()).unwrap();
+        cache.put(&CacheKey::new(ProviderId::WeatherApi, &request("Lviv")), &response()).unwrap();
+
+        assert_eq!(cache.clear().unwrap(), 2);
+        assert_eq!(cache.clear().unwrap(), 0);
+    }
+}
@@ -0,0 +1,279 @@
+//! Climate normals: what the weather is usually like in a given month.
+//!
+//! Backs `weather climate`. Daily highs, lows and precipitation of past years come from
+//! the Open-Meteo historical archive, which needs no API key, and are averaged into
+//! [`ClimateNormals`]. Addresses are looked up with Open-Meteo's geocoding, so this
+//! works whichever weather provider is configured.
+
+use chrono::{Datelike, NaiveDate, Utc};
+use serde::Deserialize;
+
+#[cfg(not(target_arch = "wasm32"))]
+use crate::{Config, provider::http::client_from_config};
+use crate::{
+    Precipitation, Temperature,
+    error::{Context, Result, WeatherError},
+    geocode::Place,
+    provider::http::HttpClient,
+};
+
+const ARCHIVE_URL: &str = "https://archive-api.open-meteo.com/v1/archive";
+const GEOCODING_URL: &str = "https://geocoding-api.open-meteo.com/v1/search";
+
+/// Days with at least this much precipitation count as wet.
+const WET_DAY_MM: f64 = 1.0;
+
+/// The usual weather of one month, averaged over several years.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ClimateNormals {
+    /// 1 (January) to 12.
+    pub month: u32,
+    pub first_year: i32,
+    pub last_year: i32,
+    /// Mean daily high and low.
+    pub high: Temperature,
+    pub low: Temperature,
+    /// Highest high and lowest low of all those days.
+    pub record_high: Temperature,
+    pub record_low: Temperature,
+    /// Mean total precipitation of the month.
+    pub precipitation: Precipitation,
+    /// Mean number of days with at least 1 mm of precipitation.
+    pub wet_days: f64,
+}
+
+#[derive(Debug, Deserialize)]
+struct ArchiveResponse {
+    daily: Daily,
+}
+
+#[derive(Debug, Deserialize)]
+struct Daily {
+    time: Vec<NaiveDate>,
+    temperature_2m_max: Vec<Option<f64>>,
+    temperature_2m_min: Vec<Option<f64>>,
+    precipitation_sum: Vec<Option<f64>>,
+}
+
+#[derive(Debug, Deserialize)]
+struct GeocodingResponse {
+    #[serde(default)]
+    results: Vec<GeocodingPlace>,
+}
+
+#[derive(Debug, Deserialize)]
+struct GeocodingPlace {
+    name: String,
+    #[serde(default)]
+    admin1: Option<String>,
+    #[serde(default)]
+    country_code: String,
+    latitude: f64,
+    longitude: f64,
+}
+
+/// Client for the Open-Meteo historical archive.
+#[derive(Debug, Clone)]
+pub struct ClimateArchive {
+    http: HttpClient,
+    archive_url: String,
+    geocoding_url: String,
+}
+
+impl ClimateArchive {
+    pub fn new(http: HttpClient) -> Self {
+        Self {
+            http,
+            archive_url: ARCHIVE_URL.to_string(),
+            geocoding_url: GEOCODING_URL.to_string(),
+        }
+    }
+
+    /// Archive with the proxy and TLS settings from config.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn from_config(config: &Config) -> Result<Self> {
+        Ok(Self::new(HttpClient::new(client_from_config(config)?, config.retry_policy())))
+    }
+
+    /// Ask `url` instead of the Open-Meteo archive; it must answer in the same format.
+    pub fn with_archive_url(mut self, url: impl Into<String>) -> Self {
+        self.archive_url = url.into();
+        self
+    }
+
+    /// Ask `url` instead of Open-Meteo geocoding; it must answer in the same format.
+    pub fn with_geocoding_url(mut self, url: impl Into<String>) -> Self {
+        self.geocoding_url = url.into();
+        self
+    }
+
+    /// The best match for `address`.
+    pub async fn geocode(&self, address: &str) -> Result<Place> {
+        let query = [("name", address), ("count", "1"), ("format", "json")];
+        let res = self.http.get(&self.geocoding_url, &query).await?;
+        let res = res.error_for_status("Open-Meteo geocoding")?;
+
+        let parsed: GeocodingResponse =
+            serde_json::from_str(&res.body).context("Failed to parse Open-Meteo geocoding JSON")?;
+        let place = parsed
+            .results
+            .into_iter()
+            .next()
+            .ok_or_else(|| WeatherError::PlaceNotFound(address.to_string()))?;
+
+        Ok(Place {
+            name: place.name,
+            region: place.admin1,
+            country: place.country_code,
+            lat: place.latitude,
+            lon: place.longitude,
+        })
+    }
+
+    /// Normals of `month` at `lat`/`lon` over the last `years` years in which that
+    /// month is over.
+    pub async fn normals(
+        &self,
+        lat: f64,
+        lon: f64,
+        month: u32,
+        years: u32,
+    ) -> Result<ClimateNormals> {
+        let (start, end) = period(month, years, Utc::now().date_naive())?;
+        let (lat, lon, start, end) =
+            (lat.to_string(), lon.to_string(), start.to_string(), end.to_string());
+        let query = [
+            ("latitude", lat.as_str()),
+            ("longitude", lon.as_str()),
+            ("start_date", start.as_str()),
+            ("end_date", end.as_str()),
+            ("daily", "temperature_2m_max,temperature_2m_min,precipitation_sum"),
+            ("timezone", "auto"),
+        ];
+
+        let res = self.http.get(&self.archive_url, &query).await?;
+        let res = res.error_for_status("Open-Meteo archive")?;
+        let parsed: ArchiveResponse =
+            serde_json::from_str(&res.body).context("Failed to parse Open-Meteo archive JSON")?;
+
+        summarize(month, &parsed.daily).ok_or_else(|| WeatherError::Parse {
+            context: "Failed to parse Open-Meteo archive JSON".to_string(),
+            source: "the response contained no daily data".into(),
+        })
+    }
+}
+
+/// First and last day to fetch: `month` of the last `years` years in which it is over
+/// by `today`, one request spanning them all.
+fn period(month: u32, years: u32, today: NaiveDate) -> Result<(NaiveDate, NaiveDate)> {
+    if !(1..=12).contains(&month) {
+        return Err(WeatherError::InvalidRequest(format!(
+            "Invalid month {month}. Expected 1 (January) to 12 (December)."
+        )));
+    }
+    if years == 0 {
+        return Err(WeatherError::InvalidRequest("At least one year is needed.".to_string()));
+    }
+
+    let last_year = if month < today.month() { today.year() } else { today.year() - 1 };
+    let first_year = last_year - years as i32 + 1;
+    let first_day = |year: i32, month: u32| NaiveDate::from_ymd_opt(year, month, 1);
+    let start = first_day(first_year, month);
+    let end = match month {
+        12 => first_day(last_year + 1, 1),
+        _ => first_day(last_year, month + 1),
+    }
+    .and_then(|next| next.pred_opt());
+
+    start.zip(end).ok_or_else(|| {
+        WeatherError::InvalidRequest(format!("No archive covers {years} years back."))
+    })
+}
+
+/// Average the days of `month` in `daily`; days missing a value are skipped. `None` if
+/// no day has all values.
+fn summarize(month: u32, daily: &Daily) -> Option<ClimateNormals> {
+    let days: Vec<(NaiveDate, f64, f64, f64)> = daily
+        .time
+        .iter()
+        .zip(&daily.temperature_2m_max)
+        .zip(&daily.temperature_2m_min)
+        .zip(&daily.precipitation_sum)
+        .filter(|(((date, _), _), _)| date.month() == month)
+        .filter_map(|(((date, high), low), mm)| Some((*date, (*high)?, (*low)?, (*mm)?)))
+        .collect();
+
+    let first_year = days.iter().map(|(date, ..)| date.year()).min()?;
+    let last_year = days.iter().map(|(date, ..)| date.year()).max()?;
+    let mut years: Vec<i32> = days.iter().map(|(date, ..)| date.year()).collect();
+    years.dedup();
+    let (count, years) = (days.len() as f64, years.len() as f64);
+
+    let mean =
+        |value: fn(&(NaiveDate, f64, f64, f64)) -> f64| days.iter().map(value).sum::<f64>() / count;
+    let wet_days = days.iter().filter(|(.., mm)| *mm >= WET_DAY_MM).count() as f64;
+
+    Some(ClimateNormals {
+        month,
+        first_year,
+        last_year,
+        high: Temperature::from_celsius(mean(|day| day.1)),
+        low: Temperature::from_celsius(mean(|day| day.2)),
+        record_high: Temperature::from_celsius(
+            days.iter().map(|day| day.1).fold(f64::MIN, f64::max),
+        ),
+        record_low: Temperature::from_celsius(
+            days.iter().map(|day| day.2).fold(f64::MAX, f64::min),
+        ),
+        precipitation: Precipitation::from_mm(days.iter().map(|day| day.3).sum::<f64>() / years),
+        wet_days: wet_days / years,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::provider::http::testing::{json_ok, serve};
+
+    fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(y, m, d).unwrap()
+    }
+
+    #[test]
+    fn period_covers_only_months_that_are_over() {
+        let today = date(2026, 10, 16);
+
+        assert_eq!(period(12, 10, today).unwrap(), (date(2016, 12, 1), date(2025, 12, 31)));
+        assert_eq!(period(10, 1, today).unwrap(), (date(2025, 10, 1), date(2025, 10, 31)));
+        assert_eq!(period(2, 3, today).unwrap(), (date(2024, 2, 1), date(2026, 2, 28)));
+        assert!(matches!(period(13, 10, today), Err(WeatherError::InvalidRequest(_))));
+    }
+
+    #[tokio::test]
+    async fn normals_average_the_month_over_the_years() {
+        // Two Decembers with two days each, one of them incomplete, plus a January day.
+        let body = r#"{
+            "latitude": 50.45, "longitude": 30.52, "timezone": "Europe/Kyiv",
+            "daily": {
+                "time": ["2024-12-01", "2024-12-02", "2025-01-01", "2025-12-01", "2025-12-02"],
+                "temperature_2m_max": [2.0, 4.0, 20.0, 6.0, null],
+                "temperature_2m_min": [-4.0, -2.0, 10.0, 0.0, -1.0],
+                "precipitation_sum": [3.0, 0.5, 9.0, 2.5, 0.0]
+            }
+        }"#;
+        let (url, requests) = serve(vec![json_ok(body)]).await;
+        let archive = ClimateArchive::new(HttpClient::default()).with_archive_url(url);
+
+        let normals = archive.normals(50.45, 30.52, 12, 2).await.unwrap();
+        assert_eq!((normals.first_year, normals.last_year), (2024, 2025));
+        assert_eq!(normals.high, Temperature::from_celsius(4.0));
+        assert_eq!(normals.low, Temperature::from_celsius(-2.0));
+        assert_eq!(normals.record_high, Temperature::from_celsius(6.0));
+        assert_eq!(normals.record_low, Temperature::from_celsius(-4.0));
+        assert_eq!(normals.precipitation, Precipitation::from_mm(3.0));
+        assert_eq!(normals.wet_days, 1.0);
+
+        let request = requests.recv().unwrap();
+        assert!(request.contains("daily=temperature_2m_max"), "{request}");
+    }
+}
@@ -1,14 +1,111 @@
-use anyhow::{Context, Result, anyhow};
+#[cfg(all(feature = "encryption", not(target_arch = "wasm32")))]
+use age::secrecy::SecretString;
+#[cfg(not(target_arch = "wasm32"))]
 use directories::ProjectDirs;
 use serde::{Deserialize, Serialize};
-use std::{collections::HashMap, fs, path::PathBuf};
+#[cfg(not(target_arch = "wasm32"))]
+use std::fs;
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+    time::Duration,
+};
 
-use crate::provider::ProviderId;
+// No file system or proxy settings on wasm32; see `crate::platform`.
+use crate::alerts::AlertThresholds;
+use crate::error::{Result, WeatherError};
+use crate::geocode::{Place, normalize_address};
+use crate::model::{Location, Precipitation, Temperature, Units, WindSpeed};
+use crate::provider::{ProviderId, http::RetryPolicy, mask};
+#[cfg(not(target_arch = "wasm32"))]
+use crate::{error::Context, provider::http};
+#[cfg(all(feature = "encryption", not(target_arch = "wasm32")))]
+use crate::{error::IntoWeatherError, secrets};
+
+/// Environment variable naming the config file, see [`Config::config_file_path`].
+pub const CONFIG_ENV: &str = "WEATHER_CONFIG";
+
+/// Name of the saved location used when a command is given none, see
+/// [`Config::default_location`]. `weather init` asks for it.
+pub const DEFAULT_LOCATION: &str = "home";
+
+/// Keys accepted by [`Config::set_value`].
+pub const SETTINGS: &[&str] = &[
+    "units",
+    "language",
+    "cache_ttl",
+    "offline_fallback",
+    "retry_attempts",
+    "retry_max_delay_ms",
+    "circuit_failure_threshold",
+    "circuit_cooldown",
+    "proxy",
+    "ca_bundle",
+    "tls_insecure_skip_verify",
+    "log_file",
+    "auto_locate",
+    "key_rotation",
+    "history",
+    "emoji",
+    "alert_wind_speed",
+    "alert_temperature_above",
+    "alert_temperature_below",
+    "alert_precipitation",
+    "webhook_url",
+    "rate_limit.<provider>",
+    "daily_quota.<provider>",
+    "monthly_quota.<provider>",
+];
 
 /// Configuration for a single provider (e.g., API key).
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ProviderConfig {
     pub api_key: String,
+    /// Further keys used alongside `api_key`, see [`KeyRotation`].
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub extra_api_keys: Vec<String>,
+}
+
+impl ProviderConfig {
+    /// `api_key` followed by the extra keys.
+    pub fn api_keys(&self) -> impl Iterator<Item = &str> {
+        std::iter::once(self.api_key.as_str()).chain(self.extra_api_keys.iter().map(String::as_str))
+    }
+}
+
+/// How requests are spread over the API keys of a provider that has several. Either
+/// way, a key that is rejected or rate limited makes the request go to the next one.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum KeyRotation {
+    /// Each request starts at the key after the previous request's.
+    #[default]
+    RoundRobin,
+    /// Always start at the first key.
+    Failover,
+}
+
+impl TryFrom<&str> for KeyRotation {
+    type Error = WeatherError;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        match value {
+            "round-robin" => Ok(KeyRotation::RoundRobin),
+            "failover" => Ok(KeyRotation::Failover),
+            _ => Err(WeatherError::InvalidSetting(format!(
+                "Invalid key_rotation '{value}'. Expected round-robin or failover."
+            ))),
+        }
+    }
+}
+
+/// Call quota of a provider plan; `None` means unlimited.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Quota {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub daily: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub monthly: Option<u64>,
 }
 
 /// Top-level configuration stored on disk.
@@ -20,18 +117,160 @@ pub struct Config {
     /// Example TOML:
     /// [providers.openweather]
     /// api_key = "..."
+    ///
+    /// Empty on disk while the keys are encrypted, see `encrypted_providers`.
+    #[serde(default)]
     pub providers: HashMap<String, ProviderConfig>,
+
+    /// The `providers` table as an ASCII-armored age file, while the API keys are
+    /// encrypted with a passphrase (`encryption` feature).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub encrypted_providers: Option<String>,
+
+    /// The passphrase that unlocked `encrypted_providers`, to encrypt them again on save;
+    /// set by [`Config::unlock`].
+    #[cfg(all(feature = "encryption", not(target_arch = "wasm32")))]
+    #[serde(skip)]
+    pub passphrase: Option<SecretString>,
+
+    /// Unit system for presenting values: "metric" (default) or "imperial".
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub units: Option<Units>,
+
+    /// Preferred language code, e.g. "uk". Used for CLI messages and provider condition
+    /// descriptions.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub language: Option<String>,
+
+    /// How long cached responses are served before asking the provider again, in seconds.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cache_ttl_secs: Option<u64>,
+
+    /// Serve the last cached response (marked as stale) when the provider is unreachable.
+    /// Enabled unless set to `false`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub offline_fallback: Option<bool>,
+
+    /// Total attempts per provider request, including the first (default 3).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub retry_attempts: Option<u32>,
+
+    /// Longest single wait between retries, in milliseconds (default 10000).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub retry_max_delay_ms: Option<u64>,
+
+    /// Consecutive provider failures before it is skipped for a while (default 5).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub circuit_failure_threshold: Option<u32>,
+
+    /// How long a failing provider is skipped, in seconds (default 300).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub circuit_cooldown_secs: Option<u64>,
+
+    /// Proxy for all provider requests, e.g. `http://proxy.corp:3128`. Overrides the
+    /// `HTTPS_PROXY`/`HTTP_PROXY` environment variables, which are used otherwise.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub proxy: Option<String>,
+
+    /// Extra PEM file with root certificates to trust, e.g. a corporate proxy CA.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ca_bundle: Option<PathBuf>,
+
+    /// Disable TLS certificate verification. Dangerous: anyone on the network path can
+    /// read and alter provider traffic, including API keys.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tls_insecure_skip_verify: Option<bool>,
+
+    /// Append diagnostic logs (see `--log-level`) to this file instead of stderr.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub log_file: Option<PathBuf>,
+
+    /// Allow `weather show --here` to look up the location of this machine's IP address
+    /// with a third-party service. Enabled unless set to `false`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub auto_locate: Option<bool>,
+
+    /// How requests are spread over several API keys of one provider (default
+    /// round-robin).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub key_rotation: Option<KeyRotation>,
+
+    /// Record every lookup in a local journal, shown by `weather history`. Enabled unless
+    /// set to `false`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub history: Option<bool>,
+
+    /// Show weather icons such as ☁ in compact output (`--oneline`, status bars). Enabled
+    /// unless set to `false`, e.g. for a font without them.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub emoji: Option<bool>,
+
+    /// `weather monitor` alerts when the wind is stronger than this, in m/s.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub alert_wind_speed_mps: Option<f64>,
+
+    /// `weather monitor` alerts when it is warmer than this, in °C.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub alert_temperature_above_c: Option<f64>,
+
+    /// `weather monitor` alerts when it is colder than this, in °C.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub alert_temperature_below_c: Option<f64>,
+
+    /// `weather monitor` alerts when more precipitation than this is reported, in mm.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub alert_precipitation_mm: Option<f64>,
+
+    /// Where `weather monitor --notify webhook` POSTs new alerts as JSON.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub webhook_url: Option<String>,
+
+    /// Requests per minute allowed for each provider; `0` means unlimited.
+    ///
+    /// Example TOML:
+    /// ```toml
+    /// [rate_limits]
+    /// openweather = 60
+    /// ```
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub rate_limits: HashMap<String, u32>,
+
+    /// Call quotas shown by `weather usage`; `0` means unlimited.
+    ///
+    /// Example TOML:
+    /// [quotas.openweather]
+    /// daily = 1000
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub quotas: HashMap<String, Quota>,
+
+    /// Places chosen for ambiguous addresses, keyed by normalized address.
+    ///
+    /// Example TOML:
+    /// [places.springfield]
+    /// name = "Springfield"
+    /// region = "Illinois"
+    /// country = "US"
+    /// lat = 39.799
+    /// lon = -89.644
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub places: HashMap<String, Place>,
+
+    /// Named locations usable wherever a location is expected, keyed by lowercase name.
+    ///
+    /// Example TOML:
+    /// ```toml
+    /// [locations]
+    /// home = "Kyiv, UA"
+    /// cabin = { lat = 48.16, lon = 24.5 }
+    /// ```
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub locations: HashMap<String, Location>,
 }
 
 impl Config {
     /// Return the default provider as a strongly-typed ProviderId.
     pub fn default_provider_id(&self) -> Result<ProviderId> {
-        let s = self.default_provider.as_ref().ok_or_else(|| {
-            anyhow::anyhow!(
-                "No default provider configured.\n\
-                 Hint: run `weather configure <provider>` (e.g. `weather configure openweather`) first."
-            )
-        })?;
+        let s = self.default_provider.as_ref().ok_or(WeatherError::NoDefaultProvider)?;
 
         ProviderId::try_from(s.as_str())
     }
@@ -49,15 +288,202 @@ impl Config {
         self.default_provider = Some(id.as_str().to_string());
     }
 
+    /// Unit system to present values in, falling back to metric.
+    pub fn units(&self) -> Units {
+        self.units.unwrap_or_default()
+    }
+
+    /// Time-to-live for cached responses, 10 minutes unless configured.
+    pub fn cache_ttl(&self) -> Duration {
+        Duration::from_secs(self.cache_ttl_secs.unwrap_or(600))
+    }
+
+    /// Whether to fall back to stale cached responses when the network is down.
+    pub fn offline_fallback(&self) -> bool {
+        self.offline_fallback.unwrap_or(true)
+    }
+
+    /// Retry policy for provider requests, with config overrides applied.
+    pub fn retry_policy(&self) -> RetryPolicy {
+        let default = RetryPolicy::default();
+
+        RetryPolicy {
+            max_attempts: self.retry_attempts.unwrap_or(default.max_attempts).max(1),
+            max_delay: self
+                .retry_max_delay_ms
+                .map(Duration::from_millis)
+                .unwrap_or(default.max_delay),
+            ..default
+        }
+    }
+
+    pub fn circuit_failure_threshold(&self) -> u32 {
+        self.circuit_failure_threshold.unwrap_or(5)
+    }
+
+    pub fn circuit_cooldown(&self) -> Duration {
+        Duration::from_secs(self.circuit_cooldown_secs.unwrap_or(300))
+    }
+
+    pub fn tls_insecure_skip_verify(&self) -> bool {
+        self.tls_insecure_skip_verify.unwrap_or(false)
+    }
+
+    pub fn auto_locate(&self) -> bool {
+        self.auto_locate.unwrap_or(true)
+    }
+
+    pub fn key_rotation(&self) -> KeyRotation {
+        self.key_rotation.unwrap_or_default()
+    }
+
+    pub fn history(&self) -> bool {
+        self.history.unwrap_or(true)
+    }
+
+    pub fn emoji(&self) -> bool {
+        self.emoji.unwrap_or(true)
+    }
+
+    /// The limits `weather monitor` checks; none unless configured.
+    pub fn alert_thresholds(&self) -> AlertThresholds {
+        AlertThresholds {
+            wind_speed: self.alert_wind_speed_mps.map(WindSpeed::from_mps),
+            temperature_above: self.alert_temperature_above_c.map(Temperature::from_celsius),
+            temperature_below: self.alert_temperature_below_c.map(Temperature::from_celsius),
+            precipitation: self.alert_precipitation_mm.map(Precipitation::from_mm),
+        }
+    }
+
+    /// Requests per minute allowed for a provider, falling back to its free-tier limit.
+    /// `None` if unlimited.
+    pub fn rate_limit(&self, id: ProviderId) -> Option<u32> {
+        match self.rate_limits.get(id.as_str()) {
+            Some(0) => None,
+            Some(&limit) => Some(limit),
+            None => id.default_rate_limit(),
+        }
+    }
+
+    /// Call quota for a provider, falling back to its free-tier limits per period.
+    pub fn quota(&self, id: ProviderId) -> Quota {
+        let configured = self.quotas.get(id.as_str()).copied().unwrap_or_default();
+        let default = id.default_quota();
+        let pick = |set: Option<u64>, default| match set {
+            Some(0) => None,
+            Some(limit) => Some(limit),
+            None => default,
+        };
+
+        Quota {
+            daily: pick(configured.daily, default.daily),
+            monthly: pick(configured.monthly, default.monthly),
+        }
+    }
+
+    /// Set a single setting by its key, as used by `weather config set <key> <value>`.
+    pub fn set_value(&mut self, key: &str, value: &str) -> Result<()> {
+        match key {
+            "units" => self.units = Some(Units::try_from(value)?),
+            "language" => self.language = Some(parse_language(value)?),
+            "cache_ttl" => self.cache_ttl_secs = Some(parse_number(key, value, "seconds")?),
+            "offline_fallback" => self.offline_fallback = Some(parse_bool(key, value)?),
+            "retry_attempts" => self.retry_attempts = Some(parse_number(key, value, "attempts")?),
+            "retry_max_delay_ms" => {
+                self.retry_max_delay_ms = Some(parse_number(key, value, "milliseconds")?)
+            }
+            "circuit_failure_threshold" => {
+                self.circuit_failure_threshold = Some(parse_number(key, value, "failures")?)
+            }
+            "circuit_cooldown" => {
+                self.circuit_cooldown_secs = Some(parse_number(key, value, "seconds")?)
+            }
+            // An empty value goes back to the environment variables.
+            "proxy" if value.is_empty() => self.proxy = None,
+            "proxy" => {
+                #[cfg(not(target_arch = "wasm32"))]
+                http::proxy(value)?;
+                self.proxy = Some(value.to_string());
+            }
+            "ca_bundle" if value.is_empty() => self.ca_bundle = None,
+            "ca_bundle" => {
+                let path = Path::new(value);
+                if !path.is_file() {
+                    return Err(WeatherError::InvalidSetting(format!(
+                        "CA bundle '{value}' does not exist or is not a file."
+                    )));
+                }
+                self.ca_bundle = Some(path.to_path_buf());
+            }
+            "tls_insecure_skip_verify" => {
+                self.tls_insecure_skip_verify = Some(parse_bool(key, value)?)
+            }
+            "log_file" if value.is_empty() => self.log_file = None,
+            "log_file" => self.log_file = Some(PathBuf::from(value)),
+            "auto_locate" => self.auto_locate = Some(parse_bool(key, value)?),
+            "history" => self.history = Some(parse_bool(key, value)?),
+            "emoji" => self.emoji = Some(parse_bool(key, value)?),
+            "key_rotation" => self.key_rotation = Some(KeyRotation::try_from(value)?),
+            // An empty value stops checking the threshold.
+            "alert_wind_speed" => self.alert_wind_speed_mps = parse_limit(key, value, "m/s")?,
+            "alert_temperature_above" => {
+                self.alert_temperature_above_c = parse_limit(key, value, "°C")?
+            }
+            "alert_temperature_below" => {
+                self.alert_temperature_below_c = parse_limit(key, value, "°C")?
+            }
+            "alert_precipitation" => self.alert_precipitation_mm = parse_limit(key, value, "mm")?,
+            "webhook_url" if value.is_empty() => self.webhook_url = None,
+            "webhook_url" => {
+                let url = reqwest::Url::parse(value).ok().filter(|url| url.has_host());
+                if url.is_none_or(|url| !matches!(url.scheme(), "http" | "https")) {
+                    return Err(WeatherError::InvalidSetting(format!(
+                        "Invalid webhook_url '{value}'. Expected an http:// or https:// URL."
+                    )));
+                }
+                self.webhook_url = Some(value.to_string());
+            }
+            _ if key.starts_with("rate_limit.") => {
+                let id = ProviderId::try_from(&key["rate_limit.".len()..])?;
+                let limit = parse_number(key, value, "requests per minute")?;
+                self.rate_limits.insert(id.as_str().to_string(), limit);
+            }
+            _ if key.starts_with("daily_quota.") => {
+                let id = ProviderId::try_from(&key["daily_quota.".len()..])?;
+                let limit = parse_number(key, value, "calls per day")?;
+                self.quotas.entry(id.as_str().to_string()).or_default().daily = Some(limit);
+            }
+            _ if key.starts_with("monthly_quota.") => {
+                let id = ProviderId::try_from(&key["monthly_quota.".len()..])?;
+                let limit = parse_number(key, value, "calls per month")?;
+                self.quotas.entry(id.as_str().to_string()).or_default().monthly = Some(limit);
+            }
+            _ => {
+                return Err(WeatherError::InvalidSetting(format!(
+                    "Unknown setting '{key}'. Supported settings: {}.",
+                    SETTINGS.join(", ")
+                )));
+            }
+        }
+
+        Ok(())
+    }
+
     /// Load config from disk, or return an empty default if it doesn't exist yet.
+    #[cfg(not(target_arch = "wasm32"))]
     pub fn load() -> Result<Self> {
-        let path = Self::config_file_path()?;
+        Self::load_from(&Self::config_file_path()?)
+    }
+
+    /// Load config from `path`, or return an empty default if it doesn't exist yet.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn load_from(path: &Path) -> Result<Self> {
         if !path.exists() {
             // First run: no config file, return empty.
             return Ok(Self::default());
         }
 
-        let contents = fs::read_to_string(&path)
+        let contents = fs::read_to_string(path)
             .with_context(|| format!("Failed to read config file: {}", path.display()))?;
 
         let cfg: Config = toml::from_str(&contents)
@@ -67,41 +493,104 @@ impl Config {
     }
 
     /// Save config to disk, creating parent directories as needed.
+    #[cfg(not(target_arch = "wasm32"))]
     pub fn save(&self) -> Result<()> {
-        let path = Self::config_file_path()?;
+        self.save_to(&Self::config_file_path()?)
+    }
 
+    /// Save config to `path`, creating parent directories as needed.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn save_to(&self, path: &Path) -> Result<()> {
         if let Some(parent) = path.parent() {
             fs::create_dir_all(parent).with_context(|| {
                 format!("Failed to create config directory: {}", parent.display())
             })?;
         }
 
-        let toml =
-            toml::to_string_pretty(self).context("Failed to serialize configuration to TOML")?;
+        let toml = self.to_toml()?;
 
-        fs::write(&path, toml)
+        fs::write(path, toml)
             .with_context(|| format!("Failed to write config file: {}", path.display()))?;
 
         Ok(())
     }
 
-    /// Path to the config file.
-    pub fn config_file_path() -> Result<PathBuf> {
-        let dirs = ProjectDirs::from("dev", "weather-task", "weather-cli")
-            .ok_or_else(|| anyhow!("Could not determine platform config directory"))?;
+    /// Parse a config file's contents. Encrypted API keys stay locked.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn from_toml(contents: &str) -> Result<Self> {
+        toml::from_str(contents).context("Failed to parse configuration TOML")
+    }
 
-        Ok(dirs.config_dir().join("config.toml"))
+    /// The config as written to disk: with the API keys encrypted, if they are.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn to_toml(&self) -> Result<String> {
+        #[cfg(feature = "encryption")]
+        if let Some(passphrase) = &self.passphrase {
+            let plaintext =
+                toml::to_string(&self.providers).context("Failed to serialize API keys")?;
+            let on_disk = Config {
+                providers: HashMap::new(),
+                encrypted_providers: Some(secrets::encrypt(&plaintext, passphrase)?),
+                ..self.clone()
+            };
+            return toml::to_string_pretty(&on_disk)
+                .context("Failed to serialize configuration to TOML");
+        }
+
+        // Never store keys in plaintext next to encrypted ones.
+        if self.is_locked() && !self.providers.is_empty() {
+            return Err(WeatherError::ConfigLocked);
+        }
+
+        toml::to_string_pretty(self).context("Failed to serialize configuration to TOML")
+    }
+
+    /// Path to the config file: `WEATHER_CONFIG` if set, otherwise `config.toml` in the
+    /// platform config directory.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn config_file_path() -> Result<PathBuf> {
+        match std::env::var_os(CONFIG_ENV) {
+            Some(path) if !path.is_empty() => Ok(PathBuf::from(path)),
+            _ => Ok(project_dirs()?.config_dir().join("config.toml")),
+        }
     }
 
     /// Convenience helper: set/replace a provider API key and optionally set default provider.
     pub fn upsert_provider_api_key(&mut self, provider_id: ProviderId, api_key: String) {
-        self.providers.insert(provider_id.as_str().to_string(), ProviderConfig { api_key });
+        self.providers.insert(
+            provider_id.as_str().to_string(),
+            ProviderConfig { api_key, extra_api_keys: Vec::new() },
+        );
 
         if self.default_provider.is_none() {
             self.default_provider = Some(provider_id.to_string());
         }
     }
 
+    /// Add `api_key` to the keys of a provider, keeping the ones it has; see
+    /// [`Config::upsert_provider_api_key`] for the rest. Returns `false` if the provider
+    /// already has this key.
+    pub fn add_provider_api_key(&mut self, provider_id: ProviderId, api_key: String) -> bool {
+        let Some(provider) = self.providers.get_mut(provider_id.as_str()) else {
+            self.upsert_provider_api_key(provider_id, api_key);
+            return true;
+        };
+        if provider.api_keys().any(|key| key == api_key) {
+            return false;
+        }
+
+        provider.extra_api_keys.push(api_key);
+        true
+    }
+
+    /// Every API key of a provider, in the order they are used; empty if none.
+    pub fn provider_api_keys(&self, provider_id: ProviderId) -> Vec<&str> {
+        self.providers
+            .get(provider_id.as_str())
+            .map(|cfg| cfg.api_keys().collect())
+            .unwrap_or_default()
+    }
+
     /// Returns API key for a provider, if present.
     pub fn provider_api_key(&self, provider_id: ProviderId) -> Option<&str> {
         self.providers.get(provider_id.as_str()).map(|cfg| cfg.api_key.as_str())
@@ -110,6 +599,218 @@ impl Config {
     pub fn is_provider_configured(&self, provider_id: ProviderId) -> bool {
         self.provider_api_key(provider_id).is_some()
     }
+
+    /// Whether the API keys are stored encrypted.
+    pub fn is_encrypted(&self) -> bool {
+        self.encrypted_providers.is_some()
+    }
+
+    /// Whether the API keys are encrypted and not unlocked yet, so none are available.
+    pub fn is_locked(&self) -> bool {
+        #[cfg(all(feature = "encryption", not(target_arch = "wasm32")))]
+        return self.is_encrypted() && self.passphrase.is_none();
+        #[cfg(not(all(feature = "encryption", not(target_arch = "wasm32"))))]
+        self.is_encrypted()
+    }
+
+    /// A copy safe to show: every API key and the webhook URL masked but for their last
+    /// characters, and no passphrase. Encrypted keys are left as they are.
+    pub fn redacted(&self) -> Self {
+        let mut cfg = self.clone();
+        for provider in cfg.providers.values_mut() {
+            provider.api_key = mask(&provider.api_key);
+            for key in &mut provider.extra_api_keys {
+                *key = mask(key);
+            }
+        }
+        // Webhook URLs often carry a token, e.g. for Slack.
+        cfg.webhook_url = cfg.webhook_url.as_deref().map(mask);
+        #[cfg(all(feature = "encryption", not(target_arch = "wasm32")))]
+        {
+            cfg.passphrase = None;
+        }
+        cfg
+    }
+
+    /// Take over everything `other` sets: its settings and providers, saved locations,
+    /// places and limits replace those of the same name here, the rest is kept.
+    ///
+    /// Both configs must be unlocked. If only `other` has encrypted API keys, the merged
+    /// keys are encrypted with its passphrase, so importing never stores them in
+    /// plaintext.
+    pub fn merge(&mut self, other: Config) -> Result<()> {
+        if self.is_locked() || other.is_locked() {
+            return Err(WeatherError::ConfigLocked);
+        }
+
+        #[cfg(all(feature = "encryption", not(target_arch = "wasm32")))]
+        if self.passphrase.is_none() && other.passphrase.is_some() {
+            self.encrypted_providers = other.encrypted_providers;
+            self.passphrase = other.passphrase;
+        }
+
+        self.default_provider = other.default_provider.or(self.default_provider.take());
+        self.units = other.units.or(self.units);
+        self.language = other.language.or(self.language.take());
+        self.cache_ttl_secs = other.cache_ttl_secs.or(self.cache_ttl_secs);
+        self.offline_fallback = other.offline_fallback.or(self.offline_fallback);
+        self.retry_attempts = other.retry_attempts.or(self.retry_attempts);
+        self.retry_max_delay_ms = other.retry_max_delay_ms.or(self.retry_max_delay_ms);
+        self.circuit_failure_threshold =
+            other.circuit_failure_threshold.or(self.circuit_failure_threshold);
+        self.circuit_cooldown_secs = other.circuit_cooldown_secs.or(self.circuit_cooldown_secs);
+        self.proxy = other.proxy.or(self.proxy.take());
+        self.ca_bundle = other.ca_bundle.or(self.ca_bundle.take());
+        self.tls_insecure_skip_verify =
+            other.tls_insecure_skip_verify.or(self.tls_insecure_skip_verify);
+        self.log_file = other.log_file.or(self.log_file.take());
+        self.auto_locate = other.auto_locate.or(self.auto_locate);
+        self.key_rotation = other.key_rotation.or(self.key_rotation);
+        self.history = other.history.or(self.history);
+        self.emoji = other.emoji.or(self.emoji);
+        self.alert_wind_speed_mps = other.alert_wind_speed_mps.or(self.alert_wind_speed_mps);
+        self.alert_temperature_above_c =
+            other.alert_temperature_above_c.or(self.alert_temperature_above_c);
+        self.alert_temperature_below_c =
+            other.alert_temperature_below_c.or(self.alert_temperature_below_c);
+        self.alert_precipitation_mm = other.alert_precipitation_mm.or(self.alert_precipitation_mm);
+        self.webhook_url = other.webhook_url.or(self.webhook_url.take());
+
+        self.providers.extend(other.providers);
+        self.rate_limits.extend(other.rate_limits);
+        self.quotas.extend(other.quotas);
+        self.places.extend(other.places);
+        self.locations.extend(other.locations);
+        Ok(())
+    }
+
+    /// The place remembered for `address`, if any; see [`Config::remember_place`].
+    pub fn remembered_place(&self, address: &str) -> Option<&Place> {
+        self.places.get(&normalize_address(address))
+    }
+
+    /// Use `place` whenever `address` (in any case or spacing) is asked for.
+    pub fn remember_place(&mut self, address: &str, place: Place) {
+        self.places.insert(normalize_address(address), place);
+    }
+
+    /// Save `location` under `name` (letters, digits, `-` and `_`), replacing any
+    /// location saved under it before.
+    pub fn add_location(&mut self, name: &str, location: Location) -> Result<()> {
+        let valid =
+            !name.is_empty() && name.chars().all(|c| c.is_alphanumeric() || c == '-' || c == '_');
+        if !valid {
+            return Err(WeatherError::InvalidSetting(format!(
+                "Invalid location name '{name}'. Use letters, digits, '-' and '_'."
+            )));
+        }
+        location.validate()?;
+
+        self.locations.insert(name.to_lowercase(), location);
+        Ok(())
+    }
+
+    /// The location saved under `name`, in any case.
+    pub fn saved_location(&self, name: &str) -> Option<&Location> {
+        self.locations.get(&name.to_lowercase())
+    }
+
+    /// The location to use when none is given: the one saved as [`DEFAULT_LOCATION`].
+    pub fn default_location(&self) -> Option<&Location> {
+        self.saved_location(DEFAULT_LOCATION)
+    }
+
+    pub fn remove_location(&mut self, name: &str) -> Option<Location> {
+        self.locations.remove(&name.to_lowercase())
+    }
+}
+
+#[cfg(all(feature = "encryption", not(target_arch = "wasm32")))]
+impl Config {
+    /// Decrypt the API keys with `passphrase`. Does nothing if they are not encrypted.
+    pub fn unlock(&mut self, passphrase: SecretString) -> Result<()> {
+        let Some(encrypted) = &self.encrypted_providers else {
+            return Ok(());
+        };
+
+        let plaintext = secrets::decrypt(encrypted, &passphrase)?;
+        self.providers = toml::from_str(&plaintext)
+            .map_err(|err| err.into_weather_error("Failed to parse decrypted API keys".into()))?;
+        self.passphrase = Some(passphrase);
+        Ok(())
+    }
+
+    /// Store the API keys encrypted with `passphrase` from the next save on.
+    pub fn encrypt_providers(&mut self, passphrase: SecretString) -> Result<()> {
+        if self.is_locked() {
+            return Err(WeatherError::ConfigLocked);
+        }
+
+        let plaintext = toml::to_string(&self.providers).context("Failed to serialize API keys")?;
+        self.encrypted_providers = Some(secrets::encrypt(&plaintext, &passphrase)?);
+        self.passphrase = Some(passphrase);
+        Ok(())
+    }
+
+    /// Store the API keys in plaintext again from the next save on.
+    pub fn decrypt_providers(&mut self) -> Result<()> {
+        if self.is_locked() {
+            return Err(WeatherError::ConfigLocked);
+        }
+
+        self.encrypted_providers = None;
+        self.passphrase = None;
+        Ok(())
+    }
+}
+
+/// Platform-specific project directories (config, cache, data).
+#[cfg(not(target_arch = "wasm32"))]
+pub(crate) fn project_dirs() -> Result<ProjectDirs> {
+    ProjectDirs::from("dev", "weather-task", "weather-cli").ok_or(WeatherError::NoPlatformDirs)
+}
+
+fn parse_bool(key: &str, value: &str) -> Result<bool> {
+    value.parse().map_err(|_| {
+        WeatherError::InvalidSetting(format!("Invalid {key} '{value}'. Expected true or false."))
+    })
+}
+
+/// A number of `unit`, or `None` for an empty value.
+fn parse_limit(key: &str, value: &str, unit: &str) -> Result<Option<f64>> {
+    if value.is_empty() {
+        return Ok(None);
+    }
+
+    match value.parse::<f64>() {
+        Ok(limit) if limit.is_finite() => Ok(Some(limit)),
+        _ => Err(WeatherError::InvalidSetting(format!(
+            "Invalid {key} '{value}'. Expected a number in {unit}, or nothing to turn it off."
+        ))),
+    }
+}
+
+fn parse_number<T: std::str::FromStr>(key: &str, value: &str, what: &str) -> Result<T> {
+    value.parse().map_err(|_| {
+        WeatherError::InvalidSetting(format!(
+            "Invalid {key} '{value}'. Expected a number of {what}."
+        ))
+    })
+}
+
+/// Accept a language tag like "uk", "pt_BR" or "zh-TW"; providers validate the rest.
+pub(crate) fn parse_language(value: &str) -> Result<String> {
+    let valid = !value.is_empty()
+        && value.len() <= 16
+        && value.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+
+    if !valid {
+        return Err(WeatherError::InvalidSetting(format!(
+            "Invalid language '{value}'. Expected a code such as 'en' or 'uk'."
+        )));
+    }
+
+    Ok(value.to_string())
 }
 
 #[cfg(test)]
@@ -122,6 +823,7 @@ mod tests {
         let cfg = Config::default();
         let err = cfg.default_provider_id().unwrap_err();
 
+        assert!(matches!(err, WeatherError::NoDefaultProvider));
         assert!(err.to_string().contains("No default provider configured"));
     }
 
@@ -168,4 +870,306 @@ mod tests {
         let default = cfg.default_provider_id().expect("default provider must exist");
         assert_eq!(default, ProviderId::WeatherApi);
     }
+
+    #[test]
+    fn units_default_to_metric_and_can_be_set() {
+        let mut cfg = Config::default();
+        assert_eq!(cfg.units(), Units::Metric);
+
+        cfg.set_value("units", "imperial").expect("units must be accepted");
+        assert_eq!(cfg.units(), Units::Imperial);
+
+        assert!(cfg.set_value("units", "kelvin").is_err());
+        assert!(matches!(cfg.set_value("colour", "blue"), Err(WeatherError::InvalidSetting(_))));
+    }
+
+    #[test]
+    fn language_setting_is_validated() {
+        let mut cfg = Config::default();
+
+        cfg.set_value("language", "uk").expect("language must be accepted");
+        assert_eq!(cfg.language.as_deref(), Some("uk"));
+
+        cfg.set_value("language", "pt_BR").expect("regional codes must be accepted");
+        assert_eq!(cfg.language.as_deref(), Some("pt_BR"));
+
+        assert!(cfg.set_value("language", "").is_err());
+        assert!(cfg.set_value("language", "uk; rm -rf").is_err());
+    }
+
+    #[test]
+    fn cache_ttl_defaults_and_can_be_set() {
+        let mut cfg = Config::default();
+        assert_eq!(cfg.cache_ttl(), Duration::from_secs(600));
+
+        cfg.set_value("cache_ttl", "60").expect("seconds must be accepted");
+        assert_eq!(cfg.cache_ttl(), Duration::from_secs(60));
+
+        assert!(cfg.set_value("cache_ttl", "soon").is_err());
+    }
+
+    #[test]
+    fn proxy_setting_is_validated_and_can_be_cleared() {
+        let mut cfg = Config::default();
+
+        cfg.set_value("proxy", "http://proxy.corp:3128").expect("proxy URL must be accepted");
+        assert_eq!(cfg.proxy.as_deref(), Some("http://proxy.corp:3128"));
+
+        assert!(cfg.set_value("proxy", "::not a proxy::").is_err());
+        assert_eq!(cfg.proxy.as_deref(), Some("http://proxy.corp:3128"));
+
+        cfg.set_value("proxy", "").unwrap();
+        assert_eq!(cfg.proxy, None);
+    }
+
+    #[test]
+    fn tls_settings_are_validated() {
+        let mut cfg = Config::default();
+        assert!(!cfg.tls_insecure_skip_verify());
+
+        cfg.set_value("tls_insecure_skip_verify", "true").unwrap();
+        assert!(cfg.tls_insecure_skip_verify());
+        assert!(cfg.set_value("tls_insecure_skip_verify", "yes please").is_err());
+
+        assert!(cfg.set_value("ca_bundle", "/definitely/not/here.pem").is_err());
+        assert_eq!(cfg.ca_bundle, None);
+
+        cfg.set_value("log_file", "/tmp/weather.log").unwrap();
+        assert_eq!(cfg.log_file, Some(PathBuf::from("/tmp/weather.log")));
+        cfg.set_value("log_file", "").unwrap();
+        assert_eq!(cfg.log_file, None);
+    }
+
+    #[test]
+    fn alert_settings_are_validated_and_can_be_cleared() {
+        let mut cfg = Config::default();
+        assert!(cfg.alert_thresholds().is_empty());
+
+        cfg.set_value("alert_wind_speed", "15").unwrap();
+        cfg.set_value("alert_temperature_below", "-10.5").unwrap();
+        let thresholds = cfg.alert_thresholds();
+        assert_eq!(thresholds.wind_speed, Some(WindSpeed::from_mps(15.0)));
+        assert_eq!(thresholds.temperature_below, Some(Temperature::from_celsius(-10.5)));
+        assert!(cfg.set_value("alert_precipitation", "lots").is_err());
+
+        cfg.set_value("alert_wind_speed", "").unwrap();
+        assert_eq!(cfg.alert_thresholds().wind_speed, None);
+
+        assert!(cfg.set_value("webhook_url", "ftp://example.com").is_err());
+        cfg.set_value("webhook_url", "https://hooks.example.com/T0/B0/secret").unwrap();
+        assert!(cfg.redacted().webhook_url.unwrap().ends_with("cret"));
+    }
+
+    #[test]
+    fn locations_are_saved_by_name() {
+        let mut cfg = Config::default();
+        cfg.add_location("Home", Location::from("Kyiv, UA")).unwrap();
+        cfg.add_location("cabin", Location::from("48.16,24.5")).unwrap();
+
+        let mut cfg: Config = toml::from_str(&toml::to_string(&cfg).unwrap()).unwrap();
+        assert_eq!(cfg.saved_location("home"), Some(&Location::Address("Kyiv, UA".to_string())));
+        assert_eq!(cfg.saved_location("CABIN"), Some(&Location::coordinates(48.16, 24.5)));
+
+        assert!(cfg.add_location("my home", Location::from("Kyiv")).is_err());
+        assert!(cfg.add_location("work", Location::from("  ")).is_err());
+        assert_eq!(cfg.default_location(), cfg.saved_location("home"));
+        assert!(cfg.remove_location("home").is_some());
+        assert!(cfg.remove_location("home").is_none());
+        assert!(cfg.default_location().is_none());
+    }
+
+    #[test]
+    fn auto_locate_can_be_disabled() {
+        let mut cfg = Config::default();
+        assert!(cfg.auto_locate());
+
+        cfg.set_value("auto_locate", "false").unwrap();
+        assert!(!cfg.auto_locate());
+        assert!(cfg.set_value("auto_locate", "never").is_err());
+    }
+
+    #[test]
+    fn extra_api_keys_are_added_after_the_first() {
+        let mut cfg = Config::default();
+        let id = ProviderId::WeatherApi;
+
+        assert!(cfg.add_provider_api_key(id, "FIRST".into()));
+        assert!(cfg.add_provider_api_key(id, "SECOND".into()));
+        assert!(!cfg.add_provider_api_key(id, "FIRST".into()));
+        assert_eq!(cfg.provider_api_keys(id), ["FIRST", "SECOND"]);
+        assert_eq!(cfg.provider_api_key(id), Some("FIRST"));
+        assert_eq!(cfg.default_provider_id().unwrap(), id);
+
+        cfg.upsert_provider_api_key(id, "ONLY".into());
+        assert_eq!(cfg.provider_api_keys(id), ["ONLY"]);
+
+        assert_eq!(cfg.key_rotation(), KeyRotation::RoundRobin);
+        cfg.set_value("key_rotation", "failover").unwrap();
+        assert_eq!(cfg.key_rotation(), KeyRotation::Failover);
+        assert!(cfg.set_value("key_rotation", "random").is_err());
+    }
+
+    #[test]
+    fn load_from_reads_what_save_to_wrote() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("nested").join("weather.toml");
+
+        assert!(Config::load_from(&path).unwrap().providers.is_empty());
+
+        let mut cfg = Config::default();
+        cfg.upsert_provider_api_key(ProviderId::OpenWeather, "OPEN_KEY".into());
+        cfg.save_to(&path).unwrap();
+
+        let cfg = Config::load_from(&path).unwrap();
+        assert_eq!(cfg.provider_api_key(ProviderId::OpenWeather), Some("OPEN_KEY"));
+    }
+
+    #[test]
+    fn retry_policy_applies_overrides() {
+        let mut cfg = Config::default();
+        assert_eq!(cfg.retry_policy(), RetryPolicy::default());
+
+        cfg.set_value("retry_attempts", "5").unwrap();
+        cfg.set_value("retry_max_delay_ms", "2500").unwrap();
+
+        let policy = cfg.retry_policy();
+        assert_eq!(policy.max_attempts, 5);
+        assert_eq!(policy.max_delay, Duration::from_millis(2500));
+
+        cfg.set_value("retry_attempts", "0").unwrap();
+        assert_eq!(cfg.retry_policy().max_attempts, 1);
+    }
+
+    #[test]
+    fn rate_limits_default_per_provider_and_can_be_overridden() {
+        let mut cfg = Config::default();
+        assert_eq!(cfg.rate_limit(ProviderId::OpenWeather), Some(60));
+        assert_eq!(cfg.rate_limit(ProviderId::WeatherApi), None);
+
+        cfg.set_value("rate_limit.weatherapi", "100").unwrap();
+        cfg.set_value("rate_limit.OpenWeather", "0").unwrap();
+        assert_eq!(cfg.rate_limit(ProviderId::WeatherApi), Some(100));
+        assert_eq!(cfg.rate_limit(ProviderId::OpenWeather), None);
+
+        assert!(cfg.set_value("rate_limit.nowhere", "10").is_err());
+        assert!(cfg.set_value("rate_limit.openweather", "lots").is_err());
+    }
+
+    #[test]
+    fn quotas_fall_back_to_provider_defaults() {
+        let mut cfg = Config::default();
+        assert_eq!(cfg.quota(ProviderId::OpenWeather), ProviderId::OpenWeather.default_quota());
+
+        cfg.set_value("daily_quota.openweather", "1000").unwrap();
+        cfg.set_value("monthly_quota.openweather", "0").unwrap();
+        assert_eq!(cfg.quota(ProviderId::OpenWeather), Quota { daily: Some(1000), monthly: None });
+
+        let saved = toml::to_string(&cfg).unwrap();
+        assert!(saved.contains("[quotas.openweather]"));
+
+        assert!(cfg.set_value("daily_quota.openweather", "-1").is_err());
+    }
+
+    #[test]
+    fn config_without_units_still_parses() {
+        let cfg: Config = toml::from_str(
+            r#"
+            default_provider = "openweather"
+
+            [providers.openweather]
+            api_key = "KEY"
+            "#,
+        )
+        .expect("legacy config must parse");
+
+        assert_eq!(cfg.units, None);
+        assert!(cfg.is_provider_configured(ProviderId::OpenWeather));
+    }
+
+    #[test]
+    fn redacted_configs_mask_every_key() {
+        let mut cfg = Config::default();
+        cfg.add_provider_api_key(ProviderId::OpenWeather, "0123456789abcdef".into());
+        cfg.add_provider_api_key(ProviderId::OpenWeather, "short".into());
+
+        let toml = cfg.redacted().to_toml().unwrap();
+        assert!(toml.contains("********cdef"));
+        assert!(!toml.contains("0123") && !toml.contains("short"));
+        assert_eq!(cfg.provider_api_key(ProviderId::OpenWeather), Some("0123456789abcdef"));
+    }
+
+    #[test]
+    fn merge_prefers_the_imported_settings() {
+        let mut cfg = Config::default();
+        cfg.upsert_provider_api_key(ProviderId::OpenWeather, "OPEN_KEY".into());
+        cfg.set_value("units", "imperial").unwrap();
+        cfg.set_value("cache_ttl", "60").unwrap();
+        cfg.add_location("home", Location::from("Kyiv")).unwrap();
+
+        let imported = Config::from_toml(
+            r#"
+            default_provider = "weatherapi"
+            cache_ttl_secs = 300
+
+            [providers.weatherapi]
+            api_key = "WEATHERAPI_KEY"
+
+            [locations]
+            home = "Lviv"
+            work = "Odesa"
+            "#,
+        )
+        .unwrap();
+        cfg.merge(imported).unwrap();
+
+        assert_eq!(cfg.default_provider_id().unwrap(), ProviderId::WeatherApi);
+        assert_eq!(cfg.units(), Units::Imperial);
+        assert_eq!(cfg.cache_ttl(), Duration::from_secs(300));
+        assert_eq!(cfg.provider_api_key(ProviderId::OpenWeather), Some("OPEN_KEY"));
+        assert_eq!(cfg.provider_api_key(ProviderId::WeatherApi), Some("WEATHERAPI_KEY"));
+        assert_eq!(cfg.saved_location("home"), Some(&Location::from("Lviv")));
+        assert_eq!(cfg.saved_location("work"), Some(&Location::from("Odesa")));
+
+        let locked = Config { encrypted_providers: Some("...".into()), ..Config::default() };
+        assert!(matches!(cfg.merge(locked), Err(WeatherError::ConfigLocked)));
+    }
+
+    #[test]
+    #[cfg(feature = "encryption")]
+    fn encrypted_keys_need_the_passphrase() {
+        let mut cfg = Config::default();
+        cfg.upsert_provider_api_key(ProviderId::OpenWeather, "OPEN_KEY".into());
+        cfg.encrypt_providers("correct horse".into()).unwrap();
+
+        let toml = cfg.to_toml().unwrap();
+        assert!(!toml.contains("OPEN_KEY"));
+
+        let mut cfg: Config = toml::from_str(&toml).unwrap();
+        assert!(cfg.is_locked());
+        assert_eq!(cfg.provider_api_key(ProviderId::OpenWeather), None);
+        assert!(matches!(cfg.unlock("wrong".into()), Err(WeatherError::WrongPassphrase)));
+
+        cfg.unlock("correct horse".into()).unwrap();
+        assert_eq!(cfg.provider_api_key(ProviderId::OpenWeather), Some("OPEN_KEY"));
+
+        cfg.decrypt_providers().unwrap();
+        assert!(cfg.to_toml().unwrap().contains("OPEN_KEY"));
+    }
+
+    #[test]
+    fn remembered_places_survive_a_roundtrip() {
+        let place = Place {
+            name: "Springfield".to_string(),
+            region: Some("Illinois".to_string()),
+            country: "US".to_string(),
+            lat: 39.8,
+            lon: -89.64,
+        };
+        let mut cfg = Config::default();
+        cfg.remember_place("Springfield", place.clone());
+
+        let cfg: Config = toml::from_str(&toml::to_string(&cfg).unwrap()).unwrap();
+        assert_eq!(cfg.remembered_place("  springfield "), Some(&place));
+        assert_eq!(cfg.remembered_place("Kyiv"), None);
+    }
 }
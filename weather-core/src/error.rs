@@ -0,0 +1,262 @@
+//! Error type shared by every `weather-core` API.
+//!
+//! [`WeatherError`] lets callers tell failure kinds apart (a rejected API key, an
+//! unknown location, an exhausted quota, ...) instead of parsing messages. Messages
+//! stay human-readable, with a `Hint:` line where there is an obvious fix.
+
+use std::{io, time::Duration};
+
+use crate::provider::{ProviderId, circuit::CircuitOpen, http::StatusError};
+
+/// `Result` with [`WeatherError`] as the default error.
+pub type Result<T, E = WeatherError> = std::result::Result<T, E>;
+
+/// Source of a [`WeatherError::Parse`], e.g. a JSON or TOML error.
+pub type BoxError = Box<dyn std::error::Error + Send + Sync>;
+
+#[derive(Debug, thiserror::Error)]
+#[non_exhaustive]
+pub enum WeatherError {
+    /// No API key is stored for the provider.
+    #[error(
+        "No API key configured for provider '{0}'.\n\
+         Hint: run `weather configure {0}` and enter your API key."
+    )]
+    ProviderNotConfigured(ProviderId),
+
+    #[error(
+        "No default provider configured.\n\
+         Hint: run `weather configure <provider>` (e.g. `weather configure openweather`) first."
+    )]
+    NoDefaultProvider,
+
+    #[error("Unknown provider '{0}'. Supported providers: openweather, weatherapi.")]
+    UnknownProvider(String),
+
+    /// A setting or option value was rejected; the message says which and why.
+    #[error("{0}")]
+    InvalidSetting(String),
+
+    /// A [`WeatherRequest`](crate::WeatherRequest) failed validation.
+    #[error("{0}")]
+    InvalidRequest(String),
+
+    /// The provider rejected the API key (HTTP 401/403).
+    #[error(
+        "Provider '{provider}' rejected the API key.\n\
+         Hint: run `weather configure {provider}` and enter a valid key."
+    )]
+    Unauthorized {
+        provider: ProviderId,
+        #[source]
+        source: StatusError,
+    },
+
+    /// The provider kept answering 429, or the plan's quota is used up.
+    #[error("Provider '{provider}' is rate limiting requests{}.", wait_hint(*retry_after))]
+    RateLimited { provider: ProviderId, retry_after: Option<Duration> },
+
+    /// The provider cannot answer for the requested date (e.g. history on a free plan).
+    #[error("{0}")]
+    UnsupportedDate(String),
+
+    #[error(
+        "Location '{address}' was not found by provider '{provider}'.\n\
+         Hint: check the spelling or add a country, e.g. 'Kyiv, UA'."
+    )]
+    LocationNotFound { provider: ProviderId, address: String },
+
+    /// Geocoding outside of a provider, e.g. for climate normals, found nothing.
+    #[error(
+        "No place named '{0}' was found.\n\
+         Hint: check the spelling, or pass \"lat,lon\" coordinates."
+    )]
+    PlaceNotFound(String),
+
+    /// The provider was skipped because it kept failing recently.
+    #[error(transparent)]
+    CircuitOpen(#[from] CircuitOpen),
+
+    #[error(
+        "No cached weather for '{0}' is available offline.\n\
+         Hint: run the same query once while online."
+    )]
+    NotCached(String),
+
+    /// Any other non-success HTTP status.
+    #[error(transparent)]
+    Status(#[from] StatusError),
+
+    /// The request could not be sent or its response not read.
+    #[error("{context}")]
+    Http {
+        context: String,
+        #[source]
+        source: reqwest::Error,
+    },
+
+    /// Malformed data: provider JSON, config or state files.
+    #[error("{context}")]
+    Parse {
+        context: String,
+        #[source]
+        source: BoxError,
+    },
+
+    #[error("{context}")]
+    Io {
+        context: String,
+        #[source]
+        source: io::Error,
+    },
+
+    #[error("Could not determine platform config directory")]
+    NoPlatformDirs,
+
+    /// The provider cannot describe its requests without sending them.
+    #[error("This provider cannot describe its requests.")]
+    PlanUnsupported,
+
+    /// The provider cannot look up places for an address.
+    #[error("This provider cannot look up places.")]
+    GeocodingUnsupported,
+
+    /// The provider cannot return a forecast series.
+    #[error("This provider cannot return a forecast.")]
+    ForecastUnsupported,
+
+    /// The provider does not publish weather alerts.
+    #[error("This provider does not publish weather alerts.")]
+    AlertsUnsupported,
+
+    #[error(
+        "Detecting the location from the IP address is disabled.\n\
+         Hint: pass a location, or run `weather config set auto_locate true`."
+    )]
+    AutoLocateDisabled,
+
+    /// The observation database could not be opened, read or written.
+    #[cfg(all(feature = "sqlite", not(target_arch = "wasm32")))]
+    #[error("{context}")]
+    Database {
+        context: String,
+        #[source]
+        source: rusqlite::Error,
+    },
+
+    /// The API keys are encrypted and no passphrase was given.
+    #[error(
+        "The API keys in the config file are encrypted.\n\
+         Hint: enter the passphrase when asked, set WEATHER_PASSPHRASE, or run `weather config unlock`."
+    )]
+    ConfigLocked,
+
+    #[error("Wrong passphrase for the encrypted API keys.")]
+    WrongPassphrase,
+
+    /// There is nowhere to keep a passphrase that goes away on logout.
+    #[error(
+        "Cannot remember the passphrase: this system has no runtime directory (XDG_RUNTIME_DIR).\n\
+         Hint: set WEATHER_PASSPHRASE instead."
+    )]
+    NoSessionDir,
+
+    /// No recording matches a replayed request.
+    #[cfg(feature = "cassette")]
+    #[error("{0}")]
+    Cassette(String),
+
+    /// A [`MockProvider`](crate::provider::mock::MockProvider) ran out of script.
+    #[cfg(any(test, feature = "test-util"))]
+    #[error("{0}")]
+    Mock(String),
+}
+
+fn wait_hint(retry_after: Option<Duration>) -> String {
+    match retry_after {
+        Some(wait) => format!("; try again in {}s", wait.as_secs().max(1)),
+        None => String::new(),
+    }
+}
+
+/// Errors that [`Context`] can attach a message to.
+pub(crate) trait IntoWeatherError {
+    fn into_weather_error(self, context: String) -> WeatherError;
+}
+
+impl IntoWeatherError for io::Error {
+    fn into_weather_error(self, context: String) -> WeatherError {
+        WeatherError::Io { context, source: self }
+    }
+}
+
+impl IntoWeatherError for reqwest::Error {
+    fn into_weather_error(self, context: String) -> WeatherError {
+        WeatherError::Http { context, source: self }
+    }
+}
+
+#[cfg(all(feature = "sqlite", not(target_arch = "wasm32")))]
+impl IntoWeatherError for rusqlite::Error {
+    fn into_weather_error(self, context: String) -> WeatherError {
+        WeatherError::Database { context, source: self }
+    }
+}
+
+macro_rules! parse_errors {
+    ($($ty:ty),*) => {$(
+        impl IntoWeatherError for $ty {
+            fn into_weather_error(self, context: String) -> WeatherError {
+                WeatherError::Parse { context, source: Box::new(self) }
+            }
+        }
+    )*};
+}
+
+parse_errors!(serde_json::Error, toml::de::Error, toml::ser::Error);
+
+/// `anyhow`-style context for the library's own fallible calls.
+pub(crate) trait Context<T> {
+    fn context(self, context: &str) -> Result<T>;
+
+    fn with_context(self, context: impl FnOnce() -> String) -> Result<T>;
+}
+
+impl<T, E: IntoWeatherError> Context<T> for std::result::Result<T, E> {
+    fn context(self, context: &str) -> Result<T> {
+        self.map_err(|err| err.into_weather_error(context.to_string()))
+    }
+
+    fn with_context(self, context: impl FnOnce() -> String) -> Result<T> {
+        self.map_err(|err| err.into_weather_error(context()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn context_keeps_the_cause() {
+        let err = serde_json::from_str::<u32>("nope").context("Failed to parse JSON").unwrap_err();
+
+        assert!(matches!(err, WeatherError::Parse { .. }));
+        assert_eq!(err.to_string(), "Failed to parse JSON");
+        assert!(std::error::Error::source(&err).is_some());
+    }
+
+    #[test]
+    fn rate_limits_mention_the_wait() {
+        let provider = ProviderId::WeatherApi;
+
+        let err =
+            WeatherError::RateLimited { provider, retry_after: Some(Duration::from_secs(30)) };
+        assert_eq!(
+            err.to_string(),
+            "Provider 'weatherapi' is rate limiting requests; try again in 30s."
+        );
+
+        let err = WeatherError::RateLimited { provider, retry_after: None };
+        assert_eq!(err.to_string(), "Provider 'weatherapi' is rate limiting requests.");
+    }
+}
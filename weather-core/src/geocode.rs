@@ -0,0 +1,70 @@
+//! Resolving free-text addresses to coordinates.
+//!
+//! Providers geocode addresses on their own, silently picking their best match. For
+//! ambiguous names ("Springfield") [`WeatherProvider::geocode`](crate::WeatherProvider::geocode)
+//! lists every candidate instead, so the caller can let the user choose one and query
+//! by its coordinates. [`Config`](crate::Config) can remember such choices.
+
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+use crate::model::Location;
+
+/// Most candidates asked of a provider for one address.
+pub(crate) const MAX_CANDIDATES: usize = 5;
+
+/// A geocoding candidate.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Place {
+    pub name: String,
+    /// State, province or similar, when the provider knows it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub region: Option<String>,
+    pub country: String,
+    pub lat: f64,
+    pub lon: f64,
+}
+
+impl Place {
+    pub fn location(&self) -> Location {
+        Location::coordinates(self.lat, self.lon)
+    }
+}
+
+/// `Springfield, Illinois, US (39.80, -89.64)`: enough to tell candidates apart.
+impl fmt::Display for Place {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.name)?;
+        if let Some(region) = &self.region {
+            write!(f, ", {region}")?;
+        }
+        write!(f, ", {} ({:.2}, {:.2})", self.country, self.lat, self.lon)
+    }
+}
+
+/// Trim, lowercase and collapse whitespace so "  Kyiv " and "kyiv" are the same address.
+pub(crate) fn normalize_address(address: &str) -> String {
+    address.split_whitespace().collect::<Vec<_>>().join(" ").to_lowercase()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn places_display_their_region_when_known() {
+        let mut place = Place {
+            name: "Springfield".to_string(),
+            region: Some("Illinois".to_string()),
+            country: "US".to_string(),
+            lat: 39.7990,
+            lon: -89.6440,
+        };
+        assert_eq!(place.to_string(), "Springfield, Illinois, US (39.80, -89.64)");
+
+        place.region = None;
+        assert_eq!(place.to_string(), "Springfield, US (39.80, -89.64)");
+        assert_eq!(place.location(), Location::coordinates(39.7990, -89.6440));
+    }
+}
@@ -0,0 +1,186 @@
+//! Finding the user's approximate location from their public IP address.
+//!
+//! Backs `weather show --here`. The lookup goes to ipinfo.io, which sees the caller's IP
+//! address; set `auto_locate = false` in config to never contact it. Detected locations
+//! are cached for an hour, so repeated queries cost no extra request.
+
+use std::{fs, path::PathBuf, time::Duration};
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use tracing::warn;
+
+#[cfg(not(target_arch = "wasm32"))]
+use crate::{Config, config::project_dirs, provider::http::client_from_config};
+use crate::{
+    error::{Context, Result, WeatherError},
+    geocode::Place,
+    provider::http::HttpClient,
+};
+
+const DEFAULT_URL: &str = "https://ipinfo.io/json";
+
+/// How long a detected location is reused before asking again.
+const DETECTED_TTL: Duration = Duration::from_secs(3600);
+
+#[derive(Debug, Serialize, Deserialize)]
+struct Detected {
+    place: Place,
+    detected_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize)]
+struct IpInfo {
+    #[serde(default)]
+    city: String,
+    #[serde(default)]
+    region: Option<String>,
+    #[serde(default)]
+    country: String,
+    /// "lat,lon"
+    loc: String,
+}
+
+/// IP geolocation lookups, optionally cached in a file.
+#[derive(Debug, Clone)]
+pub struct IpLocator {
+    http: HttpClient,
+    url: String,
+    cache_file: Option<PathBuf>,
+}
+
+impl IpLocator {
+    pub fn new(http: HttpClient) -> Self {
+        Self { http, url: DEFAULT_URL.to_string(), cache_file: None }
+    }
+
+    /// Locator with the proxy and TLS settings from config, caching in the platform
+    /// cache directory. Fails if `auto_locate` is disabled.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn from_config(config: &Config) -> Result<Self> {
+        if !config.auto_locate() {
+            return Err(WeatherError::AutoLocateDisabled);
+        }
+
+        let http = HttpClient::new(client_from_config(config)?, config.retry_policy());
+        Ok(Self::new(http).with_cache_file(project_dirs()?.cache_dir().join("here.json")))
+    }
+
+    /// Ask `url` instead of ipinfo.io; it must answer in the same format.
+    pub fn with_url(mut self, url: impl Into<String>) -> Self {
+        self.url = url.into();
+        self
+    }
+
+    /// Reuse locations detected within the last hour, stored in `path`.
+    pub fn with_cache_file(mut self, path: impl Into<PathBuf>) -> Self {
+        self.cache_file = Some(path.into());
+        self
+    }
+
+    /// The approximate location of this machine.
+    pub async fn locate(&self) -> Result<Place> {
+        if let Some(detected) = self.read_cache() {
+            let age = (Utc::now() - detected.detected_at).to_std().unwrap_or_default();
+            if age <= DETECTED_TTL {
+                return Ok(detected.place);
+            }
+        }
+
+        let res =
+            self.http.get::<&str>(&self.url, &[]).await?.error_for_status("IP geolocation")?;
+        let info: IpInfo =
+            serde_json::from_str(&res.body).context("Failed to parse IP geolocation JSON")?;
+        let place = place(info)?;
+
+        if let Err(err) = self.write_cache(&place) {
+            warn!(error = %err, "failed to cache detected location");
+        }
+
+        Ok(place)
+    }
+
+    /// The last detected location, however old; for when nothing may be sent.
+    pub fn last_known(&self) -> Option<Place> {
+        self.read_cache().map(|detected| detected.place)
+    }
+
+    fn read_cache(&self) -> Option<Detected> {
+        let contents = fs::read_to_string(self.cache_file.as_ref()?).ok()?;
+        serde_json::from_str(&contents).ok()
+    }
+
+    fn write_cache(&self, place: &Place) -> Result<()> {
+        let Some(path) = &self.cache_file else {
+            return Ok(());
+        };
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).with_context(|| {
+                format!("Failed to create cache directory: {}", parent.display())
+            })?;
+        }
+
+        let detected = Detected { place: place.clone(), detected_at: Utc::now() };
+        let json =
+            serde_json::to_string_pretty(&detected).context("Failed to serialize location")?;
+        fs::write(path, json)
+            .with_context(|| format!("Failed to write detected location: {}", path.display()))
+    }
+}
+
+fn place(info: IpInfo) -> Result<Place> {
+    let coordinates = info
+        .loc
+        .split_once(',')
+        .and_then(|(lat, lon)| Some((lat.trim().parse().ok()?, lon.trim().parse().ok()?)));
+    let Some((lat, lon)) = coordinates else {
+        return Err(WeatherError::Parse {
+            context: "Failed to parse IP geolocation JSON".to_string(),
+            source: format!("invalid coordinates '{}'", info.loc).into(),
+        });
+    };
+
+    Ok(Place {
+        name: info.city,
+        region: info.region.filter(|r| !r.is_empty()),
+        country: info.country,
+        lat,
+        lon,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::provider::http::testing::{json_ok, serve};
+
+    #[tokio::test]
+    async fn detected_locations_are_cached() {
+        let dir = tempfile::tempdir().unwrap();
+        let body = r#"{"ip":"203.0.113.7","city":"Kyiv","region":"Kyiv City","country":"UA","loc":"50.4547,30.5238"}"#;
+        let (url, requests) = serve(vec![json_ok(body)]).await;
+        let locator = IpLocator::new(HttpClient::default())
+            .with_url(url)
+            .with_cache_file(dir.path().join("here.json"));
+
+        let place = locator.locate().await.unwrap();
+        assert_eq!(place.to_string(), "Kyiv, Kyiv City, UA (50.45, 30.52)");
+
+        // The server answers once; the second lookup must come from the file.
+        assert_eq!(locator.locate().await.unwrap(), place);
+        assert_eq!(locator.last_known(), Some(place));
+        assert!(requests.recv().is_ok());
+        assert!(requests.try_recv().is_err());
+    }
+
+    #[test]
+    fn malformed_coordinates_are_rejected() {
+        let info = IpInfo {
+            city: "Kyiv".to_string(),
+            region: None,
+            country: "UA".to_string(),
+            loc: "somewhere".to_string(),
+        };
+        assert!(matches!(place(info), Err(WeatherError::Parse { .. })));
+    }
+}
@@ -0,0 +1,135 @@
+//! Local journal of weather lookups, for `weather history`.
+//!
+//! Every answered query is appended as one JSON line (timestamp, what was asked for,
+//! provider and the key fields of the response) to a file in the platform data
+//! directory. Nothing leaves the machine; set `history = false` in config to stop
+//! recording.
+
+use std::{fs, io::ErrorKind, path::PathBuf};
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+#[cfg(not(target_arch = "wasm32"))]
+use crate::config::project_dirs;
+use crate::{
+    Temperature, WeatherResponse, WindSpeed,
+    error::{Context, Result},
+    jsonl,
+};
+
+/// One answered query.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HistoryEntry {
+    pub at: DateTime<Utc>,
+    /// What was asked for: an address, coordinates or the name of a saved location.
+    pub query: String,
+    pub provider: String,
+    /// The location as named by the provider.
+    pub location_name: String,
+    pub temperature: Temperature,
+    pub condition: String,
+    pub humidity_pct: u8,
+    pub wind_speed: WindSpeed,
+}
+
+impl HistoryEntry {
+    /// Entry for `response` to `query`, recorded now.
+    pub fn new(query: impl Into<String>, response: &WeatherResponse) -> Self {
+        Self {
+            at: Utc::now(),
+            query: query.into(),
+            provider: response.provider.clone(),
+            location_name: response.location_name.clone(),
+            temperature: response.temperature,
+            condition: response.condition.clone(),
+            humidity_pct: response.humidity_pct,
+            wind_speed: response.wind_speed,
+        }
+    }
+}
+
+/// Append-only JSON-lines file of [`HistoryEntry`]s.
+#[derive(Debug, Clone)]
+pub struct History {
+    path: PathBuf,
+}
+
+impl History {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into() }
+    }
+
+    /// `history.jsonl` in the platform data directory.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn default_path() -> Result<PathBuf> {
+        Ok(project_dirs()?.data_dir().join("history.jsonl"))
+    }
+
+    pub fn record(&self, entry: &HistoryEntry) -> Result<()> {
+        jsonl::append(&self.path, entry, "history")
+    }
+
+    /// All readable entries, oldest first; a missing journal is empty and garbled lines
+    /// are skipped.
+    pub fn entries(&self) -> Result<Vec<HistoryEntry>> {
+        jsonl::read(&self.path, "history")
+    }
+
+    /// The `count` most recent entries, oldest first.
+    pub fn last(&self, count: usize) -> Result<Vec<HistoryEntry>> {
+        let mut entries = self.entries()?;
+        entries.drain(..entries.len().saturating_sub(count));
+        Ok(entries)
+    }
+
+    /// Delete the journal. Returns how many entries it held.
+    pub fn clear(&self) -> Result<usize> {
+        let count = self.entries()?.len();
+
+        match fs::remove_file(&self.path) {
+            Ok(()) => Ok(count),
+            Err(err) if err.kind() == ErrorKind::NotFound => Ok(0),
+            Err(err) => Err(err)
+                .with_context(|| format!("Failed to remove history: {}", self.path.display())),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::provider::mock;
+
+    #[test]
+    fn last_returns_the_most_recent_entries() {
+        let dir = tempfile::tempdir().unwrap();
+        let history = History::new(dir.path().join("history.jsonl"));
+
+        for city in ["Kyiv", "Lviv", "Odesa"] {
+            history.record(&HistoryEntry::new(city, &mock::response(city))).unwrap();
+        }
+
+        let queries = |entries: Vec<HistoryEntry>| -> Vec<String> {
+            entries.into_iter().map(|e| e.query).collect()
+        };
+        assert_eq!(queries(history.last(2).unwrap()), ["Lviv", "Odesa"]);
+        assert_eq!(queries(history.last(20).unwrap()), ["Kyiv", "Lviv", "Odesa"]);
+
+        let entry = &history.entries().unwrap()[0];
+        assert_eq!(entry.provider, "mock");
+        assert_eq!(entry.temperature, Temperature::from_celsius(12.5));
+    }
+
+    #[test]
+    fn clear_wipes_the_journal() {
+        let dir = tempfile::tempdir().unwrap();
+        let history = History::new(dir.path().join("history.jsonl"));
+
+        assert_eq!(history.clear().unwrap(), 0);
+        history.record(&HistoryEntry::new("Kyiv", &mock::response("Kyiv"))).unwrap();
+
+        assert_eq!(history.clear().unwrap(), 1);
+        assert!(history.entries().unwrap().is_empty());
+    }
+}
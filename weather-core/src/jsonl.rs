@@ -0,0 +1,46 @@
+//! Append-only JSON-lines files, such as the usage log and the lookup history: one
+//! JSON value per line.
+
+use std::{
+    fs::{self, OpenOptions},
+    io::{ErrorKind, Write},
+    path::Path,
+};
+
+use serde::{Serialize, de::DeserializeOwned};
+
+use crate::error::{Context, Result};
+
+/// Append `value` to the file at `path` as one line, creating the file and its
+/// directory if needed. `what` names the file in error messages, e.g. "history".
+pub(crate) fn append<T: Serialize>(path: &Path, value: &T, what: &str) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .with_context(|| format!("Failed to create data directory: {}", parent.display()))?;
+    }
+
+    let mut line =
+        serde_json::to_string(value).with_context(|| format!("Failed to serialize {what}"))?;
+    line.push('\n');
+
+    OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .and_then(|mut file| file.write_all(line.as_bytes()))
+        .with_context(|| format!("Failed to write {what}: {}", path.display()))
+}
+
+/// Every line of the file at `path` that parses, in order; a missing file is empty and
+/// garbled lines are skipped.
+pub(crate) fn read<T: DeserializeOwned>(path: &Path, what: &str) -> Result<Vec<T>> {
+    let contents = match fs::read_to_string(path) {
+        Ok(contents) => contents,
+        Err(err) if err.kind() == ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(err) => {
+            return Err(err).with_context(|| format!("Failed to read {what}: {}", path.display()));
+        }
+    };
+
+    Ok(contents.lines().filter_map(|line| serde_json::from_str(line).ok()).collect())
+}
@@ -1,18 +1,51 @@
 //! Core library for the `weather` CLI.
 //!
 //! This crate defines:
-//! - Configuration & credentials handling
+//! - Configuration & credentials handling, optionally with the API keys encrypted
 //! - Abstraction over weather providers
 //! - Shared domain models (requests, responses)
+//! - Geocoding of ambiguous addresses and IP-based location detection
+//! - Trend analysis of forecast series and climate normals from historical data
+//! - Severe-weather alerts and notifications about them
+//! - On-disk response caching
+//! - Provider call usage tracking
+//! - A local journal of past lookups and, with the `sqlite` feature, a database of
+//!   observations over time
+//! - A typed error ([`WeatherError`]) for every fallible API
 //!
 //! It is used by `weather-cli`, but can also be reused by other binaries or services.
+//! It also builds for `wasm32-unknown-unknown` (e.g. a web dashboard), minus everything
+//! that needs a file system: there, build [`Config`] in memory and pass it to
+//! [`provider::provider_from_config`].
 
+pub mod alerts;
+pub mod cache;
+pub mod climate;
 pub mod config;
+pub mod error;
+pub mod geocode;
+pub mod geoip;
+pub mod history;
+mod jsonl;
 pub mod model;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod notify;
+mod platform;
 pub mod provider;
+#[cfg(all(feature = "encryption", not(target_arch = "wasm32")))]
+pub mod secrets;
+mod state;
+#[cfg(all(feature = "sqlite", not(target_arch = "wasm32")))]
+pub mod store;
+pub mod trend;
+pub mod usage;
 
-pub use config::{Config, ProviderConfig};
-pub use model::{WeatherRequest, WeatherResponse};
+pub use config::{Config, KeyRotation, ProviderConfig};
+pub use error::WeatherError;
+pub use model::{
+    ConditionKind, Location, Precipitation, Pressure, Temperature, Units, WeatherPoint,
+    WeatherRequest, WeatherResponse, WeatherSeries, WindDirection, WindSpeed,
+};
 pub use provider::{ProviderId, WeatherProvider};
 
 #[cfg(test)]
@@ -1,20 +1,721 @@
-use chrono::{DateTime, Utc};
+use std::{fmt, ops::Range};
+
+use chrono::{DateTime, TimeZone, Utc};
 use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Clone)]
+use crate::{WeatherError, config::parse_language, error::Result};
+
+/// Longest address accepted, in characters.
+const MAX_ADDRESS_LEN: usize = 256;
+/// How far ahead a request may look; no provider forecasts further.
+const MAX_DAYS_AHEAD: i64 = 366;
+
+/// Where to report the weather for.
+///
+/// Serialized as a plain string for addresses and as `{"lat": .., "lon": ..}` for
+/// coordinates.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum Location {
+    /// Free-form address or place name, geocoded by the provider, e.g. "Kyiv".
+    Address(String),
+    /// Latitude and longitude in decimal degrees, e.g. from a GPS fix.
+    Coordinates { lat: f64, lon: f64 },
+}
+
+impl Location {
+    pub fn coordinates(lat: f64, lon: f64) -> Self {
+        Self::Coordinates { lat, lon }
+    }
+
+    /// Reject empty or overlong addresses and coordinates off the globe.
+    pub fn validate(&self) -> Result<()> {
+        let invalid = |message: String| Err(WeatherError::InvalidRequest(message));
+
+        match self {
+            Self::Address(address) if address.trim().is_empty() => {
+                invalid("The address must not be empty.".to_string())
+            }
+            Self::Address(address) if address.chars().count() > MAX_ADDRESS_LEN => {
+                invalid(format!("The address is longer than {MAX_ADDRESS_LEN} characters."))
+            }
+            Self::Address(_) => Ok(()),
+            Self::Coordinates { lat, lon } => {
+                if !(-90.0..=90.0).contains(lat) || !(-180.0..=180.0).contains(lon) {
+                    return invalid(format!(
+                        "Coordinates {self} are out of range. Expected latitude -90..90 \
+                         and longitude -180..180."
+                    ));
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+impl Default for Location {
+    fn default() -> Self {
+        Self::Address(String::new())
+    }
+}
+
+/// `"lat,lon"` (two decimal numbers) becomes [`Location::Coordinates`], anything else
+/// an [`Location::Address`].
+impl From<&str> for Location {
+    fn from(input: &str) -> Self {
+        let coordinates = input.split_once(',').and_then(|(lat, lon)| {
+            Some(Self::coordinates(lat.trim().parse().ok()?, lon.trim().parse().ok()?))
+        });
+
+        coordinates.unwrap_or_else(|| Self::Address(input.to_string()))
+    }
+}
+
+impl From<String> for Location {
+    fn from(input: String) -> Self {
+        Self::from(input.as_str())
+    }
+}
+
+impl fmt::Display for Location {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Address(address) => f.write_str(address),
+            Self::Coordinates { lat, lon } => write!(f, "{lat},{lon}"),
+        }
+    }
+}
+
+/// What to ask a provider. Build one with [`WeatherRequest::builder`], which validates.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
 pub struct WeatherRequest {
-    pub address: String,
+    pub location: Location,
+    /// Point in time to report on; `None` means now.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub when: Option<DateTime<Utc>>,
+    /// Language code for provider-side text such as condition descriptions, e.g. "uk".
+    /// `None` leaves the provider default (English).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub language: Option<String>,
+    /// Unit system the caller will present the response in. Providers always fetch
+    /// metric data; [`Units`] converts the typed quantities of the response.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub units: Option<Units>,
+}
+
+impl WeatherRequest {
+    /// ```
+    /// # use weather_core::{Units, WeatherRequest};
+    /// let request = WeatherRequest::builder()
+    ///     .address("Kyiv")
+    ///     .at(chrono::Utc::now())
+    ///     .language("uk")
+    ///     .units(Units::Metric)
+    ///     .build()?;
+    /// # Ok::<(), weather_core::WeatherError>(())
+    /// ```
+    pub fn builder() -> WeatherRequestBuilder {
+        WeatherRequestBuilder::default()
+    }
+
+    /// Reject requests no provider could answer: an empty or overlong address,
+    /// coordinates off the globe, a date before 1979 (the oldest data any provider has)
+    /// or over a year ahead, or a malformed language code.
+    pub fn validate(&self) -> Result<()> {
+        let invalid = |message: String| Err(WeatherError::InvalidRequest(message));
+
+        self.location.validate()?;
+
+        if let Some(when) = self.when {
+            let earliest = Utc.with_ymd_and_hms(1979, 1, 1, 0, 0, 0).unwrap();
+            let latest = Utc::now() + chrono::Duration::days(MAX_DAYS_AHEAD);
+            if when < earliest || when > latest {
+                return invalid(format!(
+                    "Date {when} is out of range. Expected a date between {} and {}.",
+                    earliest.date_naive(),
+                    latest.date_naive()
+                ));
+            }
+        }
+
+        if let Some(language) = &self.language {
+            parse_language(language)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Builder for [`WeatherRequest`], see [`WeatherRequest::builder`].
+#[derive(Debug, Default)]
+pub struct WeatherRequestBuilder {
+    request: WeatherRequest,
+}
+
+impl WeatherRequestBuilder {
+    pub fn address(mut self, address: impl Into<String>) -> Self {
+        self.request.location = Location::Address(address.into());
+        self
+    }
+
+    pub fn coordinates(mut self, lat: f64, lon: f64) -> Self {
+        self.request.location = Location::coordinates(lat, lon);
+        self
+    }
+
+    /// Either kind of location; strings go through [`Location::from`], so `"50.45,30.52"`
+    /// is taken as coordinates.
+    pub fn location(mut self, location: impl Into<Location>) -> Self {
+        self.request.location = location.into();
+        self
+    }
+
+    /// Report on `when` instead of now.
+    pub fn at(mut self, when: DateTime<Utc>) -> Self {
+        self.request.when = Some(when);
+        self
+    }
+
+    pub fn language(mut self, language: impl Into<String>) -> Self {
+        self.request.language = Some(language.into());
+        self
+    }
+
+    pub fn units(mut self, units: Units) -> Self {
+        self.request.units = Some(units);
+        self
+    }
+
+    /// The request, if it passes [`WeatherRequest::validate`].
+    pub fn build(self) -> Result<WeatherRequest> {
+        self.request.validate()?;
+        Ok(self.request)
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct WeatherResponse {
     pub provider: String,
     pub location_name: String,
-    pub temperature_c: f64,
-    pub feels_like_c: f64,
+    pub temperature: Temperature,
+    pub feels_like: Temperature,
     pub condition: String,
     pub humidity_pct: u8,
-    pub wind_speed_mps: f64,
+    pub wind_speed: WindSpeed,
+    /// `None` if the provider did not say.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub wind_direction: Option<WindDirection>,
+    pub pressure: Pressure,
+    pub precipitation: Precipitation,
     pub observation_time: DateTime<Utc>,
 }
+
+impl WeatherResponse {
+    /// See [`ConditionKind::of`].
+    pub fn condition_kind(&self) -> ConditionKind {
+        ConditionKind::of(&self.condition)
+    }
+}
+
+/// Forecast for one location over a time range, one [`WeatherPoint`] per step of the
+/// provider (hourly or 3-hourly), oldest first; see
+/// [`WeatherProvider::get_forecast_series`](crate::WeatherProvider::get_forecast_series).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WeatherSeries {
+    pub provider: String,
+    pub location_name: String,
+    pub points: Vec<WeatherPoint>,
+}
+
+impl WeatherSeries {
+    /// Keep only the points within `range`.
+    pub fn within(mut self, range: &Range<DateTime<Utc>>) -> Self {
+        self.points.retain(|point| range.contains(&point.time));
+        self
+    }
+
+    /// The point closest to `time` as a snapshot, as [`WeatherProvider::get_weather`]
+    /// would return it for that time.
+    ///
+    /// [`WeatherProvider::get_weather`]: crate::WeatherProvider::get_weather
+    pub fn snapshot_at(&self, time: DateTime<Utc>) -> Option<WeatherResponse> {
+        let point = self.points.iter().min_by_key(|point| (point.time - time).abs())?;
+
+        Some(WeatherResponse {
+            provider: self.provider.clone(),
+            location_name: self.location_name.clone(),
+            temperature: point.temperature,
+            feels_like: point.feels_like,
+            condition: point.condition.clone(),
+            humidity_pct: point.humidity_pct,
+            wind_speed: point.wind_speed,
+            wind_direction: point.wind_direction,
+            pressure: point.pressure,
+            precipitation: point.precipitation,
+            observation_time: point.time,
+        })
+    }
+}
+
+/// The weather at one step of a [`WeatherSeries`].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WeatherPoint {
+    /// Start of the step.
+    pub time: DateTime<Utc>,
+    pub temperature: Temperature,
+    pub feels_like: Temperature,
+    pub condition: String,
+    pub humidity_pct: u8,
+    pub wind_speed: WindSpeed,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub wind_direction: Option<WindDirection>,
+    pub pressure: Pressure,
+    /// Over the whole step.
+    pub precipitation: Precipitation,
+}
+
+impl From<WeatherResponse> for WeatherPoint {
+    fn from(response: WeatherResponse) -> Self {
+        Self {
+            time: response.observation_time,
+            temperature: response.temperature,
+            feels_like: response.feels_like,
+            condition: response.condition,
+            humidity_pct: response.humidity_pct,
+            wind_speed: response.wind_speed,
+            wind_direction: response.wind_direction,
+            pressure: response.pressure,
+            precipitation: response.precipitation,
+        }
+    }
+}
+
+/// Broad kind of a weather condition, e.g. for picking an icon or a style.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ConditionKind {
+    Clear,
+    Clouds,
+    Fog,
+    Rain,
+    Snow,
+    Thunderstorm,
+    /// Not recognized.
+    Other,
+}
+
+impl ConditionKind {
+    /// Words that mark each kind in English and Ukrainian condition descriptions, the
+    /// most severe kind first: "light rain and snow" is snow.
+    const KEYWORDS: &[(ConditionKind, &[&str])] = &[
+        (Self::Thunderstorm, &["thunder", "гроз"]),
+        (Self::Snow, &["snow", "sleet", "blizzard", "ice pellets", "сніг", "хуртовин", "крупа"]),
+        (Self::Rain, &["rain", "drizzle", "shower", "дощ", "мряк", "злив"]),
+        (Self::Fog, &["fog", "mist", "haze", "smoke", "туман", "імла", "серпанок", "дим"]),
+        (Self::Clouds, &["cloud", "overcast", "хмар", "похмур"]),
+        (Self::Clear, &["clear", "sunny", "ясно", "сонячно", "чисте небо"]),
+    ];
+
+    /// Classify a provider's condition description, such as
+    /// [`WeatherResponse::condition`].
+    pub fn of(condition: &str) -> Self {
+        let condition = condition.to_lowercase();
+
+        Self::KEYWORDS
+            .iter()
+            .find(|(_, words)| words.iter().any(|word| condition.contains(word)))
+            .map_or(Self::Other, |(kind, _)| *kind)
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Clear => "clear",
+            Self::Clouds => "clouds",
+            Self::Fog => "fog",
+            Self::Rain => "rain",
+            Self::Snow => "snow",
+            Self::Thunderstorm => "thunderstorm",
+            Self::Other => "other",
+        }
+    }
+}
+
+impl fmt::Display for ConditionKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Air temperature. Stored in °C, serialized as a plain °C number.
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd, Default, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Temperature(f64);
+
+impl Temperature {
+    pub fn from_celsius(celsius: f64) -> Self {
+        Self(celsius)
+    }
+
+    pub fn from_fahrenheit(fahrenheit: f64) -> Self {
+        Self((fahrenheit - 32.0) * 5.0 / 9.0)
+    }
+
+    pub fn celsius(&self) -> f64 {
+        self.0
+    }
+
+    pub fn fahrenheit(&self) -> f64 {
+        self.0 * 9.0 / 5.0 + 32.0
+    }
+}
+
+/// Wind speed. Stored in m/s, serialized as a plain m/s number.
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd, Default, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct WindSpeed(f64);
+
+impl WindSpeed {
+    const MPS_PER_KMH: f64 = 1.0 / 3.6;
+    const MPS_PER_MPH: f64 = 1609.344 / 3600.0;
+
+    pub fn from_mps(mps: f64) -> Self {
+        Self(mps)
+    }
+
+    pub fn from_kmh(kmh: f64) -> Self {
+        Self(kmh * Self::MPS_PER_KMH)
+    }
+
+    pub fn from_mph(mph: f64) -> Self {
+        Self(mph * Self::MPS_PER_MPH)
+    }
+
+    pub fn mps(&self) -> f64 {
+        self.0
+    }
+
+    pub fn kmh(&self) -> f64 {
+        self.0 / Self::MPS_PER_KMH
+    }
+
+    pub fn mph(&self) -> f64 {
+        self.0 / Self::MPS_PER_MPH
+    }
+}
+
+/// Where the wind blows from, in degrees clockwise from north (meteorological
+/// convention), serialized as a plain number of degrees.
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd, Default, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct WindDirection(f64);
+
+impl WindDirection {
+    const COMPASS: [&str; 8] = ["N", "NE", "E", "SE", "S", "SW", "W", "NW"];
+
+    /// Any angle; it is normalized to 0..360.
+    pub fn from_degrees(degrees: f64) -> Self {
+        Self(degrees.rem_euclid(360.0))
+    }
+
+    pub fn degrees(&self) -> f64 {
+        self.0
+    }
+
+    /// The nearest of the 8 compass points, e.g. `NW`.
+    pub fn compass(&self) -> &'static str {
+        let sector = (self.0 / 45.0).round() as usize % Self::COMPASS.len();
+        Self::COMPASS[sector]
+    }
+}
+
+/// Atmospheric pressure. Stored in hPa (= mbar), serialized as a plain hPa number.
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd, Default, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Pressure(f64);
+
+impl Pressure {
+    const HPA_PER_INHG: f64 = 33.863_886;
+
+    pub fn from_hpa(hpa: f64) -> Self {
+        Self(hpa)
+    }
+
+    pub fn from_inhg(inhg: f64) -> Self {
+        Self(inhg * Self::HPA_PER_INHG)
+    }
+
+    pub fn hpa(&self) -> f64 {
+        self.0
+    }
+
+    pub fn inhg(&self) -> f64 {
+        self.0 / Self::HPA_PER_INHG
+    }
+}
+
+/// Precipitation amount. Stored in mm, serialized as a plain mm number.
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd, Default, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Precipitation(f64);
+
+impl Precipitation {
+    const MM_PER_INCH: f64 = 25.4;
+
+    pub fn from_mm(mm: f64) -> Self {
+        Self(mm)
+    }
+
+    pub fn from_inches(inches: f64) -> Self {
+        Self(inches * Self::MM_PER_INCH)
+    }
+
+    pub fn mm(&self) -> f64 {
+        self.0
+    }
+
+    pub fn inches(&self) -> f64 {
+        self.0 / Self::MM_PER_INCH
+    }
+}
+
+/// Unit system used to present weather values.
+///
+/// Values are stored as typed quantities; the unit system only picks which
+/// representation is shown.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Units {
+    #[default]
+    Metric,
+    Imperial,
+}
+
+impl Units {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Units::Metric => "metric",
+            Units::Imperial => "imperial",
+        }
+    }
+
+    pub const fn all() -> &'static [Units] {
+        &[Units::Metric, Units::Imperial]
+    }
+
+    pub fn temperature(&self, temperature: Temperature) -> f64 {
+        match self {
+            Units::Metric => temperature.celsius(),
+            Units::Imperial => temperature.fahrenheit(),
+        }
+    }
+
+    pub fn temperature_symbol(&self) -> &'static str {
+        match self {
+            Units::Metric => "°C",
+            Units::Imperial => "°F",
+        }
+    }
+
+    pub fn wind_speed(&self, wind_speed: WindSpeed) -> f64 {
+        match self {
+            Units::Metric => wind_speed.mps(),
+            Units::Imperial => wind_speed.mph(),
+        }
+    }
+
+    pub fn wind_speed_symbol(&self) -> &'static str {
+        match self {
+            Units::Metric => "m/s",
+            Units::Imperial => "mph",
+        }
+    }
+
+    pub fn pressure(&self, pressure: Pressure) -> f64 {
+        match self {
+            Units::Metric => pressure.hpa(),
+            Units::Imperial => pressure.inhg(),
+        }
+    }
+
+    pub fn pressure_symbol(&self) -> &'static str {
+        match self {
+            Units::Metric => "hPa",
+            Units::Imperial => "inHg",
+        }
+    }
+
+    pub fn precipitation(&self, precipitation: Precipitation) -> f64 {
+        match self {
+            Units::Metric => precipitation.mm(),
+            Units::Imperial => precipitation.inches(),
+        }
+    }
+
+    pub fn precipitation_symbol(&self) -> &'static str {
+        match self {
+            Units::Metric => "mm",
+            Units::Imperial => "in",
+        }
+    }
+}
+
+impl fmt::Display for Units {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl TryFrom<&str> for Units {
+    type Error = WeatherError;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        match value.to_lowercase().as_str() {
+            "metric" => Ok(Units::Metric),
+            "imperial" => Ok(Units::Imperial),
+            _ => Err(WeatherError::InvalidSetting(format!(
+                "Unknown units '{value}'. Supported units: metric, imperial."
+            ))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn approx(a: f64, b: f64) -> bool {
+        (a - b).abs() < 1e-3
+    }
+
+    #[test]
+    fn temperature_conversions() {
+        assert!(approx(Temperature::from_celsius(0.0).fahrenheit(), 32.0));
+        assert!(approx(Temperature::from_celsius(-40.0).fahrenheit(), -40.0));
+        assert!(approx(Temperature::from_fahrenheit(212.0).celsius(), 100.0));
+    }
+
+    #[test]
+    fn wind_speed_conversions() {
+        let wind = WindSpeed::from_kmh(36.0);
+        assert!(approx(wind.mps(), 10.0));
+        assert!(approx(wind.kmh(), 36.0));
+        assert!(approx(wind.mph(), 22.369));
+        assert!(approx(WindSpeed::from_mph(22.369).mps(), 10.0));
+    }
+
+    #[test]
+    fn conditions_are_classified_in_both_languages() {
+        assert_eq!(ConditionKind::of("Light rain"), ConditionKind::Rain);
+        assert_eq!(ConditionKind::of("light rain and snow"), ConditionKind::Snow);
+        assert_eq!(
+            ConditionKind::of("Patchy light rain with thunder"),
+            ConditionKind::Thunderstorm
+        );
+        assert_eq!(ConditionKind::of("уривчасті хмари"), ConditionKind::Clouds);
+        assert_eq!(ConditionKind::of("Ясно"), ConditionKind::Clear);
+        assert_eq!(ConditionKind::of("Unknown"), ConditionKind::Other);
+    }
+
+    #[test]
+    fn wind_directions_round_to_compass_points() {
+        assert_eq!(WindDirection::from_degrees(0.0).compass(), "N");
+        assert_eq!(WindDirection::from_degrees(22.4).compass(), "N");
+        assert_eq!(WindDirection::from_degrees(22.6).compass(), "NE");
+        assert_eq!(WindDirection::from_degrees(315.0).compass(), "NW");
+        assert_eq!(WindDirection::from_degrees(350.0).compass(), "N");
+        assert_eq!(WindDirection::from_degrees(-90.0).compass(), "W");
+    }
+
+    #[test]
+    fn pressure_and_precipitation_conversions() {
+        assert!(approx(Pressure::from_hpa(1013.25).inhg(), 29.921));
+        assert!(approx(Pressure::from_inhg(30.0).hpa(), 1015.917));
+        assert!(approx(Precipitation::from_mm(25.4).inches(), 1.0));
+        assert!(approx(Precipitation::from_inches(2.0).mm(), 50.8));
+    }
+
+    #[test]
+    fn quantities_serialize_as_metric_numbers() {
+        let json = serde_json::to_string(&WindSpeed::from_kmh(36.0)).unwrap();
+        assert_eq!(json, "10.0");
+
+        let t: Temperature = serde_json::from_str("21.5").unwrap();
+        assert_eq!(t.celsius(), 21.5);
+    }
+
+    #[test]
+    fn units_pick_representation() {
+        let t = Temperature::from_celsius(0.0);
+        assert_eq!(Units::Metric.temperature(t), 0.0);
+        assert!(approx(Units::Imperial.temperature(t), 32.0));
+
+        let wind = WindSpeed::from_mps(10.0);
+        assert_eq!(Units::Metric.wind_speed(wind), 10.0);
+        assert!(approx(Units::Imperial.wind_speed(wind), 22.369));
+    }
+
+    #[test]
+    fn request_builder_validates() {
+        let request = WeatherRequest::builder()
+            .address("Kyiv")
+            .language("uk")
+            .units(Units::Imperial)
+            .build()
+            .unwrap();
+        assert_eq!(request.location, Location::Address("Kyiv".to_string()));
+        assert_eq!(request.language.as_deref(), Some("uk"));
+        assert_eq!(request.units, Some(Units::Imperial));
+
+        let invalid = |builder: WeatherRequestBuilder| {
+            matches!(builder.build(), Err(WeatherError::InvalidRequest(_)))
+        };
+        assert!(invalid(WeatherRequest::builder().address("   ")));
+        assert!(invalid(WeatherRequest::builder().address("x".repeat(300))));
+        assert!(invalid(
+            WeatherRequest::builder().address("Kyiv").at(Utc::now() + chrono::Duration::days(400))
+        ));
+        assert!(invalid(
+            WeatherRequest::builder()
+                .address("Kyiv")
+                .at(Utc.with_ymd_and_hms(1925, 12, 4, 12, 0, 0).unwrap())
+        ));
+        assert!(invalid(WeatherRequest::builder().coordinates(91.0, 30.52)));
+        assert!(invalid(WeatherRequest::builder().coordinates(50.45, f64::NAN)));
+        assert!(WeatherRequest::builder().address("Kyiv").language("uk; rm").build().is_err());
+    }
+
+    #[test]
+    fn locations_parse_coordinates() {
+        assert_eq!(Location::from("50.45,30.52"), Location::coordinates(50.45, 30.52));
+        assert_eq!(Location::from("-33.92, 18.42"), Location::coordinates(-33.92, 18.42));
+        assert_eq!(Location::from("Kyiv, UA"), Location::Address("Kyiv, UA".to_string()));
+        assert_eq!(Location::from("Kyiv"), Location::Address("Kyiv".to_string()));
+        assert_eq!(Location::coordinates(50.45, 30.52).to_string(), "50.45,30.52");
+    }
+
+    #[test]
+    fn requests_roundtrip_through_json() {
+        let request = WeatherRequest::builder()
+            .address("Kyiv")
+            .at(Utc.with_ymd_and_hms(2025, 12, 4, 12, 0, 0).unwrap())
+            .build()
+            .unwrap();
+
+        let json = serde_json::to_string(&request).unwrap();
+        assert_eq!(json, r#"{"location":"Kyiv","when":"2025-12-04T12:00:00Z"}"#);
+        assert_eq!(serde_json::from_str::<WeatherRequest>(&json).unwrap(), request);
+
+        let request = WeatherRequest::builder().coordinates(50.45, 30.52).build().unwrap();
+        let json = serde_json::to_string(&request).unwrap();
+        assert_eq!(json, r#"{"location":{"lat":50.45,"lon":30.52}}"#);
+        assert_eq!(serde_json::from_str::<WeatherRequest>(&json).unwrap(), request);
+    }
+
+    #[test]
+    fn units_as_str_roundtrip() {
+        for units in Units::all() {
+            let parsed = Units::try_from(units.as_str()).expect("roundtrip should succeed");
+            assert_eq!(*units, parsed);
+        }
+        assert!(Units::try_from("kelvin").is_err());
+    }
+}
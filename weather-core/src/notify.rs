@@ -0,0 +1,177 @@
+//! Notifications about new weather alerts, for `weather monitor`.
+//!
+//! Two channels: a desktop notification (`notify-send` on Linux, `osascript` on macOS)
+//! and a webhook, which gets every alert POSTed as JSON to the configured
+//! `webhook_url`, e.g. for a chat bot or home automation.
+
+use std::process::Command;
+
+use reqwest::Client;
+use serde::Serialize;
+
+use crate::{
+    Config,
+    alerts::WeatherAlert,
+    error::{Context, Result, WeatherError},
+    provider::http::{StatusError, client_from_config},
+};
+
+/// Where a [`Notifier`] sends alerts.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Channel {
+    Desktop,
+    Webhook,
+}
+
+impl Channel {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Channel::Desktop => "desktop",
+            Channel::Webhook => "webhook",
+        }
+    }
+}
+
+impl std::fmt::Display for Channel {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl TryFrom<&str> for Channel {
+    type Error = WeatherError;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        match value {
+            "desktop" => Ok(Channel::Desktop),
+            "webhook" => Ok(Channel::Webhook),
+            _ => Err(WeatherError::InvalidSetting(format!(
+                "Unknown notification channel '{value}'. Expected desktop or webhook."
+            ))),
+        }
+    }
+}
+
+/// Body of a webhook call.
+#[derive(Serialize)]
+struct WebhookPayload<'a> {
+    /// The location as given to the monitor.
+    location: &'a str,
+    alert: &'a WeatherAlert,
+}
+
+/// Sends alerts to a set of channels.
+#[derive(Debug, Clone)]
+pub struct Notifier {
+    channels: Vec<Channel>,
+    client: Client,
+    webhook_url: Option<String>,
+}
+
+impl Notifier {
+    /// Notifier for `channels`, with the webhook URL and the proxy and TLS settings from
+    /// config. Fails if a webhook is asked for but none is configured.
+    pub fn from_config(config: &Config, channels: Vec<Channel>) -> Result<Self> {
+        if channels.contains(&Channel::Webhook) && config.webhook_url.is_none() {
+            return Err(WeatherError::InvalidSetting(
+                "No webhook is configured.\n\
+                 Hint: run `weather config set webhook_url <url>` first."
+                    .to_string(),
+            ));
+        }
+
+        Ok(Self {
+            channels,
+            client: client_from_config(config)?,
+            webhook_url: config.webhook_url.clone(),
+        })
+    }
+
+    pub fn channels(&self) -> &[Channel] {
+        &self.channels
+    }
+
+    /// Tell `channel` about `alert` at `location`.
+    pub async fn send(&self, channel: Channel, location: &str, alert: &WeatherAlert) -> Result<()> {
+        match channel {
+            Channel::Desktop => desktop(&format!("{location}: {}", alert.event), &alert.headline),
+            Channel::Webhook => self.webhook(location, alert).await,
+        }
+    }
+
+    async fn webhook(&self, location: &str, alert: &WeatherAlert) -> Result<()> {
+        let Some(url) = &self.webhook_url else {
+            return Ok(());
+        };
+
+        let request = self.client.post(url).json(&WebhookPayload { location, alert });
+        let res = request.send().await.context("Failed to call the webhook")?;
+
+        let status = res.status();
+        if !status.is_success() {
+            let body = res.text().await.unwrap_or_default();
+            return Err(StatusError { endpoint: "Webhook".to_string(), status, body }.into());
+        }
+        Ok(())
+    }
+}
+
+/// Show a desktop notification with the platform's own tool.
+fn desktop(title: &str, body: &str) -> Result<()> {
+    let mut command = if cfg!(target_os = "macos") {
+        let quote = |text: &str| format!("\"{}\"", text.replace('\\', "\\\\").replace('"', "\\\""));
+        let script = format!("display notification {} with title {}", quote(body), quote(title));
+        let mut command = Command::new("osascript");
+        command.args(["-e", &script]);
+        command
+    } else if cfg!(unix) {
+        let mut command = Command::new("notify-send");
+        command.args(["--app-name=weather", "--urgency=critical", title, body]);
+        command
+    } else {
+        return Err(WeatherError::InvalidSetting(
+            "Desktop notifications are not supported on this platform; use a webhook.".to_string(),
+        ));
+    };
+
+    let output = command.output().context("Failed to show a desktop notification")?;
+    if !output.status.success() {
+        return Err(WeatherError::Io {
+            context: format!(
+                "Failed to show a desktop notification: {}",
+                String::from_utf8_lossy(&output.stderr).trim()
+            ),
+            source: std::io::Error::other(output.status.to_string()),
+        });
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::provider::http::testing::serve;
+
+    #[tokio::test]
+    async fn webhooks_get_the_alert_as_json() {
+        let ok = "HTTP/1.1 204 No Content\r\nConnection: close\r\n\r\n";
+        let (url, requests) = serve(vec![ok]).await;
+        let mut config = Config::default();
+        config.set_value("webhook_url", &format!("{url}hook")).unwrap();
+        let notifier = Notifier::from_config(&config, vec![Channel::Webhook]).unwrap();
+        let alert = WeatherAlert {
+            id: "threshold:wind_speed".to_string(),
+            event: "Strong wind".to_string(),
+            severity: None,
+            headline: "21.0 m/s".to_string(),
+            starts: None,
+            ends: None,
+        };
+
+        notifier.send(Channel::Webhook, "home", &alert).await.unwrap();
+        assert_eq!(requests.recv().unwrap(), "POST /hook HTTP/1.1");
+
+        let err = Notifier::from_config(&Config::default(), vec![Channel::Webhook]).unwrap_err();
+        assert!(matches!(err, WeatherError::InvalidSetting(_)));
+    }
+}
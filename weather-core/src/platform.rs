@@ -0,0 +1,38 @@
+//! Differences between native targets and `wasm32` (browsers).
+//!
+//! On `wasm32-unknown-unknown` there is no tokio runtime, so timers come from the
+//! JavaScript host, and there is no file system: config, cache and state files are
+//! compiled out and must be supplied by the embedding application instead.
+
+use std::time::Duration;
+
+/// Wait for `duration` without blocking the executor.
+#[cfg(not(target_arch = "wasm32"))]
+pub(crate) async fn sleep(duration: Duration) {
+    tokio::time::sleep(duration).await;
+}
+
+/// Wait for `duration` without blocking the executor.
+#[cfg(target_arch = "wasm32")]
+pub(crate) async fn sleep(duration: Duration) {
+    use wasm_bindgen::{JsCast, JsValue};
+
+    let millis = duration.as_millis() as f64;
+    let promise = js_sys::Promise::new(&mut |resolve, _reject| {
+        // Looked up on the global object so that windows and workers both work.
+        let set_timeout = js_sys::Reflect::get(&js_sys::global(), &"setTimeout".into())
+            .ok()
+            .and_then(|f| f.dyn_into::<js_sys::Function>().ok());
+
+        match set_timeout {
+            Some(set_timeout) => {
+                let _ = set_timeout.call2(&JsValue::NULL, &resolve, &millis.into());
+            }
+            None => {
+                let _ = resolve.call0(&JsValue::NULL);
+            }
+        }
+    });
+
+    let _ = wasm_bindgen_futures::JsFuture::from(promise).await;
+}
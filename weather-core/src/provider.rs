@@ -1,12 +1,31 @@
 use crate::{
-    Config, WeatherRequest, WeatherResponse,
-    provider::{openweather::OpenWeatherProvider, weatherapi::WeatherApiProvider},
+    Config, WeatherRequest, WeatherResponse, WeatherSeries,
+    alerts::AlertReport,
+    config::Quota,
+    error::{Result, WeatherError},
+    geocode::Place,
+    provider::{
+        http::client_from_config, openweather::OpenWeatherProvider, ratelimit::RateLimiter,
+        rotation::KeyRotatingProvider, weatherapi::WeatherApiProvider,
+    },
+    usage::UsageLog,
 };
 use async_trait::async_trait;
 use chrono::{DateTime, Utc};
-use std::{convert::TryFrom, fmt::Debug};
+use futures_util::{StreamExt, stream};
+use std::{convert::TryFrom, fmt::Debug, ops::Range};
 
+#[cfg(all(feature = "blocking", not(target_arch = "wasm32")))]
+pub mod blocking;
+#[cfg(feature = "cassette")]
+pub mod cassette;
+pub mod circuit;
+pub mod http;
+#[cfg(any(test, feature = "test-util"))]
+pub mod mock;
 pub mod openweather;
+pub mod ratelimit;
+pub mod rotation;
 pub mod weatherapi;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
@@ -23,6 +42,32 @@ impl ProviderId {
         }
     }
 
+    /// Requests per minute allowed on the provider's free tier, if it has such a limit.
+    pub fn default_rate_limit(&self) -> Option<u32> {
+        match self {
+            ProviderId::OpenWeather => Some(60),
+            // Free plan is capped per month only.
+            ProviderId::WeatherApi => None,
+        }
+    }
+
+    /// Days ahead the provider's free plan forecasts, today included.
+    pub fn forecast_days(&self) -> u8 {
+        match self {
+            ProviderId::OpenWeather => 5,
+            ProviderId::WeatherApi => 3,
+        }
+    }
+
+    /// Call quota of the provider's free plan.
+    pub fn default_quota(&self) -> Quota {
+        match self {
+            // Current weather and 5 day / 3 hour forecast APIs.
+            ProviderId::OpenWeather => Quota { daily: None, monthly: Some(1_000_000) },
+            ProviderId::WeatherApi => Quota { daily: None, monthly: Some(100_000) },
+        }
+    }
+
     pub const fn all() -> &'static [ProviderId] {
         &[ProviderId::OpenWeather, ProviderId::WeatherApi]
     }
@@ -35,7 +80,7 @@ impl std::fmt::Display for ProviderId {
 }
 
 impl TryFrom<&str> for ProviderId {
-    type Error = anyhow::Error;
+    type Error = WeatherError;
 
     fn try_from(value: &str) -> Result<Self, Self::Error> {
         let lower = value.to_lowercase();
@@ -43,44 +88,191 @@ impl TryFrom<&str> for ProviderId {
         match lower.as_str() {
             "openweather" => Ok(ProviderId::OpenWeather),
             "weatherapi" => Ok(ProviderId::WeatherApi),
-            _ => Err(anyhow::anyhow!(
-                "Unknown provider '{value}'. Supported providers: openweather, weatherapi."
-            )),
+            _ => Err(WeatherError::UnknownProvider(value.to_string())),
         }
     }
 }
 
-#[async_trait]
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
 pub trait WeatherProvider: Send + Sync + Debug {
-    async fn get_weather(&self, request: &WeatherRequest) -> anyhow::Result<WeatherResponse>;
+    async fn get_weather(&self, request: &WeatherRequest) -> Result<WeatherResponse>;
+
+    /// Resolve `request` into the call [`get_weather`](Self::get_weather) would make,
+    /// without sending anything.
+    fn plan(&self, _request: &WeatherRequest) -> Result<RequestPlan> {
+        Err(WeatherError::PlanUnsupported)
+    }
+
+    /// Places matching a free-text address, best match first (at most a handful).
+    async fn geocode(&self, _address: &str) -> Result<Vec<Place>> {
+        Err(WeatherError::GeocodingUnsupported)
+    }
+
+    /// The forecast for `request.location` over `range`, every step the provider has in
+    /// it, e.g. for charts or [`trend`](crate::trend) analysis. `request.when` is ignored;
+    /// the range may start in the past, but only forecast steps are returned.
+    async fn get_forecast_series(
+        &self,
+        _request: &WeatherRequest,
+        _range: Range<DateTime<Utc>>,
+    ) -> Result<WeatherSeries> {
+        Err(WeatherError::ForecastUnsupported)
+    }
+
+    /// Official warnings active at or announced for `request.location`, with the current
+    /// weather there, e.g. for `weather monitor`. `request.when` is ignored.
+    async fn alerts(&self, _request: &WeatherRequest) -> Result<AlertReport> {
+        Err(WeatherError::AlertsUnsupported)
+    }
+}
+
+/// A provider call as it would be sent; see [`WeatherProvider::plan`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RequestPlan {
+    pub provider: ProviderId,
+    pub date: DateRequest,
+    pub url: String,
+    /// Query parameters in request order, with the API key masked.
+    pub query: Vec<(String, String)>,
+}
+
+impl RequestPlan {
+    /// Plan for `url` and `query`, masking the value of the `secret` parameter.
+    pub(crate) fn new(
+        provider: ProviderId,
+        date: DateRequest,
+        url: String,
+        query: &[(&str, impl AsRef<str>)],
+        secret: &str,
+    ) -> Self {
+        let query = query
+            .iter()
+            .map(|(name, value)| {
+                let value = value.as_ref();
+                let value = if *name == secret { mask(value) } else { value.to_string() };
+                (name.to_string(), value)
+            })
+            .collect();
+
+        Self { provider, date, url, query }
+    }
 }
 
-/// Construct a provider from config and explicit ProviderId.
-pub fn provider_from_config(
-    id: ProviderId,
-    config: &Config,
-) -> anyhow::Result<Box<dyn WeatherProvider>> {
-    let api_key = config.provider_api_key(id).ok_or_else(|| {
-        anyhow::anyhow!(
-            "No API key configured for provider '{id}'.\n\
-                 Hint: run `weather configure {id}` and enter your API key."
-        )
-    })?;
-
-    let boxed: Box<dyn WeatherProvider> = match id {
-        ProviderId::OpenWeather => Box::new(OpenWeatherProvider::new(api_key.to_owned())),
-        ProviderId::WeatherApi => Box::new(WeatherApiProvider::new(api_key.to_owned())),
+/// Keep only the last four characters of long secrets, so keys can still be told apart.
+pub(crate) fn mask(secret: &str) -> String {
+    let len = secret.chars().count();
+    if len < 12 {
+        return "*".repeat(8);
+    }
+
+    let tail: String = secret.chars().skip(len - 4).collect();
+    format!("{}{tail}", "*".repeat(8))
+}
+
+/// Construct a provider from config and explicit ProviderId. A provider with several
+/// API keys uses them in turn, see [`rotation`].
+pub fn provider_from_config(id: ProviderId, config: &Config) -> Result<Box<dyn WeatherProvider>> {
+    let api_keys = config.provider_api_keys(id);
+    if api_keys.is_empty() {
+        // The key may well be among the encrypted ones.
+        return Err(if config.is_locked() {
+            WeatherError::ConfigLocked
+        } else {
+            WeatherError::ProviderNotConfigured(id)
+        });
+    }
+
+    let client = client_from_config(config)?;
+    let retry = config.retry_policy();
+    // Rate limit state and the usage log are files, which wasm32 does not have.
+    #[cfg(not(target_arch = "wasm32"))]
+    let (limiter, usage) = (
+        RateLimiter::from_config(config, id)?,
+        Some(UsageLog::new(UsageLog::default_path()?).with_quota(config.quota(id))),
+    );
+    #[cfg(target_arch = "wasm32")]
+    let (limiter, usage): (Option<RateLimiter>, Option<UsageLog>) = (None, None);
+
+    // With several keys, each one is rate limited and counted on its own.
+    let several = api_keys.len() > 1;
+    let keyed = |api_key: &str| -> Result<Box<dyn WeatherProvider>> {
+        let (client, mut usage, mut limiter) = (client.clone(), usage.clone(), limiter.clone());
+        if several {
+            usage = usage.map(|usage| usage.for_key(mask(api_key)));
+            limiter = limiter.map(|limiter| limiter.for_key(mask(api_key)));
+        }
+
+        Ok(match id {
+            ProviderId::OpenWeather => {
+                let mut builder = OpenWeatherProvider::builder()
+                    .api_key(api_key)
+                    .client(client)
+                    .retry_policy(retry);
+                if let Some(usage) = usage {
+                    builder = builder.usage_log(usage);
+                }
+                if let Some(limiter) = limiter {
+                    builder = builder.rate_limiter(limiter);
+                }
+                #[cfg(feature = "cassette")]
+                if let Some(cassette) = cassette::Cassette::from_env()? {
+                    builder = builder.cassette(cassette);
+                }
+                Box::new(builder.build()?)
+            }
+            ProviderId::WeatherApi => {
+                let mut builder = WeatherApiProvider::builder()
+                    .api_key(api_key)
+                    .client(client)
+                    .retry_policy(retry);
+                if let Some(usage) = usage {
+                    builder = builder.usage_log(usage);
+                }
+                if let Some(limiter) = limiter {
+                    builder = builder.rate_limiter(limiter);
+                }
+                #[cfg(feature = "cassette")]
+                if let Some(cassette) = cassette::Cassette::from_env()? {
+                    builder = builder.cassette(cassette);
+                }
+                Box::new(builder.build()?)
+            }
+        })
     };
 
-    Ok(boxed)
+    if let [api_key] = api_keys[..] {
+        return keyed(api_key);
+    }
+
+    let keys = api_keys.into_iter().map(keyed).collect::<Result<Vec<_>>>()?;
+    let rotating = KeyRotatingProvider::new(keys, id, config.key_rotation())?;
+    #[cfg(not(target_arch = "wasm32"))]
+    let rotating = rotating.with_state_file(KeyRotatingProvider::default_state_file()?);
+
+    Ok(Box::new(rotating))
 }
 
 /// Construct the default provider from config, using `default_provider` field.
-pub fn default_provider_from_config(config: &Config) -> anyhow::Result<Box<dyn WeatherProvider>> {
+pub fn default_provider_from_config(config: &Config) -> Result<Box<dyn WeatherProvider>> {
     let id = config.default_provider_id()?;
     provider_from_config(id, config)
 }
 
+/// Ask `provider` for every request, at most `concurrency` at a time. Results are in
+/// request order; one failure does not stop the others.
+pub async fn get_weather_all(
+    provider: &dyn WeatherProvider,
+    requests: &[WeatherRequest],
+    concurrency: usize,
+) -> Vec<Result<WeatherResponse>> {
+    stream::iter(requests)
+        .map(|request| provider.get_weather(request))
+        .buffered(concurrency.max(1))
+        .collect()
+        .await
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum DateRequest {
     Current,
@@ -137,6 +329,43 @@ mod tests {
         assert_eq!(classify_date(now, Some(future)), DateRequest::Future(future));
     }
 
+    #[tokio::test]
+    async fn get_weather_all_keeps_request_order() {
+        use crate::provider::mock::{MockProvider, response};
+
+        let provider = MockProvider::new()
+            .respond(response("Kyiv"))
+            .fail(WeatherError::NoDefaultProvider)
+            .respond(response("Odesa"));
+        let requests: Vec<_> = ["Kyiv", "Lviv", "Odesa"]
+            .into_iter()
+            .map(|address| WeatherRequest::builder().address(address).build().unwrap())
+            .collect();
+
+        let results = get_weather_all(&provider, &requests, 2).await;
+
+        assert_eq!(results.len(), 3);
+        assert_eq!(results[0].as_ref().unwrap().location_name, "Kyiv");
+        assert!(matches!(results[1], Err(WeatherError::NoDefaultProvider)));
+        assert_eq!(results[2].as_ref().unwrap().location_name, "Odesa");
+        assert_eq!(provider.calls().len(), 3);
+    }
+
+    #[test]
+    fn plans_mask_the_secret_parameter() {
+        let plan = RequestPlan::new(
+            ProviderId::WeatherApi,
+            DateRequest::Current,
+            "http://example.test/current.json".to_string(),
+            &[("key", "0123456789abcdef"), ("q", "Kyiv")],
+            "key",
+        );
+
+        assert_eq!(plan.query[0], ("key".to_string(), "********cdef".to_string()));
+        assert_eq!(plan.query[1], ("q".to_string(), "Kyiv".to_string()));
+        assert_eq!(mask("short"), "********");
+    }
+
     #[test]
     fn provider_id_as_str_roundtrip() {
         for id in ProviderId::all() {
@@ -149,6 +378,7 @@ mod tests {
     #[test]
     fn unknown_provider_error() {
         let err = ProviderId::try_from("doesnotexist").unwrap_err();
+        assert!(matches!(&err, WeatherError::UnknownProvider(name) if name == "doesnotexist"));
         assert!(err.to_string().contains("Unknown provider"));
     }
 
@@ -156,6 +386,7 @@ mod tests {
     fn provider_from_config_errors_when_missing_api_key() {
         let cfg = Config::default();
         let err = provider_from_config(ProviderId::OpenWeather, &cfg).unwrap_err();
+        assert!(matches!(err, WeatherError::ProviderNotConfigured(ProviderId::OpenWeather)));
         assert!(err.to_string().contains("No API key configured for provider"));
     }
 
@@ -0,0 +1,63 @@
+//! Synchronous access to providers (`blocking` feature).
+//!
+//! For applications and scripts that are not async: every call runs the provider on a
+//! private single-threaded runtime until it finishes, so callers never deal with tokio
+//! themselves.
+//!
+//! ```no_run
+//! use weather_core::{Config, WeatherRequest, provider::blocking::WeatherProviderBlockingExt};
+//!
+//! let config = Config::load()?;
+//! let provider = weather_core::provider::default_provider_from_config(&config)?;
+//!
+//! let request = WeatherRequest::builder().address("Kyiv").build()?;
+//! let weather = provider.get_weather_blocking(&request)?;
+//! println!("{}: {}", weather.location_name, weather.condition);
+//! # Ok::<(), weather_core::WeatherError>(())
+//! ```
+
+use tokio::runtime::{Builder, Runtime};
+
+use super::WeatherProvider;
+use crate::{
+    WeatherRequest, WeatherResponse,
+    error::{Context, Result},
+};
+
+/// Blocking counterpart of [`WeatherProvider::get_weather`], for every provider.
+///
+/// Must not be called from inside an async runtime: tokio panics when one runtime is
+/// blocked on from within another.
+pub trait WeatherProviderBlockingExt: WeatherProvider {
+    fn get_weather_blocking(&self, request: &WeatherRequest) -> Result<WeatherResponse> {
+        runtime()?.block_on(self.get_weather(request))
+    }
+}
+
+impl<P: WeatherProvider + ?Sized> WeatherProviderBlockingExt for P {}
+
+fn runtime() -> Result<Runtime> {
+    Builder::new_current_thread().enable_all().build().context("Failed to start async runtime")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{WeatherError, provider::mock::MockProvider};
+
+    #[test]
+    fn answers_without_an_async_context() {
+        let provider: Box<dyn WeatherProvider> = Box::new(
+            MockProvider::new()
+                .respond(crate::provider::mock::response("Kyiv"))
+                .fail(WeatherError::NoDefaultProvider),
+        );
+        let request = WeatherRequest::builder().address("Kyiv").build().unwrap();
+
+        assert_eq!(provider.get_weather_blocking(&request).unwrap().location_name, "Kyiv");
+        assert!(matches!(
+            provider.get_weather_blocking(&request),
+            Err(WeatherError::NoDefaultProvider)
+        ));
+    }
+}
@@ -0,0 +1,220 @@
+//! Record/replay of provider HTTP traffic (`cassette` feature).
+//!
+//! In record mode every completed request made through [`HttpClient`](super::http::HttpClient)
+//! is saved, together with its response, to a JSON cassette file. In replay mode the
+//! cassette answers instead of the network, which makes demos work offline and lets tests
+//! run the JSON mapping code against real provider payloads deterministically.
+//!
+//! API keys are never written: the values of known key parameters are redacted before
+//! saving and before matching. For the CLI, set `WEATHER_CASSETTE` to `record:<file>` or
+//! `replay:<file>`.
+
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex},
+};
+
+use reqwest::StatusCode;
+use serde::{Deserialize, Serialize};
+
+use super::http::HttpResponse;
+use crate::error::{Context, Result, WeatherError};
+
+/// Query parameters that carry API keys.
+const SECRET_PARAMS: &[&str] = &["appid", "key"];
+const REDACTED: &str = "<redacted>";
+
+/// Environment variable selecting a cassette for providers built from config.
+pub const CASSETTE_ENV: &str = "WEATHER_CASSETTE";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CassetteMode {
+    /// Send requests and save them with their responses.
+    Record,
+    /// Answer from the cassette, never touching the network.
+    Replay,
+}
+
+/// One recorded request/response pair.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+struct Interaction {
+    url: String,
+    query: Vec<(String, String)>,
+    status: u16,
+    body: String,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct CassetteFile {
+    interactions: Vec<Interaction>,
+}
+
+/// A cassette file plus the interactions loaded from it.
+#[derive(Debug, Clone)]
+pub struct Cassette {
+    path: PathBuf,
+    mode: CassetteMode,
+    interactions: Arc<Mutex<Vec<Interaction>>>,
+}
+
+impl Cassette {
+    /// Record into `path`, keeping interactions already on it (same requests are replaced).
+    pub fn record(path: impl Into<PathBuf>) -> Result<Self> {
+        let path = path.into();
+        let interactions = if path.exists() { load(&path)? } else { Vec::new() };
+
+        Ok(Self { path, mode: CassetteMode::Record, interactions: Arc::new(interactions.into()) })
+    }
+
+    /// Replay the interactions recorded in `path`.
+    pub fn replay(path: impl Into<PathBuf>) -> Result<Self> {
+        let path = path.into();
+        let interactions = load(&path)?;
+
+        Ok(Self { path, mode: CassetteMode::Replay, interactions: Arc::new(interactions.into()) })
+    }
+
+    /// Cassette selected by [`CASSETTE_ENV`], e.g. `replay:demo.json`; `None` if unset.
+    pub fn from_env() -> Result<Option<Self>> {
+        let Ok(value) = std::env::var(CASSETTE_ENV) else {
+            return Ok(None);
+        };
+
+        match value.split_once(':') {
+            Some(("record", path)) => Self::record(path).map(Some),
+            Some(("replay", path)) => Self::replay(path).map(Some),
+            _ => Err(WeatherError::InvalidSetting(format!(
+                "Invalid {CASSETTE_ENV} '{value}'. Expected record:<file> or replay:<file>."
+            ))),
+        }
+    }
+
+    pub fn mode(&self) -> CassetteMode {
+        self.mode
+    }
+
+    /// The recorded response for a request.
+    pub(crate) fn find(
+        &self,
+        url: &str,
+        query: &[(&str, impl AsRef<str>)],
+    ) -> Result<HttpResponse> {
+        let query = redact(query);
+        let interactions = self.interactions.lock().unwrap_or_else(|p| p.into_inner());
+
+        let found =
+            interactions.iter().find(|i| i.url == url && i.query == query).ok_or_else(|| {
+                WeatherError::Cassette(format!(
+                    "No recorded response for GET {url} in cassette {}.\n\
+                     Hint: record it first with {CASSETTE_ENV}=record:{}.",
+                    self.path.display(),
+                    self.path.display()
+                ))
+            })?;
+
+        let status = StatusCode::from_u16(found.status).map_err(|err| WeatherError::Parse {
+            context: format!("Invalid status in cassette {}", self.path.display()),
+            source: Box::new(err),
+        })?;
+
+        Ok(HttpResponse { status, body: found.body.clone(), retry_after: None })
+    }
+
+    /// Save a completed request, replacing an earlier recording of the same request.
+    pub(crate) fn store(
+        &self,
+        url: &str,
+        query: &[(&str, impl AsRef<str>)],
+        response: &HttpResponse,
+    ) -> Result<()> {
+        let interaction = Interaction {
+            url: url.to_string(),
+            query: redact(query),
+            status: response.status.as_u16(),
+            body: response.body.clone(),
+        };
+
+        let mut interactions = self.interactions.lock().unwrap_or_else(|p| p.into_inner());
+        interactions.retain(|i| i.url != interaction.url || i.query != interaction.query);
+        interactions.push(interaction);
+
+        save(&self.path, &interactions)
+    }
+}
+
+fn redact(query: &[(&str, impl AsRef<str>)]) -> Vec<(String, String)> {
+    query
+        .iter()
+        .map(|(name, value)| {
+            let value = if SECRET_PARAMS.contains(name) { REDACTED } else { value.as_ref() };
+            (name.to_string(), value.to_string())
+        })
+        .collect()
+}
+
+fn load(path: &Path) -> Result<Vec<Interaction>> {
+    let contents = fs::read_to_string(path)
+        .with_context(|| format!("Failed to read cassette: {}", path.display()))?;
+    let file: CassetteFile = serde_json::from_str(&contents)
+        .with_context(|| format!("Failed to parse cassette: {}", path.display()))?;
+
+    Ok(file.interactions)
+}
+
+fn save(path: &Path, interactions: &[Interaction]) -> Result<()> {
+    if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+        fs::create_dir_all(parent).with_context(|| {
+            format!("Failed to create cassette directory: {}", parent.display())
+        })?;
+    }
+
+    let file = CassetteFile { interactions: interactions.to_vec() };
+    let json = serde_json::to_string_pretty(&file).context("Failed to serialize cassette")?;
+    fs::write(path, json).with_context(|| format!("Failed to write cassette: {}", path.display()))
+}
+
+#[cfg(test)]
+mod tests {
+    use reqwest::Client;
+
+    use super::*;
+    use crate::provider::http::{
+        HttpClient, RetryPolicy,
+        testing::{json_ok, serve},
+    };
+
+    #[tokio::test]
+    async fn recorded_responses_replay_without_network() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("cassette.json");
+        let (url, _) = serve(vec![json_ok(r#"{"ok":true}"#)]).await;
+        let query = [("q", "Kyiv"), ("appid", "secret")];
+
+        let recorder = HttpClient::new(Client::new(), RetryPolicy::default())
+            .with_cassette(Cassette::record(&path).unwrap());
+        recorder.get(&url, &query).await.unwrap();
+
+        let saved = fs::read_to_string(&path).unwrap();
+        assert!(!saved.contains("secret"));
+
+        // The server is gone; only the cassette can answer.
+        let player = HttpClient::new(Client::new(), RetryPolicy::default())
+            .with_cassette(Cassette::replay(&path).unwrap());
+        let res = player.get(&url, &[("q", "Kyiv"), ("appid", "other-key")]).await.unwrap();
+        assert_eq!(res.status, StatusCode::OK);
+        assert_eq!(res.body, r#"{"ok":true}"#);
+
+        let err = player.get(&url, &[("q", "Lviv")]).await.unwrap_err();
+        assert!(matches!(err, WeatherError::Cassette(_)));
+        assert!(err.to_string().contains("No recorded response"));
+    }
+
+    #[test]
+    fn missing_cassette_cannot_be_replayed() {
+        let dir = tempfile::tempdir().unwrap();
+        let err = Cassette::replay(dir.path().join("missing.json")).unwrap_err();
+
+        assert!(err.to_string().contains("Failed to read cassette"));
+    }
+}
@@ -0,0 +1,290 @@
+//! Per-provider circuit breaker.
+//!
+//! After a number of consecutive provider-side failures (network errors, 429, 5xx)
+//! the circuit opens and calls to that provider fail fast for a cool-down period.
+//! Once it elapses a single trial call is let through: success closes the circuit,
+//! another failure re-opens it. State is kept in a small JSON file, locked while it is
+//! updated, so that it survives between CLI runs and is shared by concurrent ones.
+
+use std::{collections::HashMap, ops::Range, path::PathBuf, time::Duration};
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use tracing::warn;
+
+use super::{ProviderId, RequestPlan, WeatherProvider};
+#[cfg(not(target_arch = "wasm32"))]
+use crate::{Config, config::project_dirs};
+use crate::{
+    WeatherRequest, WeatherResponse, WeatherSeries,
+    alerts::AlertReport,
+    error::{Result, WeatherError},
+    geocode::Place,
+    state,
+};
+
+/// Returned instead of calling a provider whose circuit is open.
+#[derive(Debug, thiserror::Error)]
+#[error(
+    "Provider '{provider}' is temporarily skipped after {failures} consecutive failures; \
+     it will be tried again after {retry_at}.\n\
+     Hint: switch provider with `weather provider use <provider>` or wait for the cool-down."
+)]
+pub struct CircuitOpen {
+    pub provider: ProviderId,
+    pub failures: u32,
+    pub retry_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct CircuitState {
+    consecutive_failures: u32,
+    open_until: Option<DateTime<Utc>>,
+}
+
+/// Failure counting and cool-down bookkeeping, persisted to `state_file`.
+#[derive(Debug, Clone)]
+pub struct CircuitBreaker {
+    state_file: PathBuf,
+    failure_threshold: u32,
+    cooldown: Duration,
+}
+
+impl CircuitBreaker {
+    pub fn new(state_file: impl Into<PathBuf>, failure_threshold: u32, cooldown: Duration) -> Self {
+        Self {
+            state_file: state_file.into(),
+            failure_threshold: failure_threshold.max(1),
+            cooldown,
+        }
+    }
+
+    /// Breaker with thresholds from config, persisted in the platform cache directory.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn from_config(config: &Config) -> Result<Self> {
+        Ok(Self::new(
+            project_dirs()?.cache_dir().join("circuit.json"),
+            config.circuit_failure_threshold(),
+            config.circuit_cooldown(),
+        ))
+    }
+
+    /// Fail with [`CircuitOpen`] if `provider` is cooling down. A state file that
+    /// cannot be read counts as closed.
+    pub fn check(&self, provider: ProviderId, now: DateTime<Utc>) -> Result<(), CircuitOpen> {
+        let state = self
+            .update(|states| states.get(provider.as_str()).cloned())
+            .unwrap_or_else(|err| {
+                warn!(error = %err, "failed to read circuit state");
+                None
+            })
+            .unwrap_or_default();
+
+        match state.open_until {
+            Some(retry_at) if retry_at > now => {
+                Err(CircuitOpen { provider, failures: state.consecutive_failures, retry_at })
+            }
+            _ => Ok(()),
+        }
+    }
+
+    pub fn record_success(&self, provider: ProviderId) -> Result<()> {
+        self.update(|states| {
+            states.remove(provider.as_str());
+        })
+    }
+
+    pub fn record_failure(&self, provider: ProviderId, now: DateTime<Utc>) -> Result<()> {
+        // A cool-down past the last representable date lasts as long as one can.
+        let retry_at = chrono::Duration::from_std(self.cooldown)
+            .ok()
+            .and_then(|cooldown| now.checked_add_signed(cooldown))
+            .unwrap_or(DateTime::<Utc>::MAX_UTC);
+
+        self.update(|states| {
+            let state = states.entry(provider.as_str().to_string()).or_default();
+            state.consecutive_failures += 1;
+            if state.consecutive_failures >= self.failure_threshold {
+                warn!(%provider, failures = state.consecutive_failures, %retry_at, "circuit opened");
+                state.open_until = Some(retry_at);
+            }
+        })
+    }
+
+    fn update<R>(&self, change: impl FnOnce(&mut HashMap<String, CircuitState>) -> R) -> Result<R> {
+        state::update(&self.state_file, change)
+    }
+}
+
+/// Provider wrapper that skips a provider while its circuit is open.
+#[derive(Debug)]
+pub struct CircuitBreakerProvider {
+    inner: Box<dyn WeatherProvider>,
+    id: ProviderId,
+    breaker: CircuitBreaker,
+}
+
+impl CircuitBreakerProvider {
+    pub fn new(inner: Box<dyn WeatherProvider>, id: ProviderId, breaker: CircuitBreaker) -> Self {
+        Self { inner, id, breaker }
+    }
+
+    /// Run `call` unless the circuit is open, and record how it went.
+    async fn guard<T>(&self, call: impl Future<Output = Result<T>>) -> Result<T> {
+        if let Err(open) = self.breaker.check(self.id, Utc::now()) {
+            warn!(provider = %self.id, retry_at = %open.retry_at, "circuit open, skipping");
+            return Err(open.into());
+        }
+
+        let result = call.await;
+
+        let recorded = match &result {
+            Ok(_) => self.breaker.record_success(self.id),
+            Err(err) if is_provider_fault(err) => self.breaker.record_failure(self.id, Utc::now()),
+            Err(_) => Ok(()),
+        };
+        if let Err(err) = recorded {
+            warn!(error = %err, "failed to save circuit state");
+        }
+
+        result
+    }
+}
+
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+impl WeatherProvider for CircuitBreakerProvider {
+    async fn get_weather(&self, request: &WeatherRequest) -> Result<WeatherResponse> {
+        self.guard(self.inner.get_weather(request)).await
+    }
+
+    fn plan(&self, request: &WeatherRequest) -> Result<RequestPlan> {
+        self.inner.plan(request)
+    }
+
+    /// Not tracked: geocoding is a side lookup, not a weather request.
+    async fn geocode(&self, address: &str) -> Result<Vec<Place>> {
+        self.inner.geocode(address).await
+    }
+
+    async fn get_forecast_series(
+        &self,
+        request: &WeatherRequest,
+        range: Range<DateTime<Utc>>,
+    ) -> Result<WeatherSeries> {
+        self.guard(self.inner.get_forecast_series(request, range)).await
+    }
+
+    async fn alerts(&self, request: &WeatherRequest) -> Result<AlertReport> {
+        self.guard(self.inner.alerts(request)).await
+    }
+}
+
+/// Network failures and 429/5xx responses count against a provider; bad requests
+/// (unknown location, unsupported date, ...) do not.
+fn is_provider_fault(err: &WeatherError) -> bool {
+    match err {
+        WeatherError::Http { .. } | WeatherError::RateLimited { .. } => true,
+        WeatherError::Status(status) => status.is_provider_fault(),
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use reqwest::StatusCode;
+
+    use super::*;
+    use crate::provider::http::StatusError;
+
+    /// Fails with a 503 on every call.
+    #[derive(Debug)]
+    struct Unavailable;
+
+    #[async_trait]
+    impl WeatherProvider for Unavailable {
+        async fn get_weather(&self, _request: &WeatherRequest) -> Result<WeatherResponse> {
+            Err(StatusError {
+                endpoint: "Test".to_string(),
+                status: StatusCode::SERVICE_UNAVAILABLE,
+                body: String::new(),
+            }
+            .into())
+        }
+    }
+
+    fn request() -> WeatherRequest {
+        WeatherRequest::builder().address("Kyiv").build().unwrap()
+    }
+
+    #[test]
+    fn opens_after_threshold_and_closes_after_cooldown() {
+        let dir = tempfile::tempdir().unwrap();
+        let breaker =
+            CircuitBreaker::new(dir.path().join("circuit.json"), 2, Duration::from_secs(60));
+        let now = Utc::now();
+        let id = ProviderId::OpenWeather;
+
+        breaker.record_failure(id, now).unwrap();
+        assert!(breaker.check(id, now).is_ok());
+
+        breaker.record_failure(id, now).unwrap();
+        let open = breaker.check(id, now).unwrap_err();
+        assert_eq!(open.failures, 2);
+        assert!(breaker.check(ProviderId::WeatherApi, now).is_ok());
+
+        // Half-open after the cool-down: the next call is allowed through.
+        assert!(breaker.check(id, now + chrono::Duration::seconds(61)).is_ok());
+
+        breaker.record_success(id).unwrap();
+        breaker.record_failure(id, now).unwrap();
+        assert!(breaker.check(id, now).is_ok());
+    }
+
+    #[test]
+    fn a_huge_cooldown_keeps_the_circuit_open() {
+        let dir = tempfile::tempdir().unwrap();
+        let cooldown = Duration::from_secs(99_999_999_999_999);
+        let breaker = CircuitBreaker::new(dir.path().join("circuit.json"), 1, cooldown);
+        let now = Utc::now();
+
+        breaker.record_failure(ProviderId::OpenWeather, now).unwrap();
+        let open = breaker.check(ProviderId::OpenWeather, now).unwrap_err();
+        assert_eq!(open.retry_at, DateTime::<Utc>::MAX_UTC);
+    }
+
+    #[tokio::test]
+    async fn open_circuit_skips_the_provider() {
+        let dir = tempfile::tempdir().unwrap();
+        let breaker =
+            CircuitBreaker::new(dir.path().join("circuit.json"), 2, Duration::from_secs(60));
+        let provider =
+            CircuitBreakerProvider::new(Box::new(Unavailable), ProviderId::WeatherApi, breaker);
+
+        for _ in 0..2 {
+            let err = provider.get_weather(&request()).await.unwrap_err();
+            assert!(matches!(err, WeatherError::Status(_)));
+        }
+
+        let err = provider.get_weather(&request()).await.unwrap_err();
+        assert!(matches!(err, WeatherError::CircuitOpen(_)));
+        assert!(err.to_string().contains("temporarily skipped"));
+    }
+
+    #[test]
+    fn client_errors_do_not_count() {
+        let bad_request = WeatherError::Status(StatusError {
+            endpoint: "Test".to_string(),
+            status: StatusCode::BAD_REQUEST,
+            body: String::new(),
+        });
+
+        assert!(!is_provider_fault(&bad_request));
+        assert!(!is_provider_fault(&WeatherError::UnsupportedDate("Not supported".to_string())));
+        assert!(is_provider_fault(&WeatherError::RateLimited {
+            provider: ProviderId::OpenWeather,
+            retry_after: None,
+        }));
+    }
+}
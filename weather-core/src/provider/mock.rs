@@ -0,0 +1,169 @@
+//! Scripted provider for tests (`test-util` feature).
+//!
+//! ```
+//! # use std::time::Duration;
+//! # use weather_core::{ProviderId, WeatherError, WeatherProvider, WeatherRequest};
+//! # use weather_core::provider::mock::{self, MockProvider};
+//! # #[tokio::main]
+//! # async fn main() {
+//! let provider = MockProvider::new()
+//!     .respond(mock::response("Kyiv"))
+//!     .delay(Duration::from_millis(10))
+//!     .fail(WeatherError::RateLimited { provider: ProviderId::OpenWeather, retry_after: None });
+//!
+//! let request = WeatherRequest::builder().address("Kyiv").build().unwrap();
+//! assert!(provider.get_weather(&request).await.is_ok());
+//! assert!(provider.get_weather(&request).await.is_err());
+//! assert_eq!(provider.calls().len(), 2);
+//! # }
+//! ```
+
+use std::{
+    collections::VecDeque,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use async_trait::async_trait;
+use chrono::Utc;
+
+use super::WeatherProvider;
+use crate::error::{Result, WeatherError};
+use crate::platform;
+use crate::{
+    Precipitation, Pressure, Temperature, WeatherRequest, WeatherResponse, WindDirection, WindSpeed,
+};
+
+#[derive(Debug)]
+enum Step {
+    Respond(WeatherResponse),
+    Fail(WeatherError),
+    Sleep(Duration),
+}
+
+/// A [`WeatherProvider`] that plays back a script.
+///
+/// Every call consumes steps until it reaches a response or an error: delays queued in
+/// between are slept through first. Once the script runs out, calls fail. Clones share
+/// the script and the call log.
+#[derive(Debug, Clone, Default)]
+pub struct MockProvider {
+    script: Arc<Mutex<VecDeque<Step>>>,
+    calls: Arc<Mutex<Vec<WeatherRequest>>>,
+}
+
+impl MockProvider {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Answer the next call with `response`.
+    pub fn respond(self, response: WeatherResponse) -> Self {
+        self.push(Step::Respond(response))
+    }
+
+    /// Fail the next call with `error`.
+    pub fn fail(self, error: WeatherError) -> Self {
+        self.push(Step::Fail(error))
+    }
+
+    /// Wait for `delay` before the next response or error.
+    pub fn delay(self, delay: Duration) -> Self {
+        self.push(Step::Sleep(delay))
+    }
+
+    /// Requests received so far, in order.
+    pub fn calls(&self) -> Vec<WeatherRequest> {
+        self.calls.lock().unwrap_or_else(|p| p.into_inner()).clone()
+    }
+
+    fn push(self, step: Step) -> Self {
+        self.script.lock().unwrap_or_else(|p| p.into_inner()).push_back(step);
+        self
+    }
+
+    fn next_step(&self) -> Option<Step> {
+        self.script.lock().unwrap_or_else(|p| p.into_inner()).pop_front()
+    }
+}
+
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+impl WeatherProvider for MockProvider {
+    async fn get_weather(&self, request: &WeatherRequest) -> Result<WeatherResponse> {
+        self.calls.lock().unwrap_or_else(|p| p.into_inner()).push(request.clone());
+
+        loop {
+            match self.next_step() {
+                Some(Step::Sleep(delay)) => platform::sleep(delay).await,
+                Some(Step::Respond(response)) => return Ok(response),
+                Some(Step::Fail(error)) => return Err(error),
+                None => {
+                    return Err(WeatherError::Mock(format!(
+                        "MockProvider has no scripted answer for '{}'",
+                        request.location
+                    )));
+                }
+            }
+        }
+    }
+}
+
+/// A plausible response for `location`, observed now, to script or tweak in tests.
+pub fn response(location: &str) -> WeatherResponse {
+    WeatherResponse {
+        provider: "mock".to_string(),
+        location_name: location.to_string(),
+        temperature: Temperature::from_celsius(12.5),
+        feels_like: Temperature::from_celsius(11.0),
+        condition: "Partly cloudy".to_string(),
+        humidity_pct: 65,
+        wind_speed: WindSpeed::from_mps(3.0),
+        wind_direction: Some(WindDirection::from_degrees(270.0)),
+        pressure: Pressure::from_hpa(1015.0),
+        precipitation: Precipitation::from_mm(0.0),
+        observation_time: Utc::now(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn request(address: &str) -> WeatherRequest {
+        WeatherRequest::builder().address(address).build().unwrap()
+    }
+
+    #[tokio::test]
+    async fn plays_the_script_in_order() {
+        let provider = MockProvider::new()
+            .respond(response("Kyiv"))
+            .fail(WeatherError::NoDefaultProvider)
+            .delay(Duration::from_millis(5))
+            .respond(response("Lviv"));
+
+        let first = provider.get_weather(&request("Kyiv")).await.unwrap();
+        assert_eq!(first.location_name, "Kyiv");
+        let err = provider.get_weather(&request("Kyiv")).await.unwrap_err();
+        assert!(matches!(err, WeatherError::NoDefaultProvider));
+        assert_eq!(provider.get_weather(&request("Lviv")).await.unwrap().location_name, "Lviv");
+
+        let err = provider.get_weather(&request("Odesa")).await.unwrap_err();
+        assert!(err.to_string().contains("no scripted answer for 'Odesa'"));
+
+        let addresses: Vec<_> =
+            provider.calls().into_iter().map(|r| r.location.to_string()).collect();
+        assert_eq!(addresses, ["Kyiv", "Kyiv", "Lviv", "Odesa"]);
+    }
+
+    #[tokio::test]
+    async fn clones_share_the_script() {
+        let provider = MockProvider::new().respond(response("Kyiv"));
+        let boxed: Box<dyn WeatherProvider> = Box::new(provider.clone());
+
+        boxed.get_weather(&request("Kyiv")).await.unwrap();
+
+        assert_eq!(provider.calls().len(), 1);
+        assert!(provider.get_weather(&request("Kyiv")).await.is_err());
+    }
+}
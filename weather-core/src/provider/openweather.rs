@@ -1,51 +1,96 @@
-use anyhow::{Context, Result, anyhow};
+use std::ops::Range;
+
 use async_trait::async_trait;
 use chrono::{DateTime, Utc};
-use reqwest::Client;
+use reqwest::{Client, StatusCode};
 use serde::Deserialize;
 
 use crate::{
-    model::{WeatherRequest, WeatherResponse},
+    error::{Context, Result, WeatherError},
+    geocode::{MAX_CANDIDATES, Place},
+    model::{
+        Location, Precipitation, Pressure, Temperature, WeatherPoint, WeatherRequest,
+        WeatherResponse, WeatherSeries, WindDirection, WindSpeed,
+    },
     provider::{DateRequest, classify_date},
 };
 
-use super::WeatherProvider;
+use super::{
+    ProviderId, RequestPlan, WeatherProvider,
+    http::{HttpClient, HttpResponse, RetryPolicy},
+    ratelimit::RateLimiter,
+};
+use crate::usage::UsageLog;
+
+const DEFAULT_BASE_URL: &str = "https://api.openweathermap.org";
 
 #[derive(Debug, Clone)]
 pub struct OpenWeatherProvider {
     api_key: String,
-    http: Client,
+    http: HttpClient,
+    base_url: String,
 }
 
 impl OpenWeatherProvider {
+    /// Provider with a default HTTP client and the production API.
     pub fn new(api_key: String) -> Self {
-        Self { api_key, http: Client::new() }
+        Self { api_key, http: HttpClient::default(), base_url: DEFAULT_BASE_URL.to_string() }
     }
 
-    async fn fetch_current(&self, address: &str) -> Result<WeatherResponse> {
-        let url = "https://api.openweathermap.org/data/2.5/weather";
-
-        let res = self
-            .http
-            .get(url)
-            .query(&[("q", address), ("appid", self.api_key.as_str()), ("units", "metric")])
-            .send()
-            .await
-            .context("Failed to send request to OpenWeather (current weather)")?;
+    /// ```no_run
+    /// # use weather_core::provider::openweather::OpenWeatherProvider;
+    /// let provider = OpenWeatherProvider::builder()
+    ///     .api_key("KEY")
+    ///     .client(reqwest::Client::new())
+    ///     .base_url("http://localhost:8080")
+    ///     .build()?;
+    /// # Ok::<(), weather_core::WeatherError>(())
+    /// ```
+    pub fn builder() -> OpenWeatherProviderBuilder {
+        OpenWeatherProviderBuilder::default()
+    }
 
-        let status = res.status();
-        let body = res.text().await.context("Failed to read OpenWeather current response body")?;
+    /// Query parameters shared by the current and forecast endpoints.
+    fn query(&self, request: &WeatherRequest) -> Vec<(&'static str, String)> {
+        let mut query = match &request.location {
+            Location::Address(address) => vec![("q", address.clone())],
+            Location::Coordinates { lat, lon } => {
+                vec![("lat", lat.to_string()), ("lon", lon.to_string())]
+            }
+        };
+        query.extend([("appid", self.api_key.clone()), ("units", "metric".to_string())]);
+        if let Some(lang) = &request.language {
+            query.push(("lang", lang.clone()));
+        }
+        query
+    }
 
-        if !status.is_success() {
-            return Err(anyhow!(
-                "OpenWeather current request failed with status {}: {}",
-                status,
-                truncate_body(&body),
-            ));
+    /// Endpoint for a classified date, or why the free API cannot answer it.
+    fn endpoint(&self, date: DateRequest, now: DateTime<Utc>) -> Result<String> {
+        match date {
+            DateRequest::Current => Ok(format!("{}/data/2.5/weather", self.base_url)),
+            DateRequest::Past(dt) => Err(history_unsupported(dt)),
+            DateRequest::Future(dt) => {
+                let max_forecast = now + chrono::Duration::days(5);
+                if dt > max_forecast {
+                    Err(WeatherError::UnsupportedDate(format!(
+                        "Requested date {} exceeds the 5-day forecast limit of free OpenWeather API.\n\
+                         Allowed range: now .. {}.",
+                        dt, max_forecast
+                    )))
+                } else {
+                    Ok(format!("{}/data/2.5/forecast", self.base_url))
+                }
+            }
         }
+    }
+
+    async fn fetch_current(&self, url: &str, request: &WeatherRequest) -> Result<WeatherResponse> {
+        let res = self.http.get(url, &self.query(request)).await?;
+        let res = check(res, "OpenWeather current", request)?;
 
         let parsed: OwCurrentResponse =
-            serde_json::from_str(&body).context("Failed to parse OpenWeather current JSON")?;
+            serde_json::from_str(&res.body).context("Failed to parse OpenWeather current JSON")?;
 
         let observation_time = DateTime::from_timestamp(parsed.dt, 0).unwrap_or_else(Utc::now);
 
@@ -58,69 +103,184 @@ impl OpenWeatherProvider {
         Ok(WeatherResponse {
             provider: "openweather".to_string(),
             location_name: parsed.name,
-            temperature_c: parsed.main.temp,
-            feels_like_c: parsed.main.feels_like,
+            temperature: Temperature::from_celsius(parsed.main.temp),
+            feels_like: Temperature::from_celsius(parsed.main.feels_like),
             condition,
             humidity_pct: parsed.main.humidity,
-            wind_speed_mps: parsed.wind.speed,
+            wind_speed: WindSpeed::from_mps(parsed.wind.speed),
+            wind_direction: parsed.wind.deg.map(WindDirection::from_degrees),
+            pressure: Pressure::from_hpa(parsed.main.pressure),
+            precipitation: Precipitation::from_mm(
+                parsed.rain.map(|r| r.one_hour).unwrap_or_default(),
+            ),
             observation_time,
         })
     }
 
-    async fn fetch_forecast(&self, address: &str, when: DateTime<Utc>) -> Result<WeatherResponse> {
-        let url = "https://api.openweathermap.org/data/2.5/forecast";
+    async fn fetch_forecast(
+        &self,
+        url: &str,
+        request: &WeatherRequest,
+        when: DateTime<Utc>,
+    ) -> Result<WeatherResponse> {
+        self.fetch_forecast_series(url, request).await?.snapshot_at(when).ok_or_else(|| {
+            WeatherError::Parse {
+                context: "Failed to parse OpenWeather forecast JSON".to_string(),
+                source: "the forecast contained no data".into(),
+            }
+        })
+    }
 
-        let res = self
-            .http
-            .get(url)
-            .query(&[("q", address), ("appid", self.api_key.as_str()), ("units", "metric")])
-            .send()
-            .await
-            .context("Failed to send request to OpenWeather (5-day forecast)")?;
+    /// The whole forecast: 5 days in 3-hour steps.
+    async fn fetch_forecast_series(
+        &self,
+        url: &str,
+        request: &WeatherRequest,
+    ) -> Result<WeatherSeries> {
+        let res = self.http.get(url, &self.query(request)).await?;
+        let res = check(res, "OpenWeather forecast", request)?;
 
-        let status = res.status();
-        let body = res.text().await.context("Failed to read OpenWeather forecast response body")?;
+        let parsed: OwForecastResponse =
+            serde_json::from_str(&res.body).context("Failed to parse OpenWeather forecast JSON")?;
 
-        if !status.is_success() {
-            return Err(anyhow!(
-                "OpenWeather forecast request failed with status {}: {}",
-                status,
-                truncate_body(&body),
-            ));
-        }
+        let points = parsed
+            .list
+            .into_iter()
+            .map(|entry| {
+                let condition = entry
+                    .weather
+                    .first()
+                    .map(|w| w.description.clone())
+                    .unwrap_or_else(|| "Unknown".to_string());
+
+                WeatherPoint {
+                    time: DateTime::from_timestamp(entry.dt, 0).unwrap_or_else(Utc::now),
+                    temperature: Temperature::from_celsius(entry.main.temp),
+                    feels_like: Temperature::from_celsius(entry.main.feels_like),
+                    condition,
+                    humidity_pct: entry.main.humidity,
+                    wind_speed: WindSpeed::from_mps(entry.wind.speed),
+                    wind_direction: entry.wind.deg.map(WindDirection::from_degrees),
+                    pressure: Pressure::from_hpa(entry.main.pressure),
+                    precipitation: Precipitation::from_mm(
+                        entry.rain.as_ref().map(|r| r.three_hours).unwrap_or_default(),
+                    ),
+                }
+            })
+            .collect();
 
-        let parsed: OwForecastResponse =
-            serde_json::from_str(&body).context("Failed to parse OpenWeather forecast JSON")?;
+        Ok(WeatherSeries {
+            provider: "openweather".to_string(),
+            location_name: format!("{}, {}", parsed.city.name, parsed.city.country),
+            points,
+        })
+    }
+}
 
-        let target_ts = when.timestamp();
+/// Builder for [`OpenWeatherProvider`], see [`OpenWeatherProvider::builder`].
+#[derive(Debug, Default)]
+pub struct OpenWeatherProviderBuilder {
+    api_key: Option<String>,
+    client: Option<Client>,
+    retry: Option<RetryPolicy>,
+    limiter: Option<RateLimiter>,
+    usage: Option<UsageLog>,
+    #[cfg(feature = "cassette")]
+    cassette: Option<super::cassette::Cassette>,
+    base_url: Option<String>,
+}
 
-        let entry = parsed
-            .list
-            .iter()
-            .min_by_key(|e| (e.dt - target_ts).abs())
-            .ok_or_else(|| anyhow!("OpenWeather forecast response contained no data"))?;
+impl OpenWeatherProviderBuilder {
+    pub fn api_key(mut self, api_key: impl Into<String>) -> Self {
+        self.api_key = Some(api_key.into());
+        self
+    }
 
-        let observation_time = DateTime::from_timestamp(entry.dt, 0).unwrap_or_else(Utc::now);
+    /// Use a pre-configured client (proxy, timeouts, TLS, ...) instead of a default one.
+    pub fn client(mut self, client: Client) -> Self {
+        self.client = Some(client);
+        self
+    }
 
-        let condition = entry
-            .weather
-            .first()
-            .map(|w| w.description.clone())
-            .unwrap_or_else(|| "Unknown".to_string());
+    pub fn retry_policy(mut self, retry: RetryPolicy) -> Self {
+        self.retry = Some(retry);
+        self
+    }
 
-        let location_name = format!("{}, {}", parsed.city.name, parsed.city.country);
+    /// Throttle requests to stay within the provider's quota.
+    pub fn rate_limiter(mut self, limiter: RateLimiter) -> Self {
+        self.limiter = Some(limiter);
+        self
+    }
 
-        Ok(WeatherResponse {
-            provider: "openweather".to_string(),
-            location_name,
-            temperature_c: entry.main.temp,
-            feels_like_c: entry.main.feels_like,
-            condition,
-            humidity_pct: entry.main.humidity,
-            wind_speed_mps: entry.wind.speed,
-            observation_time,
-        })
+    /// Record every outbound call for `weather usage`.
+    pub fn usage_log(mut self, usage: UsageLog) -> Self {
+        self.usage = Some(usage);
+        self
+    }
+
+    /// Record or replay HTTP traffic, see [`super::cassette`].
+    #[cfg(feature = "cassette")]
+    pub fn cassette(mut self, cassette: super::cassette::Cassette) -> Self {
+        self.cassette = Some(cassette);
+        self
+    }
+
+    /// Override the API root, e.g. to point at a mock server in tests.
+    /// Defaults to `https://api.openweathermap.org`.
+    pub fn base_url(mut self, base_url: impl Into<String>) -> Self {
+        self.base_url = Some(base_url.into());
+        self
+    }
+
+    pub fn build(self) -> Result<OpenWeatherProvider> {
+        let api_key =
+            self.api_key.ok_or(WeatherError::ProviderNotConfigured(ProviderId::OpenWeather))?;
+        let base_url = self.base_url.as_deref().unwrap_or(DEFAULT_BASE_URL).trim_end_matches('/');
+
+        let mut http =
+            HttpClient::new(self.client.unwrap_or_default(), self.retry.unwrap_or_default());
+        if let Some(limiter) = self.limiter {
+            http = http.with_rate_limiter(limiter);
+        }
+        if let Some(usage) = self.usage {
+            http = http.with_usage_log(usage, ProviderId::OpenWeather);
+        }
+        #[cfg(feature = "cassette")]
+        if let Some(cassette) = self.cassette {
+            http = http.with_cassette(cassette);
+        }
+
+        Ok(OpenWeatherProvider { api_key, http, base_url: base_url.to_string() })
+    }
+}
+
+/// OpenWeather answers 404 for addresses it cannot geocode.
+fn check(res: HttpResponse, endpoint: &str, request: &WeatherRequest) -> Result<HttpResponse> {
+    if res.status == StatusCode::NOT_FOUND {
+        return Err(WeatherError::LocationNotFound {
+            provider: ProviderId::OpenWeather,
+            address: request.location.to_string(),
+        });
     }
+
+    res.error_for_provider(ProviderId::OpenWeather, endpoint)
+}
+
+fn history_unsupported(dt: DateTime<Utc>) -> WeatherError {
+    WeatherError::UnsupportedDate(format!(
+        "Historical weather ({dt}) is not supported by free OpenWeather API.\n\
+         Only current weather and up to 5 days forecast are available."
+    ))
+}
+
+#[derive(Debug, Deserialize)]
+struct OwPlace {
+    name: String,
+    state: Option<String>,
+    country: String,
+    lat: f64,
+    lon: f64,
 }
 
 #[derive(Debug, Deserialize)]
@@ -128,6 +288,7 @@ struct OwMain {
     temp: f64,
     feels_like: f64,
     humidity: u8,
+    pressure: f64,
 }
 
 #[derive(Debug, Deserialize)]
@@ -138,6 +299,16 @@ struct OwWeather {
 #[derive(Debug, Deserialize)]
 struct OwWind {
     speed: f64,
+    #[serde(default)]
+    deg: Option<f64>,
+}
+
+#[derive(Debug, Deserialize)]
+struct OwRain {
+    #[serde(rename = "1h", default)]
+    one_hour: f64,
+    #[serde(rename = "3h", default)]
+    three_hours: f64,
 }
 
 #[derive(Debug, Deserialize)]
@@ -147,6 +318,7 @@ struct OwCurrentResponse {
     main: OwMain,
     weather: Vec<OwWeather>,
     wind: OwWind,
+    rain: Option<OwRain>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -161,6 +333,7 @@ struct OwForecastEntry {
     main: OwMain,
     weather: Vec<OwWeather>,
     wind: OwWind,
+    rain: Option<OwRain>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -169,37 +342,184 @@ struct OwForecastResponse {
     list: Vec<OwForecastEntry>,
 }
 
-#[async_trait]
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
 impl WeatherProvider for OpenWeatherProvider {
     async fn get_weather(&self, request: &WeatherRequest) -> Result<WeatherResponse> {
+        request.validate()?;
         let now = Utc::now();
         let date_req = classify_date(now, request.when);
+        let url = self.endpoint(date_req, now)?;
 
         match date_req {
-            DateRequest::Current => self.fetch_current(&request.address).await,
-            DateRequest::Past(dt) => Err(anyhow!(
-                "Historical weather ({}) is not supported by free OpenWeather API.\n\
-                     Only current weather and up to 5 days forecast are available.",
-                dt
-            )),
-            DateRequest::Future(dt) => {
-                let max_forecast = now + chrono::Duration::days(5);
-                if dt > max_forecast {
-                    Err(anyhow!(
-                        "Requested date {} exceeds the 5-day forecast limit of free OpenWeather API.\n\
-                         Allowed range: now .. {}.",
-                        dt,
-                        max_forecast
-                    ))
-                } else {
-                    self.fetch_forecast(&request.address, dt).await
-                }
-            }
+            DateRequest::Current => self.fetch_current(&url, request).await,
+            DateRequest::Future(dt) => self.fetch_forecast(&url, request, dt).await,
+            DateRequest::Past(dt) => Err(history_unsupported(dt)),
         }
     }
+
+    fn plan(&self, request: &WeatherRequest) -> Result<RequestPlan> {
+        request.validate()?;
+        let now = Utc::now();
+        let date_req = classify_date(now, request.when);
+        let url = self.endpoint(date_req, now)?;
+
+        Ok(RequestPlan::new(ProviderId::OpenWeather, date_req, url, &self.query(request), "appid"))
+    }
+
+    /// At most 5 days ahead, in 3-hour steps.
+    async fn get_forecast_series(
+        &self,
+        request: &WeatherRequest,
+        range: Range<DateTime<Utc>>,
+    ) -> Result<WeatherSeries> {
+        request.validate()?;
+        let url = format!("{}/data/2.5/forecast", self.base_url);
+
+        Ok(self.fetch_forecast_series(&url, request).await?.within(&range))
+    }
+
+    async fn geocode(&self, address: &str) -> Result<Vec<Place>> {
+        let url = format!("{}/geo/1.0/direct", self.base_url);
+        let limit = MAX_CANDIDATES.to_string();
+        let query = [("q", address), ("limit", limit.as_str()), ("appid", self.api_key.as_str())];
+
+        let res = self.http.get(&url, &query).await?;
+        let res = res.error_for_provider(ProviderId::OpenWeather, "OpenWeather geocoding")?;
+
+        let parsed: Vec<OwPlace> = serde_json::from_str(&res.body)
+            .context("Failed to parse OpenWeather geocoding JSON")?;
+
+        Ok(parsed
+            .into_iter()
+            .map(|p| Place {
+                name: p.name,
+                region: p.state,
+                country: p.country,
+                lat: p.lat,
+                lon: p.lon,
+            })
+            .collect())
+    }
 }
 
-fn truncate_body(body: &str) -> String {
-    const MAX: usize = 200;
-    if body.len() > MAX { format!("{}...", &body[..MAX]) } else { body.to_string() }
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::provider::http::testing::{json_ok, serve};
+
+    const CURRENT: &str = r#"{
+        "name": "Kyiv",
+        "dt": 1700000000,
+        "main": { "temp": 3.5, "feels_like": 0.2, "humidity": 81, "pressure": 1012 },
+        "weather": [{ "description": "light rain" }],
+        "wind": { "speed": 4.1, "deg": 320 },
+        "rain": { "1h": 0.6 }
+    }"#;
+
+    #[tokio::test]
+    async fn builder_targets_custom_base_url() {
+        let (url, requests) = serve(vec![json_ok(CURRENT)]).await;
+        let provider = OpenWeatherProvider::builder()
+            .api_key("secret")
+            .client(Client::new())
+            .retry_policy(RetryPolicy { max_attempts: 1, ..RetryPolicy::default() })
+            .base_url(url)
+            .build()
+            .unwrap();
+
+        let request = WeatherRequest::builder().address("Kyiv").build().unwrap();
+        let res = provider.get_weather(&request).await.unwrap();
+
+        let line = requests.recv().unwrap();
+        assert!(line.starts_with("GET /data/2.5/weather?"), "{line}");
+        assert!(line.contains("appid=secret"));
+
+        assert_eq!(res.location_name, "Kyiv");
+        assert_eq!(res.condition, "light rain");
+        assert_eq!(res.humidity_pct, 81);
+        assert_eq!(res.temperature.celsius(), 3.5);
+        assert_eq!(res.precipitation.mm(), 0.6);
+    }
+
+    #[test]
+    fn plan_picks_the_endpoint_and_masks_the_key() {
+        let provider = OpenWeatherProvider::new("0123456789abcdef".to_string());
+        let when = Utc::now() + chrono::Duration::days(1);
+        let request = WeatherRequest::builder().address("Kyiv").at(when).build().unwrap();
+
+        let plan = provider.plan(&request).unwrap();
+        assert_eq!(plan.date, DateRequest::Future(when));
+        assert_eq!(plan.url, "https://api.openweathermap.org/data/2.5/forecast");
+        assert!(plan.query.contains(&("appid".to_string(), "********cdef".to_string())));
+
+        let past = WeatherRequest { when: Some(Utc::now() - chrono::Duration::days(1)), ..request };
+        let err = provider.plan(&past).unwrap_err();
+        assert!(matches!(err, WeatherError::UnsupportedDate(_)));
+        assert!(err.to_string().contains("not supported"));
+    }
+
+    #[tokio::test]
+    async fn language_is_sent_as_lang() {
+        let (url, requests) = serve(vec![json_ok(CURRENT), json_ok(CURRENT)]).await;
+        let provider =
+            OpenWeatherProvider::builder().api_key("secret").base_url(url).build().unwrap();
+
+        let request = WeatherRequest::builder().address("Kyiv").language("uk").build().unwrap();
+        provider.get_weather(&request).await.unwrap();
+        assert!(requests.recv().unwrap().contains("&lang=uk"));
+
+        let request = WeatherRequest { language: None, ..request };
+        provider.get_weather(&request).await.unwrap();
+        assert!(!requests.recv().unwrap().contains("lang="));
+    }
+
+    #[tokio::test]
+    async fn past_dates_are_refused_without_a_request() {
+        let (url, requests) = serve(vec![json_ok(CURRENT)]).await;
+        let provider =
+            OpenWeatherProvider::builder().api_key("secret").base_url(url).build().unwrap();
+        let when = Utc::now() - chrono::Duration::days(1);
+        let request = WeatherRequest::builder().address("Kyiv").at(when).build().unwrap();
+
+        let err = provider.get_weather(&request).await.unwrap_err();
+        assert!(matches!(err, WeatherError::UnsupportedDate(_)));
+        assert!(requests.try_recv().is_err());
+    }
+
+    #[tokio::test]
+    async fn geocoding_lists_candidate_places() {
+        let body = r#"[
+            {"name":"Springfield","lat":39.8,"lon":-89.64,"country":"US","state":"Illinois"},
+            {"name":"Springfield","lat":37.21,"lon":-93.29,"country":"US","state":"Missouri"}
+        ]"#;
+        let (url, requests) = serve(vec![json_ok(body)]).await;
+        let provider =
+            OpenWeatherProvider::builder().api_key("secret").base_url(url).build().unwrap();
+
+        let places = provider.geocode("Springfield").await.unwrap();
+
+        let line = requests.recv().unwrap();
+        assert!(line.starts_with("GET /geo/1.0/direct?q=Springfield&limit=5&"), "{line}");
+        assert_eq!(places.len(), 2);
+        assert_eq!(places[1].to_string(), "Springfield, Missouri, US (37.21, -93.29)");
+    }
+
+    #[test]
+    fn coordinates_are_sent_as_lat_and_lon() {
+        let provider = OpenWeatherProvider::new("0123456789abcdef".to_string());
+        let request = WeatherRequest::builder().coordinates(50.45, 30.52).build().unwrap();
+
+        let plan = provider.plan(&request).unwrap();
+        let names: Vec<_> = plan.query.iter().map(|(name, _)| name.as_str()).collect();
+        assert_eq!(names, ["lat", "lon", "appid", "units"]);
+        assert_eq!(plan.query[0].1, "50.45");
+        assert_eq!(plan.query[1].1, "30.52");
+    }
+
+    #[test]
+    fn builder_requires_an_api_key() {
+        let err = OpenWeatherProvider::builder().build().unwrap_err();
+        assert!(matches!(err, WeatherError::ProviderNotConfigured(ProviderId::OpenWeather)));
+    }
 }
@@ -0,0 +1,169 @@
+//! Client-side rate limiting per provider.
+//!
+//! Each provider gets a token bucket: it holds up to `limit` tokens, so that many
+//! requests can go out at once, and refills at `limit` tokens per `window`. Every
+//! request takes one token; when the bucket is empty the request waits for the next
+//! token instead of burning through the quota. The buckets are kept in a small JSON
+//! file, locked while it is updated, so the limit also holds across concurrent and
+//! short-lived CLI invocations.
+
+use std::{collections::HashMap, path::PathBuf, time::Duration};
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use tracing::{info, warn};
+
+use super::ProviderId;
+#[cfg(not(target_arch = "wasm32"))]
+use crate::{Config, config::project_dirs, error::Result};
+use crate::{platform, state};
+
+/// Per-provider request budget, persisted to `state_file`.
+#[derive(Debug, Clone)]
+pub struct RateLimiter {
+    state_file: PathBuf,
+    provider: ProviderId,
+    /// Masked API key, when the provider has several and each has its own budget.
+    key: Option<String>,
+    limit: u32,
+    window: Duration,
+}
+
+/// Tokens left in a bucket as of `updated`.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+struct Bucket {
+    tokens: f64,
+    updated: DateTime<Utc>,
+}
+
+impl RateLimiter {
+    pub fn new(
+        state_file: impl Into<PathBuf>,
+        provider: ProviderId,
+        limit: u32,
+        window: Duration,
+    ) -> Self {
+        Self { state_file: state_file.into(), provider, key: None, limit: limit.max(1), window }
+    }
+
+    /// Limiter with the per-minute limit from config, persisted in the platform cache
+    /// directory. `None` if the provider is not limited.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn from_config(config: &Config, provider: ProviderId) -> Result<Option<Self>> {
+        let Some(limit) = config.rate_limit(provider) else {
+            return Ok(None);
+        };
+
+        Ok(Some(Self::new(
+            project_dirs()?.cache_dir().join("ratelimit.json"),
+            provider,
+            limit,
+            Duration::from_secs(60),
+        )))
+    }
+
+    /// Budget the calls made with one of several API keys separately from the others.
+    /// `key` is only an identity, so pass it masked.
+    pub fn for_key(mut self, key: impl Into<String>) -> Self {
+        self.key = Some(key.into());
+        self
+    }
+
+    /// Entry in the state file: the provider, and the key if there are several.
+    fn bucket(&self) -> String {
+        match &self.key {
+            Some(key) => format!("{} {key}", self.provider),
+            None => self.provider.as_str().to_string(),
+        }
+    }
+
+    /// Take a token, waiting for one to come back if the bucket is empty.
+    pub async fn acquire(&self) {
+        while let Some(wait) = self.try_acquire(Utc::now()) {
+            let wait_ms = wait.as_millis() as u64;
+            info!(bucket = %self.bucket(), wait_ms, "rate limit reached, waiting");
+            platform::sleep(wait).await;
+        }
+    }
+
+    /// Take a token if one is available at `now`; otherwise return how long until the
+    /// next one is. A state file that cannot be updated does not hold requests back.
+    fn try_acquire(&self, now: DateTime<Utc>) -> Option<Duration> {
+        let capacity = f64::from(self.limit);
+        let secs_per_token = self.window.as_secs_f64() / capacity;
+
+        let wait = state::update(&self.state_file, |buckets: &mut HashMap<String, Bucket>| {
+            let bucket =
+                buckets.entry(self.bucket()).or_insert(Bucket { tokens: capacity, updated: now });
+
+            let elapsed = (now - bucket.updated).to_std().unwrap_or_default();
+            bucket.tokens = (bucket.tokens + elapsed.as_secs_f64() / secs_per_token).min(capacity);
+            bucket.updated = bucket.updated.max(now);
+
+            if bucket.tokens >= 1.0 {
+                bucket.tokens -= 1.0;
+                return None;
+            }
+            let missing = 1.0 - bucket.tokens;
+            Some(Duration::from_secs_f64(missing * secs_per_token).max(Duration::from_millis(1)))
+        });
+
+        wait.unwrap_or_else(|err| {
+            warn!(error = %err, "failed to update rate limit state");
+            None
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bursts_up_to_the_limit_then_waits_for_the_refill() {
+        let dir = tempfile::tempdir().unwrap();
+        let limiter = RateLimiter::new(
+            dir.path().join("ratelimit.json"),
+            ProviderId::OpenWeather,
+            2,
+            Duration::from_secs(60),
+        );
+        let now = Utc::now();
+
+        assert_eq!(limiter.try_acquire(now), None);
+        assert_eq!(limiter.try_acquire(now), None);
+        // A token every 30 seconds: half of one is back after 15.
+        assert_eq!(
+            limiter.try_acquire(now + chrono::Duration::seconds(15)),
+            Some(Duration::from_secs(15))
+        );
+        assert_eq!(limiter.try_acquire(now + chrono::Duration::seconds(30)), None);
+
+        // An idle bucket fills up to the limit and no further.
+        let later = now + chrono::Duration::minutes(10);
+        assert_eq!(limiter.try_acquire(later), None);
+        assert_eq!(limiter.try_acquire(later), None);
+        assert!(limiter.try_acquire(later).is_some());
+    }
+
+    #[test]
+    fn limits_hold_across_instances_and_are_per_provider_and_key() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("ratelimit.json");
+        let now = Utc::now();
+
+        let first = RateLimiter::new(&file, ProviderId::OpenWeather, 1, Duration::from_secs(60));
+        assert_eq!(first.try_acquire(now), None);
+
+        let second = RateLimiter::new(&file, ProviderId::OpenWeather, 1, Duration::from_secs(60));
+        assert!(second.try_acquire(now).is_some());
+
+        let other = RateLimiter::new(&file, ProviderId::WeatherApi, 1, Duration::from_secs(60));
+        assert_eq!(other.try_acquire(now), None);
+
+        let other_key =
+            RateLimiter::new(&file, ProviderId::OpenWeather, 1, Duration::from_secs(60))
+                .for_key("********abcd");
+        assert_eq!(other_key.try_acquire(now), None);
+    }
+}
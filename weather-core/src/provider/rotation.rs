@@ -0,0 +1,237 @@
+//! Several API keys for one provider.
+//!
+//! Heavy users can pool the free tiers of several keys: [`KeyRotatingProvider`] holds
+//! one provider per key and, when a key is rejected (401/403) or rate limited (429),
+//! retries the request with the next one. With [`KeyRotation::RoundRobin`] every request
+//! also starts at the key after the one the previous request started at; the position
+//! is kept in a small JSON file so that it carries over between CLI runs. Each key has
+//! its own rate limit and usage records, see
+//! [`provider_from_config`](super::provider_from_config).
+
+use std::{
+    collections::HashMap,
+    ops::Range,
+    path::PathBuf,
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use tracing::warn;
+
+use super::{ProviderId, RequestPlan, WeatherProvider};
+#[cfg(not(target_arch = "wasm32"))]
+use crate::config::project_dirs;
+use crate::{
+    WeatherRequest, WeatherResponse, WeatherSeries,
+    alerts::AlertReport,
+    config::KeyRotation,
+    error::{Result, WeatherError},
+    geocode::Place,
+    state,
+};
+
+/// Provider wrapper that spreads requests over several API keys.
+#[derive(Debug)]
+pub struct KeyRotatingProvider {
+    /// One provider per key, in the configured order.
+    keys: Vec<Box<dyn WeatherProvider>>,
+    id: ProviderId,
+    rotation: KeyRotation,
+    /// Where the next round-robin request starts, without a state file.
+    next: AtomicUsize,
+    state_file: Option<PathBuf>,
+}
+
+impl KeyRotatingProvider {
+    /// Fails with [`WeatherError::ProviderNotConfigured`] if `keys` is empty.
+    pub fn new(
+        keys: Vec<Box<dyn WeatherProvider>>,
+        id: ProviderId,
+        rotation: KeyRotation,
+    ) -> Result<Self> {
+        if keys.is_empty() {
+            return Err(WeatherError::ProviderNotConfigured(id));
+        }
+        Ok(Self { keys, id, rotation, next: AtomicUsize::new(0), state_file: None })
+    }
+
+    /// Keep the round-robin position in `path`, shared with other processes.
+    pub fn with_state_file(mut self, path: impl Into<PathBuf>) -> Self {
+        self.state_file = Some(path.into());
+        self
+    }
+
+    /// `keys.json` in the platform cache directory.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn default_state_file() -> Result<PathBuf> {
+        Ok(project_dirs()?.cache_dir().join("keys.json"))
+    }
+
+    /// Index of the key to try first for the next request.
+    fn take_turn(&self) -> usize {
+        if self.rotation == KeyRotation::Failover {
+            return 0;
+        }
+
+        let Some(path) = &self.state_file else {
+            return self.next.fetch_add(1, Ordering::Relaxed) % self.keys.len();
+        };
+
+        let count = self.keys.len();
+        let turn = state::update(path, |turns: &mut HashMap<String, usize>| {
+            let turn = turns.get(self.id.as_str()).copied().unwrap_or(0) % count;
+            turns.insert(self.id.as_str().to_string(), (turn + 1) % count);
+            turn
+        });
+
+        turn.unwrap_or_else(|err| {
+            warn!(error = %err, "failed to update API key rotation state");
+            self.next.fetch_add(1, Ordering::Relaxed) % count
+        })
+    }
+
+    /// Make `call` with the key whose turn it is, then with the following keys for as
+    /// long as they are rejected or rate limited.
+    async fn rotate<'a, T, F>(&'a self, call: impl Fn(&'a dyn WeatherProvider) -> F) -> Result<T>
+    where
+        F: Future<Output = Result<T>>,
+    {
+        let start = self.take_turn();
+
+        for attempt in 0..self.keys.len() {
+            let index = (start + attempt) % self.keys.len();
+            match call(self.keys[index].as_ref()).await {
+                Err(err) if is_key_problem(&err) && attempt + 1 < self.keys.len() => {
+                    self.switching(index, &err);
+                }
+                result => return result,
+            }
+        }
+        unreachable!("the last key returns its result")
+    }
+
+    /// Log that the key at `index` failed and the next one will be tried.
+    fn switching(&self, index: usize, err: &WeatherError) {
+        warn!(provider = %self.id, key = index + 1, error = %err, "switching to the next API key");
+    }
+}
+
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+impl WeatherProvider for KeyRotatingProvider {
+    async fn get_weather(&self, request: &WeatherRequest) -> Result<WeatherResponse> {
+        self.rotate(|key| key.get_weather(request)).await
+    }
+
+    /// Planned with the first key, which is masked anyway.
+    fn plan(&self, request: &WeatherRequest) -> Result<RequestPlan> {
+        self.keys[0].plan(request)
+    }
+
+    async fn get_forecast_series(
+        &self,
+        request: &WeatherRequest,
+        range: Range<DateTime<Utc>>,
+    ) -> Result<WeatherSeries> {
+        self.rotate(|key| key.get_forecast_series(request, range.clone())).await
+    }
+
+    async fn alerts(&self, request: &WeatherRequest) -> Result<AlertReport> {
+        self.rotate(|key| key.alerts(request)).await
+    }
+
+    /// Keys are tried in order: a side lookup does not take a round-robin turn.
+    async fn geocode(&self, address: &str) -> Result<Vec<Place>> {
+        for (index, key) in self.keys.iter().enumerate() {
+            match key.geocode(address).await {
+                Err(err) if is_key_problem(&err) && index + 1 < self.keys.len() => {
+                    self.switching(index, &err);
+                }
+                result => return result,
+            }
+        }
+        unreachable!("the last key returns its result")
+    }
+}
+
+/// Failures another key may not have.
+fn is_key_problem(err: &WeatherError) -> bool {
+    matches!(err, WeatherError::Unauthorized { .. } | WeatherError::RateLimited { .. })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::provider::mock::{self, MockProvider};
+
+    fn request() -> WeatherRequest {
+        WeatherRequest::builder().address("Kyiv").build().unwrap()
+    }
+
+    fn rate_limited() -> WeatherError {
+        WeatherError::RateLimited { provider: ProviderId::OpenWeather, retry_after: None }
+    }
+
+    fn rotating(keys: &[MockProvider], rotation: KeyRotation) -> KeyRotatingProvider {
+        let keys = keys.iter().map(|key| Box::new(key.clone()) as Box<dyn WeatherProvider>);
+        KeyRotatingProvider::new(keys.collect(), ProviderId::OpenWeather, rotation).unwrap()
+    }
+
+    #[tokio::test]
+    async fn round_robin_takes_turns_across_runs() {
+        let dir = tempfile::tempdir().unwrap();
+        let state = dir.path().join("keys.json");
+        let keys = [
+            MockProvider::new().respond(mock::response("first")),
+            MockProvider::new().respond(mock::response("second")),
+        ];
+
+        // Each "run" gets a fresh provider, as separate CLI invocations do.
+        for expected in ["first", "second"] {
+            let provider = rotating(&keys, KeyRotation::RoundRobin).with_state_file(state.clone());
+            let response = provider.get_weather(&request()).await.unwrap();
+            assert_eq!(response.location_name, expected);
+        }
+    }
+
+    #[test]
+    fn at_least_one_key_is_needed() {
+        let err =
+            KeyRotatingProvider::new(Vec::new(), ProviderId::WeatherApi, KeyRotation::Failover)
+                .unwrap_err();
+        assert!(matches!(err, WeatherError::ProviderNotConfigured(ProviderId::WeatherApi)));
+    }
+
+    #[tokio::test]
+    async fn rejected_keys_fall_over_to_the_next() {
+        let keys = [
+            MockProvider::new().fail(rate_limited()).fail(rate_limited()),
+            MockProvider::new().respond(mock::response("Kyiv")).fail(rate_limited()),
+        ];
+        let provider = rotating(&keys, KeyRotation::Failover);
+
+        assert_eq!(provider.get_weather(&request()).await.unwrap().location_name, "Kyiv");
+
+        // With every key exhausted, the last error is returned.
+        let err = provider.get_weather(&request()).await.unwrap_err();
+        assert!(matches!(err, WeatherError::RateLimited { .. }));
+        assert_eq!((keys[0].calls().len(), keys[1].calls().len()), (2, 2));
+    }
+
+    #[tokio::test]
+    async fn other_errors_are_not_retried() {
+        let keys = [
+            MockProvider::new().fail(WeatherError::LocationNotFound {
+                provider: ProviderId::OpenWeather,
+                address: "Nowhere".to_string(),
+            }),
+            MockProvider::new(),
+        ];
+        let provider = rotating(&keys, KeyRotation::Failover);
+
+        let err = provider.get_weather(&request()).await.unwrap_err();
+        assert!(matches!(err, WeatherError::LocationNotFound { .. }));
+        assert!(keys[1].calls().is_empty());
+    }
+}
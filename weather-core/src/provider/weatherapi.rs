@@ -1,157 +1,336 @@
-use anyhow::{Context, Result};
+use std::ops::Range;
+
 use async_trait::async_trait;
 use chrono::{DateTime, Timelike, Utc};
 use reqwest::Client;
 use serde::Deserialize;
 
 use crate::{
-    model::{WeatherRequest, WeatherResponse},
+    alerts::{AlertReport, WeatherAlert},
+    error::{Context, Result, WeatherError},
+    geocode::{MAX_CANDIDATES, Place},
+    model::{
+        Precipitation, Pressure, Temperature, WeatherRequest, WeatherResponse, WeatherSeries,
+        WindDirection, WindSpeed,
+    },
     provider::{DateRequest, classify_date},
 };
 
-use super::WeatherProvider;
+use super::{
+    ProviderId, RequestPlan, WeatherProvider,
+    http::{HttpClient, HttpResponse, RetryPolicy},
+    ratelimit::RateLimiter,
+};
+use crate::usage::UsageLog;
+
+const DEFAULT_BASE_URL: &str = "http://api.weatherapi.com/v1";
 
 #[derive(Debug, Clone)]
 pub struct WeatherApiProvider {
     api_key: String,
-    http: Client,
+    http: HttpClient,
+    base_url: String,
 }
 
 impl WeatherApiProvider {
+    /// Provider with a default HTTP client and the production API.
     pub fn new(api_key: String) -> Self {
-        Self { api_key, http: Client::new() }
+        Self { api_key, http: HttpClient::default(), base_url: DEFAULT_BASE_URL.to_string() }
     }
 
-    async fn fetch_current(&self, request: &WeatherRequest) -> Result<WeatherResponse> {
-        let url = "http://api.weatherapi.com/v1/current.json";
-
-        let res = self
-            .http
-            .get(url)
-            .query(&[("key", self.api_key.as_str()), ("q", request.address.as_str())])
-            .send()
-            .await
-            .context("Failed to send request to WeatherAPI.com (current)")?;
+    /// ```no_run
+    /// # use weather_core::provider::weatherapi::WeatherApiProvider;
+    /// let provider = WeatherApiProvider::builder()
+    ///     .api_key("KEY")
+    ///     .client(reqwest::Client::new())
+    ///     .base_url("http://localhost:8080/v1")
+    ///     .build()?;
+    /// # Ok::<(), weather_core::WeatherError>(())
+    /// ```
+    pub fn builder() -> WeatherApiProviderBuilder {
+        WeatherApiProviderBuilder::default()
+    }
 
-        let status = res.status();
-        let body = res.text().await.context("Failed to read WeatherAPI current response body")?;
+    /// Query parameters shared by all endpoints.
+    ///
+    /// `q` takes addresses and `lat,lon` pairs alike.
+    fn query(&self, request: &WeatherRequest) -> Vec<(&'static str, String)> {
+        let mut query = vec![("key", self.api_key.clone()), ("q", request.location.to_string())];
+        if let Some(lang) = &request.language {
+            query.push(("lang", lang.clone()));
+        }
+        query
+    }
 
-        if !status.is_success() {
-            return Err(anyhow::anyhow!(
-                "WeatherAPI current request failed with status {}: {}",
-                status,
-                truncate_body(&body),
-            ));
+    /// Endpoint for a classified date: current, forecast or history.
+    fn endpoint(&self, date: DateRequest) -> String {
+        match date {
+            DateRequest::Current => format!("{}/current.json", self.base_url),
+            DateRequest::Future(_) => format!("{}/forecast.json", self.base_url),
+            DateRequest::Past(_) => format!("{}/history.json", self.base_url),
         }
+    }
+
+    async fn fetch_current(&self, url: &str, request: &WeatherRequest) -> Result<WeatherResponse> {
+        let res = self.http.get(url, &self.query(request)).await?;
+        let res = check(res, "WeatherAPI current", request)?;
 
         let parsed: WaResponse =
-            serde_json::from_str(&body).context("Failed to parse WeatherAPI current JSON")?;
-
-        let observation_time = parsed
-            .current
-            .last_updated_epoch
-            .or(parsed.location.localtime_epoch)
-            .and_then(|ts| DateTime::from_timestamp(ts, 0))
-            .unwrap_or_else(Utc::now);
-
-        let location_name = format!("{}, {}", parsed.location.name, parsed.location.country);
-        let wind_speed_mps = parsed.current.wind_kph / 3.6;
-
-        Ok(WeatherResponse {
-            provider: "weatherapi".to_string(),
-            location_name,
-            temperature_c: parsed.current.temp_c,
-            feels_like_c: parsed.current.feelslike_c,
-            condition: parsed.current.condition.text,
-            humidity_pct: parsed.current.humidity,
-            wind_speed_mps,
-            observation_time,
-        })
+            serde_json::from_str(&res.body).context("Failed to parse WeatherAPI current JSON")?;
+
+        Ok(current_response(parsed.location, parsed.current))
     }
 
     async fn fetch_at(
         &self,
+        url: &str,
         request: &WeatherRequest,
         when: DateTime<Utc>,
         is_forecast: bool,
     ) -> Result<WeatherResponse> {
-        let base_url = if is_forecast {
-            "http://api.weatherapi.com/v1/forecast.json"
-        } else {
-            "http://api.weatherapi.com/v1/history.json"
-        };
+        let [unixdt_param, hour_param] = time_params(when);
+        let mut query = self.query(request);
+        query.extend([("unixdt", unixdt_param), ("hour", hour_param)]);
 
-        let unixdt = when.timestamp();
-        let hour = when.hour(); // 0–23
-
-        let res = self
-            .http
-            .get(base_url)
-            .query(&[
-                ("key", self.api_key.as_str()),
-                ("q", request.address.as_str()),
-                ("unixdt", &unixdt.to_string()),
-                ("hour", &hour.to_string()),
-            ])
-            .send()
-            .await
-            .with_context(|| {
-                format!(
-                    "Failed to send request to WeatherAPI.com ({})",
-                    if is_forecast { "forecast" } else { "history" }
-                )
-            })?;
-
-        let status = res.status();
-        let body =
-            res.text().await.context("Failed to read WeatherAPI forecast/history response body")?;
-
-        if !status.is_success() {
-            return Err(anyhow::anyhow!(
-                "WeatherAPI {} request failed with status {}: {}",
-                if is_forecast { "forecast" } else { "history" },
-                status,
-                truncate_body(&body),
-            ));
-        }
+        let kind = if is_forecast { "forecast" } else { "history" };
+        let res = self.http.get(url, &query).await?;
+        let res = check(res, &format!("WeatherAPI {kind}"), request)?;
 
-        let parsed: WaForecastResponse = serde_json::from_str(&body).with_context(|| {
-            format!(
-                "Failed to parse WeatherAPI {} JSON",
-                if is_forecast { "forecast" } else { "history" }
-            )
-        })?;
-
-        let location_name = format!("{}, {}", parsed.location.name, parsed.location.country);
-
-        let target_ts = unixdt;
-
-        let day =
-            parsed.forecast.forecastday.first().ok_or_else(|| {
-                anyhow::anyhow!("WeatherAPI response contained no forecastday data")
-            })?;
-
-        let hour_entry = day
-            .hour
-            .iter()
-            .min_by_key(|h| (h.time_epoch - target_ts).abs())
-            .ok_or_else(|| anyhow::anyhow!("WeatherAPI response contained no hourly data"))?;
-
-        let observation_time =
-            DateTime::from_timestamp(hour_entry.time_epoch, 0).unwrap_or_else(Utc::now);
-        let wind_speed_mps = hour_entry.wind_kph / 3.6;
-
-        Ok(WeatherResponse {
-            provider: "weatherapi".to_string(),
-            location_name,
-            temperature_c: hour_entry.temp_c,
-            feels_like_c: hour_entry.feelslike_c,
-            condition: hour_entry.condition.text.clone(),
-            humidity_pct: hour_entry.humidity,
-            wind_speed_mps,
-            observation_time,
+        let parsed: WaForecastResponse = serde_json::from_str(&res.body)
+            .with_context(|| format!("Failed to parse WeatherAPI {kind} JSON"))?;
+
+        forecast_series(&parsed).snapshot_at(when).ok_or_else(|| WeatherError::Parse {
+            context: format!("Failed to parse WeatherAPI {kind} JSON"),
+            source: "the response contained no hourly data".into(),
         })
     }
+
+    /// Every hour of the next `days` days, today included.
+    async fn fetch_forecast_series(
+        &self,
+        request: &WeatherRequest,
+        days: i64,
+    ) -> Result<WeatherSeries> {
+        let url = self.endpoint(DateRequest::Future(Utc::now()));
+        let mut query = self.query(request);
+        query.push(("days", days.to_string()));
+
+        let res = self.http.get(&url, &query).await?;
+        let res = check(res, "WeatherAPI forecast", request)?;
+
+        let parsed: WaForecastResponse =
+            serde_json::from_str(&res.body).context("Failed to parse WeatherAPI forecast JSON")?;
+
+        Ok(forecast_series(&parsed))
+    }
+
+    /// Alerts only come with the forecast, which also carries the current weather.
+    async fn fetch_alerts(&self, request: &WeatherRequest) -> Result<AlertReport> {
+        let url = self.endpoint(DateRequest::Future(Utc::now()));
+        let mut query = self.query(request);
+        query.extend([("days", "1".to_string()), ("alerts", "yes".to_string())]);
+
+        let res = self.http.get(&url, &query).await?;
+        let res = check(res, "WeatherAPI alerts", request)?;
+
+        let parsed: WaAlertsResponse =
+            serde_json::from_str(&res.body).context("Failed to parse WeatherAPI alerts JSON")?;
+
+        Ok(AlertReport {
+            current: current_response(parsed.location, parsed.current),
+            alerts: parsed.alerts.alert.into_iter().map(alert).collect(),
+        })
+    }
+}
+
+/// Every hour of every day in a forecast or history response.
+fn forecast_series(parsed: &WaForecastResponse) -> WeatherSeries {
+    let location_name = format!("{}, {}", parsed.location.name, parsed.location.country);
+    let points = parsed.forecast.forecastday.iter().flat_map(|day| &day.hour);
+
+    WeatherSeries {
+        provider: "weatherapi".to_string(),
+        points: points.map(|hour| hour_response(&location_name, hour).into()).collect(),
+        location_name,
+    }
+}
+
+fn current_response(location: WaLocation, current: WaCurrent) -> WeatherResponse {
+    let observation_time = current
+        .last_updated_epoch
+        .or(location.localtime_epoch)
+        .and_then(|ts| DateTime::from_timestamp(ts, 0))
+        .unwrap_or_else(Utc::now);
+
+    WeatherResponse {
+        provider: "weatherapi".to_string(),
+        location_name: format!("{}, {}", location.name, location.country),
+        temperature: Temperature::from_celsius(current.temp_c),
+        feels_like: Temperature::from_celsius(current.feelslike_c),
+        condition: current.condition.text,
+        humidity_pct: current.humidity,
+        wind_speed: WindSpeed::from_kmh(current.wind_kph),
+        wind_direction: current.wind_degree.map(WindDirection::from_degrees),
+        pressure: Pressure::from_hpa(current.pressure_mb),
+        precipitation: Precipitation::from_mm(current.precip_mm),
+        observation_time,
+    }
+}
+
+fn alert(alert: WaAlert) -> WeatherAlert {
+    let time = |value: &str| DateTime::parse_from_rfc3339(value).ok().map(|t| t.to_utc());
+    let non_empty = |value: String| Some(value).filter(|v| !v.is_empty());
+
+    WeatherAlert {
+        // Alerts have no id; the same event starting at the same time is the same alert.
+        id: format!("weatherapi:{}|{}", alert.event, alert.effective),
+        starts: time(&alert.effective),
+        ends: time(&alert.expires),
+        severity: non_empty(alert.severity),
+        headline: non_empty(alert.headline).unwrap_or_else(|| alert.event.clone()),
+        event: alert.event,
+    }
+}
+
+fn hour_response(location_name: &str, hour: &WaForecastHour) -> WeatherResponse {
+    WeatherResponse {
+        provider: "weatherapi".to_string(),
+        location_name: location_name.to_string(),
+        temperature: Temperature::from_celsius(hour.temp_c),
+        feels_like: Temperature::from_celsius(hour.feelslike_c),
+        condition: hour.condition.text.clone(),
+        humidity_pct: hour.humidity,
+        wind_speed: WindSpeed::from_kmh(hour.wind_kph),
+        wind_direction: hour.wind_degree.map(WindDirection::from_degrees),
+        pressure: Pressure::from_hpa(hour.pressure_mb),
+        precipitation: Precipitation::from_mm(hour.precip_mm),
+        observation_time: DateTime::from_timestamp(hour.time_epoch, 0).unwrap_or_else(Utc::now),
+    }
+}
+
+/// Builder for [`WeatherApiProvider`], see [`WeatherApiProvider::builder`].
+#[derive(Debug, Default)]
+pub struct WeatherApiProviderBuilder {
+    api_key: Option<String>,
+    client: Option<Client>,
+    retry: Option<RetryPolicy>,
+    limiter: Option<RateLimiter>,
+    usage: Option<UsageLog>,
+    #[cfg(feature = "cassette")]
+    cassette: Option<super::cassette::Cassette>,
+    base_url: Option<String>,
+}
+
+impl WeatherApiProviderBuilder {
+    pub fn api_key(mut self, api_key: impl Into<String>) -> Self {
+        self.api_key = Some(api_key.into());
+        self
+    }
+
+    /// Use a pre-configured client (proxy, timeouts, TLS, ...) instead of a default one.
+    pub fn client(mut self, client: Client) -> Self {
+        self.client = Some(client);
+        self
+    }
+
+    pub fn retry_policy(mut self, retry: RetryPolicy) -> Self {
+        self.retry = Some(retry);
+        self
+    }
+
+    /// Throttle requests to stay within the provider's quota.
+    pub fn rate_limiter(mut self, limiter: RateLimiter) -> Self {
+        self.limiter = Some(limiter);
+        self
+    }
+
+    /// Record every outbound call for `weather usage`.
+    pub fn usage_log(mut self, usage: UsageLog) -> Self {
+        self.usage = Some(usage);
+        self
+    }
+
+    /// Record or replay HTTP traffic, see [`super::cassette`].
+    #[cfg(feature = "cassette")]
+    pub fn cassette(mut self, cassette: super::cassette::Cassette) -> Self {
+        self.cassette = Some(cassette);
+        self
+    }
+
+    /// Override the API root, e.g. to point at a mock server in tests.
+    /// Defaults to `http://api.weatherapi.com/v1`.
+    pub fn base_url(mut self, base_url: impl Into<String>) -> Self {
+        self.base_url = Some(base_url.into());
+        self
+    }
+
+    pub fn build(self) -> Result<WeatherApiProvider> {
+        let api_key =
+            self.api_key.ok_or(WeatherError::ProviderNotConfigured(ProviderId::WeatherApi))?;
+        let base_url = self.base_url.as_deref().unwrap_or(DEFAULT_BASE_URL).trim_end_matches('/');
+
+        let mut http =
+            HttpClient::new(self.client.unwrap_or_default(), self.retry.unwrap_or_default());
+        if let Some(limiter) = self.limiter {
+            http = http.with_rate_limiter(limiter);
+        }
+        if let Some(usage) = self.usage {
+            http = http.with_usage_log(usage, ProviderId::WeatherApi);
+        }
+        #[cfg(feature = "cassette")]
+        if let Some(cassette) = self.cassette {
+            http = http.with_cassette(cassette);
+        }
+
+        Ok(WeatherApiProvider { api_key, http, base_url: base_url.to_string() })
+    }
+}
+
+/// Error codes from the body of a failed WeatherAPI request.
+const NO_MATCHING_LOCATION: u32 = 1006;
+const QUOTA_EXCEEDED: u32 = 2007;
+
+/// WeatherAPI reports unknown locations and spent quotas as error codes in the body.
+fn check(res: HttpResponse, endpoint: &str, request: &WeatherRequest) -> Result<HttpResponse> {
+    if !res.status.is_success() {
+        let code = serde_json::from_str::<WaErrorResponse>(&res.body).map(|e| e.error.code);
+        let provider = ProviderId::WeatherApi;
+
+        match code {
+            Ok(NO_MATCHING_LOCATION) => {
+                return Err(WeatherError::LocationNotFound {
+                    provider,
+                    address: request.location.to_string(),
+                });
+            }
+            Ok(QUOTA_EXCEEDED) => {
+                return Err(WeatherError::RateLimited { provider, retry_after: None });
+            }
+            _ => {}
+        }
+    }
+
+    res.error_for_provider(ProviderId::WeatherApi, endpoint)
+}
+
+#[derive(Debug, Deserialize)]
+struct WaError {
+    code: u32,
+}
+
+#[derive(Debug, Deserialize)]
+struct WaPlace {
+    name: String,
+    #[serde(default)]
+    region: String,
+    country: String,
+    lat: f64,
+    lon: f64,
+}
+
+#[derive(Debug, Deserialize)]
+struct WaErrorResponse {
+    error: WaError,
 }
 
 #[derive(Debug, Deserialize)]
@@ -172,6 +351,10 @@ struct WaCurrent {
     feelslike_c: f64,
     humidity: u8,
     wind_kph: f64,
+    #[serde(default)]
+    wind_degree: Option<f64>,
+    pressure_mb: f64,
+    precip_mm: f64,
     condition: WaCondition,
     last_updated_epoch: Option<i64>,
 }
@@ -189,6 +372,10 @@ struct WaForecastHour {
     feelslike_c: f64,
     humidity: u8,
     wind_kph: f64,
+    #[serde(default)]
+    wind_degree: Option<f64>,
+    pressure_mb: f64,
+    precip_mm: f64,
     condition: WaCondition,
 }
 
@@ -208,27 +395,303 @@ struct WaForecastResponse {
     forecast: WaForecast,
 }
 
-#[async_trait]
+#[derive(Debug, Deserialize)]
+struct WaAlert {
+    #[serde(default)]
+    headline: String,
+    #[serde(default)]
+    severity: String,
+    #[serde(default)]
+    event: String,
+    #[serde(default)]
+    effective: String,
+    #[serde(default)]
+    expires: String,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct WaAlerts {
+    #[serde(default)]
+    alert: Vec<WaAlert>,
+}
+
+#[derive(Debug, Deserialize)]
+struct WaAlertsResponse {
+    location: WaLocation,
+    current: WaCurrent,
+    #[serde(default)]
+    alerts: WaAlerts,
+}
+
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
 impl WeatherProvider for WeatherApiProvider {
     async fn get_weather(&self, request: &WeatherRequest) -> Result<WeatherResponse> {
+        request.validate()?;
         let now = Utc::now();
         let date_req = classify_date(now, request.when);
+        let url = self.endpoint(date_req);
 
         match date_req {
-            DateRequest::Current => self.fetch_current(request).await,
-            DateRequest::Future(dt) => {
-                // future → forecast.json
-                self.fetch_at(request, dt, true).await
-            }
-            DateRequest::Past(dt) => {
-                // past → history.json
-                self.fetch_at(request, dt, false).await
-            }
+            DateRequest::Current => self.fetch_current(&url, request).await,
+            DateRequest::Future(dt) => self.fetch_at(&url, request, dt, true).await,
+            DateRequest::Past(dt) => self.fetch_at(&url, request, dt, false).await,
+        }
+    }
+
+    fn plan(&self, request: &WeatherRequest) -> Result<RequestPlan> {
+        request.validate()?;
+        let date_req = classify_date(Utc::now(), request.when);
+        let url = self.endpoint(date_req);
+
+        let mut query = self.query(request);
+        if let DateRequest::Future(dt) | DateRequest::Past(dt) = date_req {
+            let [unixdt, hour] = time_params(dt);
+            query.extend([("unixdt", unixdt), ("hour", hour)]);
         }
+
+        Ok(RequestPlan::new(ProviderId::WeatherApi, date_req, url, &query, "key"))
+    }
+
+    /// At most 3 days ahead (the free plan), today included, in 1-hour steps.
+    async fn get_forecast_series(
+        &self,
+        request: &WeatherRequest,
+        range: Range<DateTime<Utc>>,
+    ) -> Result<WeatherSeries> {
+        request.validate()?;
+        // Only as many days as the range reaches into.
+        let days = (range.end.date_naive() - Utc::now().date_naive()).num_days() + 1;
+        let max_days = ProviderId::WeatherApi.forecast_days().into();
+        let series = self.fetch_forecast_series(request, days.clamp(1, max_days)).await?;
+
+        Ok(series.within(&range))
+    }
+
+    async fn alerts(&self, request: &WeatherRequest) -> Result<AlertReport> {
+        request.validate()?;
+        self.fetch_alerts(request).await
+    }
+
+    async fn geocode(&self, address: &str) -> Result<Vec<Place>> {
+        let url = format!("{}/search.json", self.base_url);
+        let query = [("key", self.api_key.as_str()), ("q", address)];
+
+        let res = self.http.get(&url, &query).await?;
+        let res = res.error_for_provider(ProviderId::WeatherApi, "WeatherAPI search")?;
+
+        let parsed: Vec<WaPlace> =
+            serde_json::from_str(&res.body).context("Failed to parse WeatherAPI search JSON")?;
+
+        Ok(parsed
+            .into_iter()
+            .take(MAX_CANDIDATES)
+            .map(|p| Place {
+                name: p.name,
+                region: Some(p.region).filter(|r| !r.is_empty()),
+                country: p.country,
+                lat: p.lat,
+                lon: p.lon,
+            })
+            .collect())
     }
 }
 
-fn truncate_body(body: &str) -> String {
-    const MAX: usize = 200;
-    if body.len() > MAX { format!("{}...", &body[..MAX]) } else { body.to_string() }
+/// `unixdt` and `hour` (0-23) parameters of the forecast and history endpoints.
+fn time_params(when: DateTime<Utc>) -> [String; 2] {
+    [when.timestamp().to_string(), when.hour().to_string()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::provider::http::testing::{json_ok, serve};
+
+    const CURRENT: &str = r#"{
+        "location": { "name": "Lviv", "country": "Ukraine", "localtime_epoch": 1700000000 },
+        "current": {
+            "temp_c": 5.0, "feelslike_c": 2.5, "humidity": 70, "wind_kph": 18.0, "wind_degree": 200,
+            "pressure_mb": 1009.0, "precip_mm": 0.0,
+            "condition": { "text": "Overcast" }, "last_updated_epoch": 1700000000
+        }
+    }"#;
+
+    #[tokio::test]
+    async fn builder_targets_custom_base_url() {
+        let (url, requests) = serve(vec![json_ok(CURRENT)]).await;
+        let provider = WeatherApiProvider::builder()
+            .api_key("secret")
+            .client(Client::new())
+            .base_url(format!("{url}v1/"))
+            .build()
+            .unwrap();
+
+        let request = WeatherRequest::builder().address("Lviv").build().unwrap();
+        let res = provider.get_weather(&request).await.unwrap();
+
+        let line = requests.recv().unwrap();
+        assert!(line.starts_with("GET /v1/current.json?"), "{line}");
+        assert!(line.contains("key=secret"));
+
+        assert_eq!(res.location_name, "Lviv, Ukraine");
+        assert_eq!(res.condition, "Overcast");
+        assert_eq!(res.wind_speed.kmh(), 18.0);
+        assert_eq!(res.wind_direction.map(|d| d.compass()), Some("S"));
+    }
+
+    #[tokio::test]
+    async fn language_is_sent_as_lang() {
+        let (url, requests) = serve(vec![json_ok(CURRENT), json_ok(CURRENT)]).await;
+        let provider =
+            WeatherApiProvider::builder().api_key("secret").base_url(url).build().unwrap();
+
+        let request = WeatherRequest::builder().address("Lviv").language("uk").build().unwrap();
+        provider.get_weather(&request).await.unwrap();
+        assert!(requests.recv().unwrap().contains("&lang=uk"));
+
+        let request = WeatherRequest { language: None, ..request };
+        provider.get_weather(&request).await.unwrap();
+        assert!(!requests.recv().unwrap().contains("lang="));
+    }
+
+    #[tokio::test]
+    async fn error_codes_are_classified() {
+        let error = |status: &str, code: u32| {
+            let body = format!(r#"{{"error":{{"code":{code},"message":"..."}}}}"#);
+            format!(
+                "HTTP/1.1 {status}\r\nConnection: close\r\nContent-Length: {}\r\n\r\n{body}",
+                body.len()
+            )
+        };
+        let (url, _) = serve(vec![
+            error("400 Bad Request", NO_MATCHING_LOCATION),
+            error("403 Forbidden", QUOTA_EXCEEDED),
+            error("403 Forbidden", 2006),
+        ])
+        .await;
+        let provider =
+            WeatherApiProvider::builder().api_key("secret").base_url(url).build().unwrap();
+        let request = WeatherRequest::builder().address("Atlantis").build().unwrap();
+
+        let err = provider.get_weather(&request).await.unwrap_err();
+        assert!(
+            matches!(err, WeatherError::LocationNotFound { ref address, .. } if address == "Atlantis")
+        );
+
+        let err = provider.get_weather(&request).await.unwrap_err();
+        assert!(matches!(err, WeatherError::RateLimited { retry_after: None, .. }));
+
+        let err = provider.get_weather(&request).await.unwrap_err();
+        assert!(matches!(err, WeatherError::Unauthorized { .. }));
+    }
+
+    #[tokio::test]
+    async fn search_lists_candidate_places() {
+        let body = r#"[
+            {"id":1,"name":"Springfield","region":"Illinois","country":"United States of America","lat":39.8,"lon":-89.64},
+            {"id":2,"name":"Springfield","region":"","country":"Jamaica","lat":18.03,"lon":-77.52}
+        ]"#;
+        let (url, requests) = serve(vec![json_ok(body)]).await;
+        let provider =
+            WeatherApiProvider::builder().api_key("secret").base_url(url).build().unwrap();
+
+        let places = provider.geocode("Springfield").await.unwrap();
+
+        assert!(requests.recv().unwrap().starts_with("GET /search.json?"));
+        assert_eq!(places.len(), 2);
+        assert_eq!(places[0].region.as_deref(), Some("Illinois"));
+        assert_eq!(places[1].region, None);
+        assert_eq!(places[1].location(), crate::Location::coordinates(18.03, -77.52));
+    }
+
+    #[tokio::test]
+    async fn forecast_series_covers_the_range() {
+        let hour = Utc::now().timestamp() / 3600 * 3600;
+        let hours: Vec<String> = (-1..5)
+            .map(|i| {
+                format!(
+                    r#"{{ "time_epoch": {}, "temp_c": {i}.0, "feelslike_c": 0.0, "humidity": 80,
+                        "wind_kph": 10.0, "pressure_mb": 1012.0, "precip_mm": 0.0,
+                        "condition": {{ "text": "Cloudy" }} }}"#,
+                    hour + i * 3600
+                )
+            })
+            .collect();
+        let body = format!(
+            r#"{{ "location": {{ "name": "Lviv", "country": "Ukraine" }},
+                "forecast": {{ "forecastday": [{{ "hour": [{}] }}] }} }}"#,
+            hours.join(",")
+        );
+        let (url, requests) = serve(vec![json_ok(&body)]).await;
+        let provider =
+            WeatherApiProvider::builder().api_key("secret").base_url(url).build().unwrap();
+        let request = WeatherRequest::builder().address("Lviv").build().unwrap();
+
+        // Half an hour into the current hour, and further ahead than the free plan goes.
+        let start = DateTime::from_timestamp(hour + 1800, 0).unwrap();
+        let range = start..start + chrono::Duration::days(10);
+        let series = provider.get_forecast_series(&request, range).await.unwrap();
+
+        assert!(requests.recv().unwrap().contains("days=3"));
+        assert_eq!(series.location_name, "Lviv, Ukraine");
+        let temperatures: Vec<f64> =
+            series.points.iter().map(|point| point.temperature.celsius()).collect();
+        assert_eq!(temperatures, [1.0, 2.0, 3.0, 4.0]);
+    }
+
+    #[tokio::test]
+    async fn alerts_come_with_the_current_weather() {
+        let body = r#"{
+            "location": { "name": "Miami", "country": "USA" },
+            "current": {
+                "temp_c": 27.0, "feelslike_c": 31.0, "humidity": 88, "wind_kph": 90.0,
+                "pressure_mb": 990.0, "precip_mm": 12.0, "condition": { "text": "Heavy rain" }
+            },
+            "forecast": { "forecastday": [] },
+            "alerts": { "alert": [{
+                "headline": "Hurricane Warning issued", "msgtype": "Alert",
+                "severity": "Extreme", "event": "Hurricane Warning",
+                "effective": "2024-10-09T05:00:00-04:00", "expires": "2024-10-10T05:00:00-04:00",
+                "desc": "..."
+            }] }
+        }"#;
+        // One response only: the current weather must not need a second call.
+        let (url, requests) = serve(vec![json_ok(body)]).await;
+        let provider =
+            WeatherApiProvider::builder().api_key("secret").base_url(url).build().unwrap();
+        let request = WeatherRequest::builder().address("Miami").build().unwrap();
+
+        let report = provider.alerts(&request).await.unwrap();
+
+        assert!(requests.recv().unwrap().contains("alerts=yes"));
+        assert_eq!(report.current.location_name, "Miami, USA");
+        assert_eq!(report.current.precipitation.mm(), 12.0);
+        let alerts = &report.alerts;
+        assert_eq!(alerts.len(), 1);
+        assert_eq!(alerts[0].event, "Hurricane Warning");
+        assert_eq!(alerts[0].severity.as_deref(), Some("Extreme"));
+        assert_eq!(alerts[0].starts, DateTime::from_timestamp(1_728_464_400, 0));
+    }
+
+    #[test]
+    fn plan_uses_history_for_past_dates() {
+        let provider = WeatherApiProvider::new("secret".to_string());
+        let when = Utc::now() - chrono::Duration::days(2);
+        let request = WeatherRequest::builder().address("Lviv").at(when).build().unwrap();
+
+        let plan = provider.plan(&request).unwrap();
+        assert_eq!(plan.date, DateRequest::Past(when));
+        assert_eq!(plan.url, "http://api.weatherapi.com/v1/history.json");
+        assert_eq!(plan.query[0], ("key".to_string(), "********".to_string()));
+        assert!(plan.query.contains(&("unixdt".to_string(), when.timestamp().to_string())));
+    }
+
+    #[test]
+    fn coordinates_go_into_q() {
+        let provider = WeatherApiProvider::new("secret".to_string());
+        let request = WeatherRequest::builder().coordinates(-33.92, 18.42).build().unwrap();
+
+        let plan = provider.plan(&request).unwrap();
+        assert_eq!(plan.query[1], ("q".to_string(), "-33.92,18.42".to_string()));
+    }
 }
@@ -0,0 +1,185 @@
+//! Passphrase encryption of the API keys in `config.toml` (`encryption` feature).
+//!
+//! For machines without a keyring: [`Config::encrypt_providers`](crate::Config::encrypt_providers)
+//! stores the `providers` table as an age file (scrypt, ASCII-armored) inside the config
+//! file, and [`Config::unlock`](crate::Config::unlock) decrypts it again. Deriving the key
+//! takes about a second on purpose, so a [`PassphraseSession`] can keep the passphrase
+//! around for a while instead of asking for it on every command.
+
+use std::{
+    fs::{self, OpenOptions},
+    io::{ErrorKind, Read, Write},
+    iter,
+    path::PathBuf,
+    time::Duration,
+};
+
+use age::{
+    DecryptError, Decryptor, Encryptor,
+    armor::{ArmoredReader, ArmoredWriter, Format},
+    scrypt,
+    secrecy::ExposeSecret,
+};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+pub use age::secrecy::SecretString;
+
+use crate::{
+    config::project_dirs,
+    error::{Context, Result, WeatherError},
+};
+
+/// Encrypt `plaintext` with `passphrase` into an ASCII-armored age file.
+pub(crate) fn encrypt(plaintext: &str, passphrase: &SecretString) -> Result<String> {
+    let encryptor = Encryptor::with_user_passphrase(passphrase.clone());
+
+    let mut armored = Vec::new();
+    let output = ArmoredWriter::wrap_output(&mut armored, Format::AsciiArmor)
+        .and_then(|armor| {
+            let mut writer = encryptor.wrap_output(armor)?;
+            writer.write_all(plaintext.as_bytes())?;
+            writer.finish()
+        })
+        .and_then(|armor| armor.finish());
+    output.context("Failed to encrypt API keys")?;
+
+    String::from_utf8(armored).map_err(|err| WeatherError::Parse {
+        context: "Failed to encrypt API keys".to_string(),
+        source: err.into(),
+    })
+}
+
+/// Decrypt an armored age file made by [`encrypt`].
+pub(crate) fn decrypt(armored: &str, passphrase: &SecretString) -> Result<String> {
+    let invalid = |source: DecryptError| WeatherError::Parse {
+        context: "Failed to decrypt API keys".to_string(),
+        source: source.into(),
+    };
+
+    let decryptor = Decryptor::new(ArmoredReader::new(armored.as_bytes())).map_err(invalid)?;
+    let identity = scrypt::Identity::new(passphrase.clone());
+    let mut reader = match decryptor.decrypt(iter::once(&identity as _)) {
+        Ok(reader) => reader,
+        Err(DecryptError::DecryptionFailed | DecryptError::NoMatchingKeys) => {
+            return Err(WeatherError::WrongPassphrase);
+        }
+        Err(err) => return Err(invalid(err)),
+    };
+
+    let mut plaintext = String::new();
+    reader.read_to_string(&mut plaintext).context("Failed to decrypt API keys")?;
+    Ok(plaintext)
+}
+
+#[derive(Serialize, Deserialize)]
+struct Session {
+    passphrase: String,
+    expires_at: DateTime<Utc>,
+}
+
+/// A passphrase kept in a file until it expires.
+///
+/// The file holds the passphrase itself, readable only by its owner, so it only ever
+/// goes to the runtime directory, which is private to the user and cleared on logout.
+#[derive(Debug, Clone)]
+pub struct PassphraseSession {
+    path: PathBuf,
+}
+
+impl PassphraseSession {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into() }
+    }
+
+    /// `session.json` in the platform runtime directory. Fails with
+    /// [`WeatherError::NoSessionDir`] where there is none (e.g. macOS and Windows): a
+    /// persistent directory would keep the passphrase on disk.
+    pub fn default_path() -> Result<PathBuf> {
+        let dirs = project_dirs()?;
+        let dir = dirs.runtime_dir().ok_or(WeatherError::NoSessionDir)?;
+        Ok(dir.join("session.json"))
+    }
+
+    /// When a session of `ttl` started now would end. Fails for a `ttl` that reaches past
+    /// the last representable date, so it can be checked before asking for a passphrase.
+    pub fn expires_at(ttl: Duration) -> Result<DateTime<Utc>> {
+        chrono::Duration::from_std(ttl)
+            .ok()
+            .and_then(|ttl| Utc::now().checked_add_signed(ttl))
+            .ok_or_else(|| {
+                WeatherError::InvalidRequest("The session duration is too long.".to_string())
+            })
+    }
+
+    /// Keep `passphrase` for `ttl`, replacing any previous session.
+    pub fn start(&self, passphrase: &SecretString, ttl: Duration) -> Result<()> {
+        let expires_at = Self::expires_at(ttl)?;
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent).with_context(|| {
+                format!("Failed to create session directory: {}", parent.display())
+            })?;
+        }
+
+        let session = Session { passphrase: passphrase.expose_secret().to_string(), expires_at };
+        let json = serde_json::to_string(&session).context("Failed to serialize session")?;
+
+        // Remove first: `mode` only applies to newly created files.
+        self.end()?;
+        let mut options = OpenOptions::new();
+        options.write(true).create_new(true);
+        #[cfg(unix)]
+        std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+        options
+            .open(&self.path)
+            .and_then(|mut file| file.write_all(json.as_bytes()))
+            .with_context(|| format!("Failed to write session: {}", self.path.display()))
+    }
+
+    /// The passphrase, unless there is no session or it has expired.
+    pub fn passphrase(&self) -> Option<SecretString> {
+        let contents = fs::read_to_string(&self.path).ok()?;
+        let session: Session = serde_json::from_str(&contents).ok()?;
+
+        if session.expires_at <= Utc::now() {
+            let _ = self.end();
+            return None;
+        }
+        Some(session.passphrase.into())
+    }
+
+    /// Forget the passphrase. Returns whether there was a session.
+    pub fn end(&self) -> Result<bool> {
+        match fs::remove_file(&self.path) {
+            Ok(()) => Ok(true),
+            Err(err) if err.kind() == ErrorKind::NotFound => Ok(false),
+            Err(err) => Err(err)
+                .with_context(|| format!("Failed to remove session: {}", self.path.display())),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sessions_expire() {
+        let dir = tempfile::tempdir().unwrap();
+        let session = PassphraseSession::new(dir.path().join("session.json"));
+        let passphrase = SecretString::from("correct horse");
+
+        session.start(&passphrase, Duration::from_secs(60)).unwrap();
+        assert_eq!(session.passphrase().unwrap().expose_secret(), "correct horse");
+
+        session.start(&passphrase, Duration::ZERO).unwrap();
+        assert!(session.passphrase().is_none());
+        assert!(!session.end().unwrap());
+
+        let forever = Duration::from_secs(100_000_000_000 * 86_400);
+        assert!(matches!(
+            session.start(&passphrase, forever),
+            Err(WeatherError::InvalidRequest(_))
+        ));
+    }
+}
@@ -0,0 +1,83 @@
+//! Small JSON state files shared between processes, such as the API key rotation and
+//! rate limit state.
+
+use std::{
+    fs::{self, OpenOptions},
+    io::{Read, Seek, SeekFrom, Write},
+    path::Path,
+};
+
+use serde::{Serialize, de::DeserializeOwned};
+
+use crate::error::{Context, Result};
+
+/// Apply `change` to the state stored in `path` while holding an exclusive lock on
+/// the file, so that concurrent CLI runs do not overwrite each other's updates. A
+/// missing or unreadable file counts as the default state.
+pub(crate) fn update<T, R>(path: &Path, change: impl FnOnce(&mut T) -> R) -> Result<R>
+where
+    T: Default + Serialize + DeserializeOwned,
+{
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .with_context(|| format!("Failed to create state directory: {}", parent.display()))?;
+    }
+
+    let mut file = OpenOptions::new()
+        .read(true)
+        .write(true)
+        .create(true)
+        .truncate(false)
+        .open(path)
+        .with_context(|| format!("Failed to open state file: {}", path.display()))?;
+    // Released when `file` is dropped.
+    file.lock().with_context(|| format!("Failed to lock state file: {}", path.display()))?;
+
+    let mut contents = String::new();
+    file.read_to_string(&mut contents)
+        .with_context(|| format!("Failed to read state file: {}", path.display()))?;
+    let mut state = serde_json::from_str(&contents).unwrap_or_default();
+
+    let result = change(&mut state);
+
+    let json = serde_json::to_string(&state).context("Failed to serialize state")?;
+    file.set_len(0)
+        .and_then(|()| file.seek(SeekFrom::Start(0)))
+        .and_then(|_| file.write_all(json.as_bytes()))
+        .with_context(|| format!("Failed to write state file: {}", path.display()))?;
+
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::*;
+
+    #[test]
+    fn concurrent_updates_are_not_lost() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("state").join("counter.json");
+
+        let threads: Vec<_> = (0..8)
+            .map(|_| {
+                let path = path.clone();
+                std::thread::spawn(move || {
+                    for _ in 0..25 {
+                        update(&path, |counts: &mut HashMap<String, u32>| {
+                            *counts.entry("calls".to_string()).or_default() += 1;
+                        })
+                        .unwrap();
+                    }
+                })
+            })
+            .collect();
+        for thread in threads {
+            thread.join().unwrap();
+        }
+
+        let total = update(&path, |counts: &mut HashMap<String, u32>| counts["calls"]).unwrap();
+        assert_eq!(total, 200);
+    }
+}
@@ -0,0 +1,223 @@
+//! SQLite log of observations over time (`sqlite` feature).
+//!
+//! Backs `weather record` and `weather stats`: responses are stored one row each,
+//! keyed by the requested location, and summarized (min/max/avg) over a time window.
+//! An observation already stored (same location, provider and observation time, e.g. a
+//! cached response) is not stored again, so it cannot skew the averages.
+
+use std::{fs, path::Path};
+
+use chrono::{DateTime, Utc};
+use rusqlite::{Connection, OptionalExtension, params};
+
+use crate::{
+    Location, Pressure, Temperature, WeatherResponse, WindSpeed,
+    config::project_dirs,
+    error::{Context, Result},
+    geocode::normalize_address,
+};
+
+const SCHEMA: &str = "
+    CREATE TABLE IF NOT EXISTS observations (
+        location TEXT NOT NULL,
+        provider TEXT NOT NULL,
+        location_name TEXT NOT NULL,
+        observed_at INTEGER NOT NULL,
+        temperature_c REAL NOT NULL,
+        feels_like_c REAL NOT NULL,
+        condition TEXT NOT NULL,
+        humidity_pct INTEGER NOT NULL,
+        wind_speed_mps REAL NOT NULL,
+        pressure_hpa REAL NOT NULL,
+        precipitation_mm REAL NOT NULL,
+        UNIQUE (location, provider, observed_at)
+    );
+    CREATE INDEX IF NOT EXISTS observations_by_time ON observations (location, observed_at);
+";
+
+/// Lowest, highest and mean value of a quantity.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Summary<T> {
+    pub min: T,
+    pub max: T,
+    pub avg: T,
+}
+
+/// Summary of the observations of one location within a time window.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ObservationStats {
+    pub count: u64,
+    pub first: DateTime<Utc>,
+    pub last: DateTime<Utc>,
+    pub temperature: Summary<Temperature>,
+    pub feels_like: Summary<Temperature>,
+    pub humidity_pct: Summary<f64>,
+    pub wind_speed: Summary<WindSpeed>,
+    pub pressure: Summary<Pressure>,
+}
+
+/// Observations stored in a SQLite database file.
+#[derive(Debug)]
+pub struct ObservationStore {
+    conn: Connection,
+}
+
+impl ObservationStore {
+    /// Open the database at `path`, creating it (and its directory) if needed.
+    pub fn open(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).with_context(|| {
+                format!("Failed to create data directory: {}", parent.display())
+            })?;
+        }
+
+        let conn = Connection::open(path)
+            .with_context(|| format!("Failed to open observation database: {}", path.display()))?;
+        conn.execute_batch(SCHEMA).with_context(|| {
+            format!("Failed to set up observation database: {}", path.display())
+        })?;
+
+        Ok(Self { conn })
+    }
+
+    /// Open `observations.sqlite` in the platform data directory.
+    pub fn open_default() -> Result<Self> {
+        Self::open(project_dirs()?.data_dir().join("observations.sqlite"))
+    }
+
+    /// Store `response` as an observation of `location`. Returns `false` if it was
+    /// already stored.
+    pub fn insert(&self, location: &Location, response: &WeatherResponse) -> Result<bool> {
+        let inserted = self
+            .conn
+            .execute(
+                "INSERT OR IGNORE INTO observations (
+                    location, provider, location_name, observed_at, temperature_c,
+                    feels_like_c, condition, humidity_pct, wind_speed_mps, pressure_hpa,
+                    precipitation_mm
+                ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)",
+                params![
+                    key(location),
+                    response.provider,
+                    response.location_name,
+                    response.observation_time.timestamp(),
+                    response.temperature.celsius(),
+                    response.feels_like.celsius(),
+                    response.condition,
+                    response.humidity_pct,
+                    response.wind_speed.mps(),
+                    response.pressure.hpa(),
+                    response.precipitation.mm(),
+                ],
+            )
+            .context("Failed to store observation")?;
+
+        Ok(inserted > 0)
+    }
+
+    /// Summary of the observations of `location` made at or after `since`, or `None` if
+    /// there are none.
+    pub fn stats(
+        &self,
+        location: &Location,
+        since: DateTime<Utc>,
+    ) -> Result<Option<ObservationStats>> {
+        let row = self
+            .conn
+            .query_row(
+                "SELECT COUNT(*), MIN(observed_at), MAX(observed_at),
+                    MIN(temperature_c), MAX(temperature_c), AVG(temperature_c),
+                    MIN(feels_like_c), MAX(feels_like_c), AVG(feels_like_c),
+                    MIN(humidity_pct), MAX(humidity_pct), AVG(humidity_pct),
+                    MIN(wind_speed_mps), MAX(wind_speed_mps), AVG(wind_speed_mps),
+                    MIN(pressure_hpa), MAX(pressure_hpa), AVG(pressure_hpa)
+                FROM observations
+                WHERE location = ?1 AND observed_at >= ?2
+                HAVING COUNT(*) > 0",
+                params![key(location), since.timestamp()],
+                |row| {
+                    let summary = |i: usize| -> rusqlite::Result<Summary<f64>> {
+                        Ok(Summary { min: row.get(i)?, max: row.get(i + 1)?, avg: row.get(i + 2)? })
+                    };
+                    let time = |i: usize| -> rusqlite::Result<DateTime<Utc>> {
+                        Ok(DateTime::from_timestamp(row.get(i)?, 0).unwrap_or_default())
+                    };
+
+                    Ok(ObservationStats {
+                        count: row.get(0)?,
+                        first: time(1)?,
+                        last: time(2)?,
+                        temperature: summary(3)?.map(Temperature::from_celsius),
+                        feels_like: summary(6)?.map(Temperature::from_celsius),
+                        humidity_pct: summary(9)?,
+                        wind_speed: summary(12)?.map(WindSpeed::from_mps),
+                        pressure: summary(15)?.map(Pressure::from_hpa),
+                    })
+                },
+            )
+            .optional()
+            .context("Failed to read observations")?;
+
+        Ok(row)
+    }
+}
+
+impl<T> Summary<T> {
+    /// Convert every value, e.g. into another unit.
+    pub fn map<U>(self, f: impl Fn(T) -> U) -> Summary<U> {
+        Summary { min: f(self.min), max: f(self.max), avg: f(self.avg) }
+    }
+}
+
+/// Rows are keyed by the requested location, so "Kyiv" and " kyiv" are the same one.
+fn key(location: &Location) -> String {
+    normalize_address(&location.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Duration;
+
+    use super::*;
+    use crate::provider::mock;
+
+    fn observation(celsius: f64, observed_at: DateTime<Utc>) -> WeatherResponse {
+        WeatherResponse {
+            temperature: Temperature::from_celsius(celsius),
+            observation_time: observed_at,
+            ..mock::response("Kyiv")
+        }
+    }
+
+    #[test]
+    fn stats_summarize_the_window() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = ObservationStore::open(dir.path().join("observations.sqlite")).unwrap();
+        let kyiv = Location::from("Kyiv");
+        let now = DateTime::from_timestamp(1_760_000_000, 0).unwrap();
+
+        assert!(store.insert(&kyiv, &observation(30.0, now - Duration::days(10))).unwrap());
+        assert!(store.insert(&kyiv, &observation(4.0, now - Duration::hours(2))).unwrap());
+        assert!(store.insert(&kyiv, &observation(8.0, now)).unwrap());
+        // The same observation again, e.g. from the cache.
+        assert!(!store.insert(&kyiv, &observation(8.0, now)).unwrap());
+        store.insert(&Location::from("Lviv"), &observation(-5.0, now)).unwrap();
+
+        let stats = store.stats(&Location::from(" kyiv "), now - Duration::days(7)).unwrap();
+        let stats = stats.unwrap();
+        assert_eq!(stats.count, 2);
+        assert_eq!((stats.first, stats.last), (now - Duration::hours(2), now));
+        assert_eq!(
+            stats.temperature,
+            Summary {
+                min: Temperature::from_celsius(4.0),
+                max: Temperature::from_celsius(8.0),
+                avg: Temperature::from_celsius(6.0),
+            }
+        );
+        assert_eq!(stats.humidity_pct.avg, 65.0);
+
+        assert_eq!(store.stats(&kyiv, now + Duration::hours(1)).unwrap(), None);
+    }
+}
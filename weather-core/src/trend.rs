@@ -0,0 +1,197 @@
+//! Trends in a forecast series, as shown by `weather forecast`.
+//!
+//! Everything here reads the points of a [`WeatherSeries`](crate::WeatherSeries), such as
+//! [`WeatherProvider::get_forecast_series`](crate::WeatherProvider::get_forecast_series)
+//! returns: how fast it warms or cools, which way the air pressure is heading (a fast fall
+//! often comes before a storm) and when precipitation is expected.
+
+use chrono::{DateTime, Duration, Utc};
+
+use crate::{Precipitation, WeatherPoint};
+
+/// Steps with less precipitation than this count as dry.
+const WET_MM: f64 = 0.1;
+
+/// Pressure changes per 3 hours (hPa) below which pressure is steady, and beyond which
+/// it is falling fast.
+const STEADY_HPA: f64 = 0.5;
+const FALLING_FAST_HPA: f64 = 1.6;
+
+/// Which way the air pressure is heading over the next day.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PressureTendency {
+    Rising,
+    Steady,
+    Falling,
+    /// Falling by more than 1.6 hPa per 3 hours: a storm may be coming.
+    FallingFast,
+}
+
+/// Consecutive forecast steps with precipitation.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PrecipitationSpell {
+    pub start: DateTime<Utc>,
+    /// End of the last wet step.
+    pub end: DateTime<Utc>,
+    pub total: Precipitation,
+}
+
+/// Everything this module can tell about a series.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Trend {
+    /// See [`warming_per_day`].
+    pub warming_per_day: Option<f64>,
+    pub pressure: Option<PressureTendency>,
+    pub precipitation: Vec<PrecipitationSpell>,
+}
+
+impl Trend {
+    pub fn of(series: &[WeatherPoint]) -> Self {
+        Self {
+            warming_per_day: warming_per_day(series),
+            pressure: pressure_tendency(series),
+            precipitation: precipitation_spells(series),
+        }
+    }
+}
+
+/// How many degrees Celsius it warms (or, if negative, cools) per day over the series,
+/// fitted to all steps so that the daily cycle evens out. `None` for fewer than two
+/// steps.
+pub fn warming_per_day(series: &[WeatherPoint]) -> Option<f64> {
+    let start = series.first()?.time;
+    let days = |time: DateTime<Utc>| (time - start).num_seconds() as f64 / 86_400.0;
+
+    slope(series.iter().map(|step| (days(step.time), step.temperature.celsius())))
+}
+
+/// Which way the pressure is heading over the first day of the series. `None` for fewer
+/// than two steps.
+pub fn pressure_tendency(series: &[WeatherPoint]) -> Option<PressureTendency> {
+    let start = series.first()?.time;
+    let next_day = series.iter().take_while(|step| step.time - start <= Duration::days(1));
+    let hours = |time: DateTime<Utc>| (time - start).num_seconds() as f64 / 3_600.0;
+
+    let per_3h = 3.0 * slope(next_day.map(|step| (hours(step.time), step.pressure.hpa())))?;
+
+    Some(match per_3h {
+        change if change <= -FALLING_FAST_HPA => PressureTendency::FallingFast,
+        change if change <= -STEADY_HPA => PressureTendency::Falling,
+        change if change >= STEADY_HPA => PressureTendency::Rising,
+        _ => PressureTendency::Steady,
+    })
+}
+
+/// The stretches of the series with precipitation, in order.
+pub fn precipitation_spells(series: &[WeatherPoint]) -> Vec<PrecipitationSpell> {
+    let mut spells: Vec<PrecipitationSpell> = Vec::new();
+    let mut in_spell = false;
+
+    for (i, step) in series.iter().enumerate() {
+        if step.precipitation.mm() < WET_MM {
+            in_spell = false;
+            continue;
+        }
+
+        // A step lasts until the next one; the last one as long as the one before it.
+        let length = match (series.get(i + 1), i.checked_sub(1).map(|prev| &series[prev])) {
+            (Some(next), _) => next.time - step.time,
+            (None, Some(prev)) => step.time - prev.time,
+            (None, None) => Duration::zero(),
+        };
+        let end = step.time + length;
+
+        match spells.last_mut() {
+            Some(spell) if in_spell => {
+                spell.end = end;
+                spell.total = Precipitation::from_mm(spell.total.mm() + step.precipitation.mm());
+            }
+            _ => {
+                spells.push(PrecipitationSpell { start: step.time, end, total: step.precipitation })
+            }
+        }
+        in_spell = true;
+    }
+
+    spells
+}
+
+/// Least-squares slope of `points`, or `None` if they do not spread along x.
+fn slope(points: impl Iterator<Item = (f64, f64)>) -> Option<f64> {
+    let points: Vec<_> = points.collect();
+    let n = points.len() as f64;
+    let mean_x = points.iter().map(|(x, _)| x).sum::<f64>() / n;
+    let mean_y = points.iter().map(|(_, y)| y).sum::<f64>() / n;
+
+    let spread: f64 = points.iter().map(|(x, _)| (x - mean_x).powi(2)).sum();
+    if points.len() < 2 || spread == 0.0 {
+        return None;
+    }
+
+    let covariance: f64 = points.iter().map(|(x, y)| (x - mean_x) * (y - mean_y)).sum();
+    Some(covariance / spread)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Pressure, Temperature, provider::mock};
+
+    /// Three-hourly steps from `start`, with the given values per step.
+    fn series(steps: &[(f64, f64, f64)]) -> Vec<WeatherPoint> {
+        let start = DateTime::from_timestamp(1_760_000_400, 0).unwrap();
+        steps
+            .iter()
+            .enumerate()
+            .map(|(i, &(celsius, hpa, mm))| WeatherPoint {
+                temperature: Temperature::from_celsius(celsius),
+                pressure: Pressure::from_hpa(hpa),
+                precipitation: Precipitation::from_mm(mm),
+                time: start + Duration::hours(3 * i as i64),
+                ..mock::response("Kyiv").into()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn warming_is_measured_per_day() {
+        // +0.25 °C every 3 hours is +2 °C a day.
+        let steps: Vec<_> = (0..16).map(|i| (10.0 + 0.25 * i as f64, 1015.0, 0.0)).collect();
+        let warming = warming_per_day(&series(&steps)).unwrap();
+        assert!((warming - 2.0).abs() < 1e-9, "{warming}");
+
+        assert_eq!(warming_per_day(&series(&steps[..1])), None);
+    }
+
+    #[test]
+    fn fast_pressure_falls_are_told_apart() {
+        let tendency = |per_step: f64| {
+            let steps: Vec<_> = (0..8).map(|i| (10.0, 1015.0 + per_step * i as f64, 0.0)).collect();
+            pressure_tendency(&series(&steps)).unwrap()
+        };
+
+        assert_eq!(tendency(-2.0), PressureTendency::FallingFast);
+        assert_eq!(tendency(-1.0), PressureTendency::Falling);
+        assert_eq!(tendency(0.2), PressureTendency::Steady);
+        assert_eq!(tendency(1.0), PressureTendency::Rising);
+    }
+
+    #[test]
+    fn wet_steps_form_spells() {
+        let steps = series(&[
+            (10.0, 1015.0, 0.0),
+            (10.0, 1015.0, 1.5),
+            (10.0, 1015.0, 0.5),
+            (10.0, 1015.0, 0.0),
+            (10.0, 1015.0, 2.0),
+        ]);
+
+        let spells = precipitation_spells(&steps);
+        assert_eq!(spells.len(), 2);
+        assert_eq!(spells[0].start, steps[1].time);
+        assert_eq!(spells[0].end, steps[3].time);
+        assert_eq!(spells[0].total, Precipitation::from_mm(2.0));
+        // The last step lasts as long as the one before it.
+        assert_eq!(spells[1].end, steps[4].time + Duration::hours(3));
+    }
+}
@@ -0,0 +1,425 @@
+//! Local log of outbound provider calls, for quota tracking.
+//!
+//! Every HTTP attempt made on behalf of a provider is appended as one JSON line
+//! (timestamp, provider, endpoint path, status) to a file in the platform data
+//! directory. Query strings are never recorded since they carry API keys; a provider
+//! with several keys tags each call with the masked key instead. Days and
+//! months are counted in UTC, which is what providers reset their quotas on. Once a
+//! quota is nearly used up, every further call logs a warning; for that, the calls of
+//! the current day and month are also counted in a small state file next to the log,
+//! so that no call has to read the whole log.
+
+use std::{collections::HashMap, fs, path::PathBuf};
+
+use chrono::{DateTime, Datelike, NaiveDate, NaiveTime, TimeZone, Utc};
+use serde::{Deserialize, Serialize};
+
+#[cfg(not(target_arch = "wasm32"))]
+use crate::config::project_dirs;
+use crate::{
+    Config,
+    config::Quota,
+    error::{Context, Result},
+    jsonl,
+    provider::ProviderId,
+    state,
+};
+
+/// Share of a quota after which [`ProviderUsage`] reports it as nearly used up.
+const NEAR_CAP_PERCENT: u128 = 80;
+
+/// One outbound provider call.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct UsageRecord {
+    pub at: DateTime<Utc>,
+    pub provider: String,
+    /// Masked API key, when the provider has several.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub key: Option<String>,
+    /// URL path, e.g. `/data/2.5/weather`.
+    pub endpoint: String,
+    /// HTTP status, or `None` if no response arrived.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub status: Option<u16>,
+}
+
+/// Calls made to one provider (or one of its keys) today and this month, against its
+/// quota.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProviderUsage {
+    pub provider: ProviderId,
+    /// The masked key, for a provider whose calls were made with several.
+    pub key: Option<String>,
+    pub today: u64,
+    pub this_month: u64,
+    pub quota: Quota,
+}
+
+impl ProviderUsage {
+    pub fn near_daily_cap(&self) -> bool {
+        near_cap(self.today, self.quota.daily)
+    }
+
+    pub fn near_monthly_cap(&self) -> bool {
+        near_cap(self.this_month, self.quota.monthly)
+    }
+}
+
+/// Calls of one provider (or one of its keys) on the latest day seen and in its month.
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct Tally {
+    day: Option<NaiveDate>,
+    today: u64,
+    this_month: u64,
+}
+
+impl Tally {
+    fn add(&mut self, day: NaiveDate) {
+        match self.day {
+            // A call recorded late only counts towards its month.
+            Some(latest) if day < latest => {
+                if same_month(day, latest) {
+                    self.this_month += 1;
+                }
+                return;
+            }
+            Some(latest) if day == latest => {}
+            Some(latest) if same_month(day, latest) => self.today = 0,
+            _ => (self.today, self.this_month) = (0, 0),
+        }
+        self.day = Some(day);
+        self.today += 1;
+        self.this_month += 1;
+    }
+
+    /// Calls on `day` and in its month.
+    fn on(&self, day: NaiveDate) -> (u64, u64) {
+        match self.day {
+            Some(latest) if latest == day => (self.today, self.this_month),
+            Some(latest) if latest < day && same_month(day, latest) => (0, self.this_month),
+            _ => (0, 0),
+        }
+    }
+}
+
+/// Append-only JSON-lines file of [`UsageRecord`]s.
+#[derive(Debug, Clone)]
+pub struct UsageLog {
+    path: PathBuf,
+    key: Option<String>,
+    quota: Option<Quota>,
+}
+
+impl UsageLog {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into(), key: None, quota: None }
+    }
+
+    /// Tag the calls made with one of several API keys, so that each key is counted
+    /// against its own quota. `key` is only an identity, so pass it masked.
+    pub fn for_key(mut self, key: impl Into<String>) -> Self {
+        self.key = Some(key.into());
+        self
+    }
+
+    /// The key records are tagged with, see [`for_key`](Self::for_key).
+    pub fn key(&self) -> Option<&str> {
+        self.key.as_deref()
+    }
+
+    /// The quota calls are counted against, so that recording one can warn when it is
+    /// nearly used up.
+    pub fn with_quota(mut self, quota: Quota) -> Self {
+        self.quota = Some(quota);
+        self
+    }
+
+    /// The quota set with [`with_quota`](Self::with_quota).
+    pub fn quota(&self) -> Option<Quota> {
+        self.quota
+    }
+
+    /// `usage.jsonl` in the platform data directory.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn default_path() -> Result<PathBuf> {
+        Ok(project_dirs()?.data_dir().join("usage.jsonl"))
+    }
+
+    pub fn record(&self, record: &UsageRecord) -> Result<()> {
+        jsonl::append(&self.path, record, "usage log")?;
+
+        let bucket = bucket(&record.provider, record.key.as_deref());
+        state::update(&self.tallies_path(), |tallies: &mut HashMap<String, Tally>| {
+            tallies.entry(bucket).or_default().add(record.at.date_naive());
+        })
+    }
+
+    /// All readable records; a missing log is empty and garbled lines are skipped.
+    pub fn records(&self) -> Result<Vec<UsageRecord>> {
+        jsonl::read(&self.path, "usage log")
+    }
+
+    /// Usage of every provider for the UTC day and month containing `now`, one entry
+    /// per key for a provider whose calls were tagged with several.
+    pub fn report(&self, config: &Config, now: DateTime<Utc>) -> Result<Vec<ProviderUsage>> {
+        let records = self.records()?;
+
+        let mut report = Vec::new();
+        for &provider in ProviderId::all() {
+            let calls = this_month(&records, provider, now);
+
+            let mut keys: Vec<_> = calls.iter().map(|r| r.key.clone()).collect();
+            keys.sort();
+            keys.dedup();
+            if keys.is_empty() {
+                keys.push(None);
+            }
+
+            for key in keys {
+                let calls: Vec<_> = calls.iter().copied().filter(|r| r.key == key).collect();
+                report.push(tally(&calls, provider, key, config.quota(provider), now));
+            }
+        }
+
+        Ok(report)
+    }
+
+    /// Usage of `provider` with this log's key for the UTC day and month containing
+    /// `now`, against the quota set with [`with_quota`](Self::with_quota).
+    pub fn usage(&self, provider: ProviderId, now: DateTime<Utc>) -> Result<ProviderUsage> {
+        let bucket = bucket(provider.as_str(), self.key.as_deref());
+        let (today, this_month) =
+            state::update(&self.tallies_path(), |tallies: &mut HashMap<String, Tally>| {
+                tallies.get(&bucket).map(|tally| tally.on(now.date_naive())).unwrap_or_default()
+            })?;
+
+        Ok(ProviderUsage {
+            provider,
+            key: self.key.clone(),
+            today,
+            this_month,
+            quota: self.quota.unwrap_or_default(),
+        })
+    }
+
+    /// The day and month counts kept alongside the log, e.g. `usage.counts.json`.
+    fn tallies_path(&self) -> PathBuf {
+        self.path.with_extension("counts.json")
+    }
+
+    /// Drop records from before the month containing `now`. Returns how many were removed.
+    pub fn prune(&self, now: DateTime<Utc>) -> Result<usize> {
+        let records = self.records()?;
+        let cutoff = month_start(now);
+        let kept: Vec<_> = records.iter().filter(|r| r.at >= cutoff).collect();
+
+        let removed = records.len() - kept.len();
+        if removed == 0 {
+            return Ok(0);
+        }
+
+        let mut contents = String::new();
+        for record in kept {
+            contents.push_str(&serde_json::to_string(record).context("Failed to serialize usage")?);
+            contents.push('\n');
+        }
+
+        fs::write(&self.path, contents)
+            .with_context(|| format!("Failed to write usage log: {}", self.path.display()))?;
+
+        Ok(removed)
+    }
+}
+
+/// Calls to `provider` in the UTC month containing `now`, up to `now`.
+fn this_month(
+    records: &[UsageRecord],
+    provider: ProviderId,
+    now: DateTime<Utc>,
+) -> Vec<&UsageRecord> {
+    let month_start = month_start(now);
+    records
+        .iter()
+        .filter(|r| r.provider == provider.as_str() && r.at <= now && r.at >= month_start)
+        .collect()
+}
+
+/// Usage made up of `calls`, all from the month containing `now`.
+fn tally(
+    calls: &[&UsageRecord],
+    provider: ProviderId,
+    key: Option<String>,
+    quota: Quota,
+    now: DateTime<Utc>,
+) -> ProviderUsage {
+    let day_start = Utc.from_utc_datetime(&now.date_naive().and_time(NaiveTime::MIN));
+    ProviderUsage {
+        provider,
+        key,
+        today: calls.iter().filter(|r| r.at >= day_start).count() as u64,
+        this_month: calls.len() as u64,
+        quota,
+    }
+}
+
+fn month_start(now: DateTime<Utc>) -> DateTime<Utc> {
+    let first = now.date_naive().with_day(1).unwrap_or(now.date_naive());
+    Utc.from_utc_datetime(&first.and_time(NaiveTime::MIN))
+}
+
+fn near_cap(used: u64, limit: Option<u64>) -> bool {
+    // In u128, which no quota overflows.
+    limit.is_some_and(|limit| u128::from(used) * 100 >= u128::from(limit) * NEAR_CAP_PERCENT)
+}
+
+fn same_month(a: NaiveDate, b: NaiveDate) -> bool {
+    (a.year(), a.month()) == (b.year(), b.month())
+}
+
+/// Entry in the counts file: the provider, and the key if there are several.
+fn bucket(provider: &str, key: Option<&str>) -> String {
+    match key {
+        Some(key) => format!("{provider} {key}"),
+        None => provider.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::*;
+
+    fn record(at: DateTime<Utc>, provider: ProviderId) -> UsageRecord {
+        UsageRecord {
+            at,
+            provider: provider.as_str().to_string(),
+            key: None,
+            endpoint: "/current.json".to_string(),
+            status: Some(200),
+        }
+    }
+
+    #[test]
+    fn report_counts_today_and_this_month() {
+        let dir = tempfile::tempdir().unwrap();
+        let log = UsageLog::new(dir.path().join("usage.jsonl"));
+        let now = Utc.with_ymd_and_hms(2025, 3, 15, 12, 0, 0).unwrap();
+
+        log.record(&record(now, ProviderId::WeatherApi)).unwrap();
+        log.record(&record(now - chrono::Duration::hours(1), ProviderId::WeatherApi)).unwrap();
+        log.record(&record(now - chrono::Duration::days(3), ProviderId::WeatherApi)).unwrap();
+        log.record(&record(now - chrono::Duration::days(30), ProviderId::WeatherApi)).unwrap();
+        log.record(&record(now, ProviderId::OpenWeather)).unwrap();
+
+        let cfg = Config {
+            quotas: HashMap::from([(
+                "weatherapi".to_string(),
+                Quota { daily: Some(2), monthly: Some(100) },
+            )]),
+            ..Config::default()
+        };
+        let report = log.report(&cfg, now).unwrap();
+
+        let weatherapi = report.iter().find(|u| u.provider == ProviderId::WeatherApi).unwrap();
+        assert_eq!((weatherapi.today, weatherapi.this_month), (2, 3));
+        assert!(weatherapi.near_daily_cap());
+        assert!(!weatherapi.near_monthly_cap());
+
+        let openweather = report.iter().find(|u| u.provider == ProviderId::OpenWeather).unwrap();
+        assert_eq!((openweather.today, openweather.this_month), (1, 1));
+    }
+
+    #[test]
+    fn report_counts_every_key_separately() {
+        let dir = tempfile::tempdir().unwrap();
+        let log = UsageLog::new(dir.path().join("usage.jsonl"));
+        let now = Utc.with_ymd_and_hms(2025, 3, 15, 12, 0, 0).unwrap();
+
+        let tagged = |key: &str| UsageRecord {
+            key: Some(key.to_string()),
+            ..record(now, ProviderId::WeatherApi)
+        };
+        log.record(&tagged("********aaaa")).unwrap();
+        log.record(&tagged("********bbbb")).unwrap();
+        log.record(&tagged("********bbbb")).unwrap();
+
+        let report = log.report(&Config::default(), now).unwrap();
+        let counts: Vec<_> = report
+            .iter()
+            .filter(|u| u.provider == ProviderId::WeatherApi)
+            .map(|u| (u.key.as_deref(), u.this_month))
+            .collect();
+        assert_eq!(counts, [(Some("********aaaa"), 1), (Some("********bbbb"), 2)]);
+    }
+
+    #[test]
+    fn usage_counts_this_logs_key_against_its_quota() {
+        let dir = tempfile::tempdir().unwrap();
+        let now = Utc.with_ymd_and_hms(2025, 3, 15, 12, 0, 0).unwrap();
+        let log = UsageLog::new(dir.path().join("usage.jsonl"))
+            .for_key("********bbbb")
+            .with_quota(Quota { daily: Some(2), monthly: None });
+        let tagged = UsageRecord {
+            key: Some("********bbbb".to_string()),
+            ..record(now, ProviderId::WeatherApi)
+        };
+
+        log.record(&tagged).unwrap();
+        log.record(&record(now, ProviderId::WeatherApi)).unwrap();
+        let used = log.usage(ProviderId::WeatherApi, now).unwrap();
+        assert_eq!((used.today, used.this_month), (1, 1));
+        assert!(!used.near_daily_cap());
+
+        log.record(&tagged).unwrap();
+        assert!(log.usage(ProviderId::WeatherApi, now).unwrap().near_daily_cap());
+    }
+
+    #[test]
+    fn prune_drops_previous_months() {
+        let dir = tempfile::tempdir().unwrap();
+        let log = UsageLog::new(dir.path().join("usage.jsonl"));
+        let now = Utc.with_ymd_and_hms(2025, 3, 1, 0, 30, 0).unwrap();
+
+        log.record(&record(now - chrono::Duration::hours(1), ProviderId::OpenWeather)).unwrap();
+        log.record(&record(now, ProviderId::OpenWeather)).unwrap();
+
+        assert_eq!(log.prune(now).unwrap(), 1);
+        assert_eq!(log.records().unwrap(), vec![record(now, ProviderId::OpenWeather)]);
+        assert_eq!(log.prune(now).unwrap(), 0);
+    }
+
+    #[test]
+    fn missing_log_is_empty() {
+        let dir = tempfile::tempdir().unwrap();
+        let log = UsageLog::new(dir.path().join("nope").join("usage.jsonl"));
+
+        assert!(log.records().unwrap().is_empty());
+    }
+
+    #[test]
+    fn tallies_start_over_each_day_and_month() {
+        let day = |d: u32, m: u32| NaiveDate::from_ymd_opt(2025, m, d).unwrap();
+        let mut tally = Tally::default();
+
+        tally.add(day(14, 3));
+        tally.add(day(15, 3));
+        tally.add(day(15, 3));
+        assert_eq!(tally.on(day(15, 3)), (2, 3));
+        assert_eq!(tally.on(day(16, 3)), (0, 3));
+
+        // Late, from an earlier day of the month.
+        tally.add(day(14, 3));
+        assert_eq!(tally.on(day(15, 3)), (2, 4));
+
+        tally.add(day(1, 4));
+        assert_eq!(tally.on(day(1, 4)), (1, 1));
+        assert_eq!(tally.on(day(1, 5)), (0, 0));
+    }
+
+    #[test]
+    fn huge_quotas_are_never_near() {
+        assert!(!near_cap(5, Some(1_000_000_000_000_000_000)));
+        assert!(near_cap(u64::MAX, Some(u64::MAX)));
+    }
+}
@@ -0,0 +1,103 @@
+//! Provider JSON mapping against recorded payloads (`cassette` feature).
+//!
+//! Cassettes live in `tests/cassettes/`. To refresh one, point a provider at the real
+//! API with `Cassette::record` (or run the CLI with `WEATHER_CASSETTE=record:<file>`)
+//! and switch the recorded base URL to `http://cassette.test`.
+
+#![cfg(feature = "cassette")]
+
+use chrono::{TimeZone, Utc};
+use weather_core::{
+    ProviderId, WeatherError, WeatherProvider, WeatherRequest,
+    provider::{
+        cassette::Cassette, openweather::OpenWeatherProvider, weatherapi::WeatherApiProvider,
+    },
+};
+
+fn cassette(name: &str) -> Cassette {
+    let path = format!("{}/tests/cassettes/{name}.json", env!("CARGO_MANIFEST_DIR"));
+    Cassette::replay(path).unwrap()
+}
+
+fn request(address: &str) -> WeatherRequest {
+    WeatherRequest::builder().address(address).build().unwrap()
+}
+
+#[tokio::test]
+async fn openweather_current_is_mapped() {
+    let provider = OpenWeatherProvider::builder()
+        .api_key("test")
+        .base_url("http://cassette.test")
+        .cassette(cassette("openweather"))
+        .build()
+        .unwrap();
+
+    let res = provider.get_weather(&request("Kyiv")).await.unwrap();
+
+    assert_eq!(res.provider, "openweather");
+    assert_eq!(res.location_name, "Kyiv");
+    assert_eq!(res.condition, "light rain");
+    assert_eq!(res.temperature.celsius(), 3.42);
+    assert_eq!(res.feels_like.celsius(), -0.35);
+    assert_eq!(res.humidity_pct, 87);
+    assert_eq!(res.wind_speed.mps(), 4.12);
+    assert_eq!(res.pressure.hpa(), 1008.0);
+    assert_eq!(res.precipitation.mm(), 0.42);
+    assert_eq!(res.observation_time, Utc.with_ymd_and_hms(2024, 12, 4, 12, 0, 0).unwrap());
+}
+
+#[tokio::test]
+async fn openweather_unknown_city_is_not_found() {
+    let provider = OpenWeatherProvider::builder()
+        .api_key("test")
+        .base_url("http://cassette.test")
+        .cassette(cassette("openweather"))
+        .build()
+        .unwrap();
+
+    let err = provider.get_weather(&request("Atlantis")).await.unwrap_err();
+
+    assert!(matches!(
+        err,
+        WeatherError::LocationNotFound { provider: ProviderId::OpenWeather, ref address }
+            if address == "Atlantis"
+    ));
+}
+
+#[tokio::test]
+async fn weatherapi_current_is_mapped() {
+    let provider = WeatherApiProvider::builder()
+        .api_key("test")
+        .base_url("http://cassette.test/v1")
+        .cassette(cassette("weatherapi"))
+        .build()
+        .unwrap();
+
+    let res = provider.get_weather(&request("Lviv")).await.unwrap();
+
+    assert_eq!(res.location_name, "Lviv, Ukraine");
+    assert_eq!(res.condition, "Partly cloudy");
+    assert_eq!(res.temperature.celsius(), 2.1);
+    assert_eq!(res.humidity_pct, 80);
+    assert_eq!(res.wind_speed.kmh(), 15.1);
+    assert_eq!(res.observation_time.timestamp(), 1733313300);
+}
+
+#[tokio::test]
+async fn weatherapi_history_picks_the_closest_hour() {
+    let provider = WeatherApiProvider::builder()
+        .api_key("test")
+        .base_url("http://cassette.test/v1")
+        .cassette(cassette("weatherapi"))
+        .build()
+        .unwrap();
+
+    let when = Utc.with_ymd_and_hms(2024, 12, 4, 10, 0, 0).unwrap();
+    let request = WeatherRequest::builder().address("Lviv").at(when).build().unwrap();
+    let res = provider.get_weather(&request).await.unwrap();
+
+    assert_eq!(res.observation_time, when);
+    assert_eq!(res.condition, "Light snow");
+    assert_eq!(res.temperature.celsius(), 2.0);
+    assert_eq!(res.precipitation.mm(), 0.3);
+}